@@ -0,0 +1,71 @@
+#ifndef CHRONO_SLOTS_H
+#define CHRONO_SLOTS_H
+
+/* This file is generated by cbindgen. Do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Version of the JSON shape of FindReport. It is raised on any change that breaks readers:
+ * a field removed, renamed, or changed in type or meaning. Fields and warnings may be added
+ * without raising it, so readers should ignore unknown fields.
+ */
+#define REPORT_SCHEMA_VERSION 1
+
+/**
+ * Result code of the C functions.
+ */
+typedef enum ChronoSlotsStatus {
+  CHRONO_SLOTS_STATUS_OK = 0,
+  CHRONO_SLOTS_STATUS_NULL_POINTER = 1,
+  CHRONO_SLOTS_STATUS_INVALID_TIME = 2,
+  CHRONO_SLOTS_STATUS_OUT_OF_RANGE = 3,
+  CHRONO_SLOTS_STATUS_INVALID_ARGUMENT = 4,
+} ChronoSlotsStatus;
+
+/**
+ * A period expressed in Unix epoch seconds.
+ */
+typedef struct ChronoSlotsPeriod {
+  int64_t start;
+  int64_t end;
+} ChronoSlotsPeriod;
+
+/**
+ * Slots allocated by the library. Release it with `chrono_slots_free`.
+ */
+typedef struct ChronoSlotsSlotArray {
+  struct ChronoSlotsPeriod *data;
+  size_t len;
+} ChronoSlotsSlotArray;
+
+/**
+ * Find available slots between `span_start` and `span_end` excluding `blocks`.
+ *
+ * On success, `out` receives the slots, which must be released with `chrono_slots_free`.
+ *
+ * # Safety
+ *
+ * `blocks` must point to `blocks_len` readable periods (it may be null when `blocks_len` is 0),
+ * and `out` must point to writable memory.
+ */
+enum ChronoSlotsStatus chrono_slots_find(int64_t span_start,
+                                         int64_t span_end,
+                                         const struct ChronoSlotsPeriod *blocks,
+                                         size_t blocks_len,
+                                         struct ChronoSlotsSlotArray *out);
+
+/**
+ * Release slots returned by `chrono_slots_find`.
+ *
+ * # Safety
+ *
+ * `slots` must have been returned by `chrono_slots_find` and not released yet.
+ */
+void chrono_slots_free(struct ChronoSlotsSlotArray slots);
+
+#endif  /* CHRONO_SLOTS_H */
@@ -0,0 +1,14 @@
+pub use self::concurrency::*;
+pub use self::distribution::*;
+pub use self::heatmap::*;
+pub use self::overlap::*;
+pub use self::report::*;
+pub use self::stats::*;
+pub use self::verify::*;
+pub mod concurrency;
+pub mod distribution;
+pub mod heatmap;
+pub mod overlap;
+pub mod report;
+pub mod stats;
+pub mod verify;
@@ -0,0 +1,148 @@
+use crate::{
+    finder::coverage::headcount,
+    periods::{
+        period::{Input, Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+// How many inputs are active over the span, as a step function: consecutive periods covering the
+// whole span, each with the number of inputs overlapping it. Periods with no input have a count of 0.
+pub fn concurrency<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+) -> Result<Vec<(Slot, usize)>, PeriodError> {
+    let counts = headcount(&span, &inputs)?;
+    let ends = counts
+        .iter()
+        .skip(1)
+        .map(|(time, _)| *time)
+        .chain(std::iter::once(span.end()));
+    let mut steps: Vec<(Slot, usize)> = Vec::new();
+    for ((start, count), end) in counts.iter().zip(ends) {
+        if *start >= end {
+            continue;
+        }
+        match steps.last_mut() {
+            Some(last) if last.1 == *count => last.0 = Slot::new(last.0.start(), end)?,
+            _ => steps.push((Slot::new(*start, end)?, *count)),
+        }
+    }
+    Ok(steps)
+}
+
+// The maximum number of inputs active at once within the span, and the periods at that count
+// in time order. Without any input the peak is 0 over the whole span.
+pub fn peak_concurrency<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+) -> Result<(usize, Vec<Slot>), PeriodError> {
+    let steps = concurrency(span, inputs)?;
+    let peak = steps.iter().map(|(_, count)| *count).max().unwrap_or(0);
+    let slots = steps
+        .into_iter()
+        .filter(|(_, count)| *count == peak)
+        .map(|(slot, _)| slot)
+        .collect();
+    Ok((peak, slots))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, Duration, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: i64) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            + Duration::hours(hour)
+    }
+
+    fn block(start: i64, end: i64) -> Result<Block, PeriodError> {
+        Block::new(dt(start), dt(end))
+    }
+
+    #[test]
+    fn test_concurrency() -> Result<(), PeriodError> {
+        struct TestCase {
+            name: &'static str,
+            blocks: Vec<Block>,
+            expected: Vec<(i64, i64, usize)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Stacked blocks",
+                blocks: vec![block(1, 4)?, block(2, 5)?, block(3, 4)?],
+                expected: vec![
+                    (0, 1, 0),
+                    (1, 2, 1),
+                    (2, 3, 2),
+                    (3, 4, 3),
+                    (4, 5, 1),
+                    (5, 8, 0),
+                ],
+            },
+            TestCase {
+                name: "Back to back blocks keep the count",
+                blocks: vec![block(2, 4)?, block(4, 6)?],
+                expected: vec![(0, 2, 0), (2, 6, 1), (6, 8, 0)],
+            },
+            TestCase {
+                name: "Blocks beyond the span are clipped",
+                blocks: vec![block(-2, 3)?, block(7, 10)?],
+                expected: vec![(0, 3, 1), (3, 7, 0), (7, 8, 1)],
+            },
+            TestCase {
+                name: "No blocks",
+                blocks: vec![],
+                expected: vec![(0, 8, 0)],
+            },
+        ];
+
+        for case in cases {
+            let actual = concurrency(Span::new(dt(0), dt(8))?, case.blocks)?
+                .iter()
+                .map(|(slot, count)| {
+                    (
+                        (slot.start() - dt(0)).num_hours(),
+                        (slot.end() - dt(0)).num_hours(),
+                        *count,
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_peak_concurrency() -> Result<(), PeriodError> {
+        let cases = vec![
+            (
+                "Two windows at the peak",
+                vec![block(1, 3)?, block(2, 4)?, block(5, 7)?, block(6, 8)?],
+                (2, vec![(2, 3), (6, 7)]),
+            ),
+            ("No blocks", vec![], (0, vec![(0, 8)])),
+        ];
+        for (name, blocks, expected) in cases {
+            let (peak, slots) = peak_concurrency(Span::new(dt(0), dt(8))?, blocks)?;
+            let slots = slots
+                .iter()
+                .map(|s| {
+                    (
+                        (s.start() - dt(0)).num_hours(),
+                        (s.end() - dt(0)).num_hours(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!((peak, slots), expected, "{}", name);
+        }
+        Ok(())
+    }
+}
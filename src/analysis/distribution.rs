@@ -0,0 +1,159 @@
+use chrono::Duration;
+
+use crate::{
+    finder::find::find_slots,
+    periods::{
+        period::{Input, Period, PeriodError},
+        span::Span,
+    },
+};
+
+/// A bucket of the histogram of slot lengths. (min <= length < max)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bucket {
+    pub min: Duration,
+    /// None for the last bucket, which has no upper bound.
+    pub max: Option<Duration>,
+    pub count: usize,
+}
+
+/// Distribution of free-slot lengths.
+#[derive(Debug, Clone)]
+pub struct LengthDistribution {
+    lengths: Vec<Duration>,
+}
+
+impl LengthDistribution {
+    /// constructor
+    pub fn new<P: Period>(slots: &[P]) -> Self {
+        let mut lengths = slots
+            .iter()
+            .map(|s| s.end() - s.start())
+            .collect::<Vec<_>>();
+        lengths.sort();
+        LengthDistribution { lengths }
+    }
+
+    /// Number of slots.
+    pub fn len(&self) -> usize {
+        self.lengths.len()
+    }
+
+    /// Whether there are no slots.
+    pub fn is_empty(&self) -> bool {
+        self.lengths.is_empty()
+    }
+
+    /// The length at the percentile (0.0 - 100.0), by the nearest-rank method.
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        if self.lengths.is_empty() || !(0.0..=100.0).contains(&p) {
+            return None;
+        }
+        let rank = (p / 100.0 * self.lengths.len() as f64).ceil() as usize;
+        Some(self.lengths[rank.max(1) - 1])
+    }
+
+    /// Count the slots by length. The bounds split the lengths into buckets, e.g. [30m, 1h, 2h]
+    /// makes buckets of under 30m, 30m-1h, 1h-2h, and 2h or more.
+    pub fn histogram(&self, bounds: &[Duration]) -> Vec<Bucket> {
+        let mut bounds = bounds.to_vec();
+        bounds.sort();
+        bounds.dedup();
+        let mins = std::iter::once(Duration::zero()).chain(bounds.iter().copied());
+        let maxs = bounds
+            .iter()
+            .copied()
+            .map(Some)
+            .chain(std::iter::once(None));
+        mins.zip(maxs)
+            .map(|(min, max)| Bucket {
+                min,
+                max,
+                count: self
+                    .lengths
+                    .iter()
+                    .filter(|l| min <= **l && max.is_none_or(|max| **l < max))
+                    .count(),
+            })
+            .collect()
+    }
+}
+
+// Find free slots of the span and compute the distribution of their lengths.
+pub fn length_distribution<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+) -> Result<LengthDistribution, PeriodError> {
+    Ok(LengthDistribution::new(&find_slots(span, inputs)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    fn dt(now: DateTime<Tz>, minutes: i64) -> DateTime<Tz> {
+        now + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn test_length_distribution() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        // Free 0-30, 60-75, 105-135, 165-285 (minutes)
+        let span = Span::new(dt(now, 0), dt(now, 285))?;
+        let blocks = vec![
+            Block::new(dt(now, 30), dt(now, 60))?,
+            Block::new(dt(now, 75), dt(now, 105))?,
+            Block::new(dt(now, 135), dt(now, 165))?,
+        ];
+        let distribution = length_distribution(span, blocks)?;
+        assert_eq!(distribution.len(), 4);
+
+        let minutes = |m| Duration::minutes(m);
+        let cases = vec![
+            (0.0, Some(minutes(15))),
+            (25.0, Some(minutes(15))),
+            (50.0, Some(minutes(30))),
+            (75.0, Some(minutes(30))),
+            (100.0, Some(minutes(120))),
+            (101.0, None),
+        ];
+        for (p, expected) in cases {
+            assert_eq!(distribution.percentile(p), expected, "p{}", p);
+        }
+
+        let histogram = distribution.histogram(&[minutes(60), minutes(30), minutes(120)]);
+        assert_eq!(
+            histogram,
+            vec![
+                Bucket {
+                    min: minutes(0),
+                    max: Some(minutes(30)),
+                    count: 1
+                },
+                Bucket {
+                    min: minutes(30),
+                    max: Some(minutes(60)),
+                    count: 2
+                },
+                Bucket {
+                    min: minutes(60),
+                    max: Some(minutes(120)),
+                    count: 0
+                },
+                Bucket {
+                    min: minutes(120),
+                    max: None,
+                    count: 1
+                },
+            ]
+        );
+
+        let empty = LengthDistribution::new::<Block>(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.percentile(50.0), None);
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+use chrono::{Datelike, Duration, NaiveTime};
+use chrono_tz::Tz;
+
+use crate::{
+    finder::find::find_slots,
+    periods::{
+        period::{Input, Period, PeriodError},
+        span::Span,
+    },
+    rules::work_week::local_time,
+};
+
+// Average free time per hour of the week (or per bucket of the resolution) over the span, in the
+// timezone. Rows are the weekdays from Monday, and columns the buckets of the day from local midnight,
+// e.g. 7×24 for a resolution of an hour. Each cell averages over the times the bucket occurs in the
+// span, so it is at most the resolution. The resolution must divide a day.
+pub fn free_time_heatmap<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+    tz: Tz,
+    resolution: Duration,
+) -> Result<Vec<Vec<Duration>>, PeriodError> {
+    let day = Duration::days(1);
+    let step = resolution.num_nanoseconds().unwrap_or(0);
+    if step <= 0 || day.num_nanoseconds().unwrap_or(0) % step != 0 {
+        return Err(PeriodError::InvalidDuration(resolution));
+    }
+    let buckets = (day.num_nanoseconds().unwrap_or(0) / step) as usize;
+    let slots = find_slots(span.clone(), inputs)?;
+
+    let mut free = vec![vec![Duration::zero(); buckets]; 7];
+    let mut occurrences = vec![vec![0i32; buckets]; 7];
+    let mut cursor = 0;
+    for (date, part) in span.days_in(tz)? {
+        let weekday = date.weekday().num_days_from_monday() as usize;
+        let midnight = date.and_time(NaiveTime::MIN);
+        for k in 0..buckets {
+            let start = local_time(&tz, midnight + resolution * k as i32)?.max(part.start());
+            let end = local_time(&tz, midnight + resolution * (k as i32 + 1))?.min(part.end());
+            if start >= end {
+                continue;
+            }
+            occurrences[weekday][k] += 1;
+            while cursor < slots.len() && slots[cursor].end() <= start {
+                cursor += 1;
+            }
+            for slot in slots[cursor..].iter().take_while(|s| s.start() < end) {
+                let overlap = slot.end().min(end) - slot.start().max(start);
+                if overlap > Duration::zero() {
+                    free[weekday][k] += overlap;
+                }
+            }
+        }
+    }
+    Ok(free
+        .into_iter()
+        .zip(occurrences)
+        .map(|(row, counts)| {
+            row.into_iter()
+                .zip(counts)
+                .map(|(total, count)| if count == 0 { total } else { total / count })
+                .collect()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, TimeZone};
+
+    fn tokyo(d: u32, h: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, 1, d, h, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_free_time_heatmap() -> Result<(), PeriodError> {
+        let tz = chrono_tz::Asia::Tokyo;
+        // Two weeks from Monday 2024-01-01. Busy on Monday 09-10 of both weeks, and 09:00-09:30
+        // on the Tuesday of the first week.
+        let span = Span::new(tokyo(1, 0), tokyo(15, 0))?;
+        let blocks = vec![
+            Block::new(tokyo(1, 9), tokyo(1, 10))?,
+            Block::new(tokyo(8, 9), tokyo(8, 10))?,
+            Block::new(tokyo(2, 9), tokyo(2, 9) + Duration::minutes(30))?,
+        ];
+        let heatmap = free_time_heatmap(span.clone(), blocks.clone(), tz, Duration::hours(1))?;
+        assert_eq!(heatmap.len(), 7);
+        assert!(heatmap.iter().all(|row| row.len() == 24));
+        assert_eq!(heatmap[0][9], Duration::zero());
+        assert_eq!(heatmap[0][10], Duration::hours(1));
+        assert_eq!(heatmap[1][9], Duration::minutes(45));
+        assert_eq!(heatmap[6][23], Duration::hours(1));
+
+        let heatmap = free_time_heatmap(span.clone(), blocks.clone(), tz, Duration::hours(6))?;
+        assert_eq!(heatmap[0].len(), 4);
+        assert_eq!(heatmap[0][1], Duration::hours(5));
+
+        // Buckets follow the local time of another timezone: Monday 09:00 JST is Monday 00:00 UTC.
+        let heatmap = free_time_heatmap(span.clone(), blocks.clone(), Tz::UTC, Duration::hours(1))?;
+        assert_eq!(heatmap[0][0], Duration::zero());
+
+        for resolution in [Duration::zero(), Duration::minutes(7)] {
+            assert!(free_time_heatmap(span.clone(), blocks.clone(), tz, resolution).is_err());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use chrono::Duration;
+
+use crate::{
+    finder::find::find_slots,
+    periods::{
+        period::{Input, Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+// Total time both lists of sorted slots are free.
+fn shared_time(a: &[Slot], b: &[Slot]) -> Duration {
+    let (mut i, mut j) = (0, 0);
+    let mut total = Duration::zero();
+    while i < a.len() && j < b.len() {
+        let start = a[i].start().max(b[j].start());
+        let end = a[i].end().min(b[j].end());
+        if start < end {
+            total += end - start;
+        }
+        if a[i].end() < b[j].end() {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    total
+}
+
+// Calculate the free time shared by each pair of calendars within the span, as an N×N matrix.
+// matrix[i][j] is the time both calendar i and j are free, and matrix[i][i] is the free time of calendar i.
+pub fn overlap_matrix<In: Input>(
+    span: Span,
+    calendars: Vec<Vec<In>>,
+) -> Result<Vec<Vec<Duration>>, PeriodError> {
+    let free = calendars
+        .into_iter()
+        .map(|inputs| find_slots(span.clone(), inputs))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut matrix = vec![vec![Duration::zero(); free.len()]; free.len()];
+    for i in 0..free.len() {
+        for j in i..free.len() {
+            let shared = shared_time(&free[i], &free[j]);
+            matrix[i][j] = shared;
+            matrix[j][i] = shared;
+        }
+    }
+    Ok(matrix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    fn dt(now: DateTime<Tz>, hours: i64) -> DateTime<Tz> {
+        now + Duration::hours(hours)
+    }
+
+    #[test]
+    fn test_overlap_matrix() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let span = Span::new(dt(now, 0), dt(now, 8))?;
+        // Free: a 0-2, 4-8 / b 0-5 / c 6-8
+        let calendars = vec![
+            vec![Block::new(dt(now, 2), dt(now, 4))?],
+            vec![Block::new(dt(now, 5), dt(now, 9))?],
+            vec![Block::new(dt(now, -1), dt(now, 6))?],
+        ];
+        let matrix = overlap_matrix(span, calendars)?;
+        let hours = matrix
+            .iter()
+            .map(|row| row.iter().map(|d| d.num_hours()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(hours, vec![vec![6, 3, 2], vec![3, 5, 0], vec![2, 0, 2]]);
+
+        let empty = overlap_matrix::<Block>(Span::new(dt(now, 0), dt(now, 8))?, vec![])?;
+        assert!(empty.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, IsoWeek, NaiveDate};
+use chrono_tz::Tz;
+
+use crate::{
+    finder::{
+        find::{find_slots, find_with},
+        options::FindOptions,
+    },
+    periods::{
+        period::{intersect, Input, Period, PeriodError, PeriodVec},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+/// Busy time of a local day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyBusy {
+    pub date: NaiveDate,
+    pub busy: Duration,
+}
+
+// Busy time per local day of the timezone within the span. Every day the span touches is reported,
+// including days without any block. Days shortened or lengthened by DST are measured in real time.
+pub fn busy_per_day<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+    tz: Tz,
+) -> Result<Vec<DailyBusy>, PeriodError> {
+    let slots = find_slots(span.clone(), inputs)?;
+
+    // Busy intervals are what the slots leave of the span.
+    let mut busy = Vec::new();
+    let mut cursor = span.start();
+    for slot in &slots {
+        if cursor < slot.start() {
+            busy.push((cursor, slot.start()));
+        }
+        cursor = slot.end();
+    }
+    if cursor < span.end() {
+        busy.push((cursor, span.end()));
+    }
+
+    let mut report = Vec::new();
+    for (date, day) in span.days_in(tz)? {
+        let total = busy.iter().fold(Duration::zero(), |acc, (start, end)| {
+            let start = day.start().max(start.with_timezone(&tz));
+            let end = day.end().min(end.with_timezone(&tz));
+            if start < end {
+                acc + (end - start)
+            } else {
+                acc
+            }
+        });
+        report.push(DailyBusy { date, busy: total });
+    }
+    Ok(report)
+}
+
+/// How best_days ranks the days.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DayRanking {
+    /// By the total free time of the day.
+    #[default]
+    TotalFree,
+    /// By the longest contiguous free time of the day, e.g. for a workshop.
+    LongestFree,
+}
+
+/// Free time of a local day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DailyFree {
+    pub date: NaiveDate,
+    pub total: Duration,
+    pub longest: Duration,
+}
+
+// The top local days of the timezone within the span by free time, best first (earlier among ties).
+// Free time is what find_with offers under the options; overlapping candidates are counted once.
+pub fn best_days<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+    options: &FindOptions,
+    tz: Tz,
+    ranking: DayRanking,
+    top: usize,
+) -> Result<Vec<DailyFree>, PeriodError> {
+    let slots: Vec<Slot> = find_with(span.clone(), inputs, options)?;
+    let free: Vec<Slot> = slots.merge_overlapping();
+    let mut days = Vec::new();
+    for (date, day) in span.days_in(tz)? {
+        let parts = free
+            .iter()
+            .filter_map(|slot| intersect(&day, slot))
+            .map(|part| part.end() - part.start())
+            .collect::<Vec<_>>();
+        days.push(DailyFree {
+            date,
+            total: parts.iter().fold(Duration::zero(), |acc, d| acc + *d),
+            longest: parts.into_iter().max().unwrap_or_else(Duration::zero),
+        });
+    }
+    days.sort_by_key(|d| match ranking {
+        DayRanking::TotalFree => (std::cmp::Reverse(d.total), d.date),
+        DayRanking::LongestFree => (std::cmp::Reverse(d.longest), d.date),
+    });
+    days.truncate(top);
+    Ok(days)
+}
+
+// Group the periods by the ISO week of their start in the timezone, in order of the weeks.
+// A period is not split at the week boundary; use Span::iso_week to search a week instead.
+pub fn group_by_iso_week<P: Period + Clone>(periods: &[P], tz: Tz) -> Vec<(IsoWeek, Vec<P>)> {
+    let mut weeks: BTreeMap<IsoWeek, Vec<P>> = BTreeMap::new();
+    for period in periods {
+        let week = period.start().with_timezone(&tz).iso_week();
+        weeks.entry(week).or_default().push(period.clone());
+    }
+    weeks.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, TimeZone};
+
+    fn ny(d: u32, h: u32) -> DateTime<Tz> {
+        chrono_tz::America::New_York
+            .with_ymd_and_hms(2024, 3, d, h, 0, 0)
+            .unwrap()
+    }
+
+    fn date(d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, 3, d).unwrap()
+    }
+
+    #[test]
+    fn test_busy_per_day() -> Result<(), PeriodError> {
+        struct TestCase {
+            name: &'static str,
+            span: Span,
+            blocks: Vec<Block>,
+            tz: Tz,
+            expected: Vec<(NaiveDate, i64)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Spread over days",
+                span: Span::new(ny(8, 0), ny(10, 0))?,
+                blocks: vec![
+                    Block::new(ny(8, 9), ny(8, 11))?,
+                    Block::new(ny(8, 10), ny(8, 12))?,
+                    Block::new(ny(8, 22), ny(9, 2))?,
+                ],
+                tz: chrono_tz::America::New_York,
+                expected: vec![(date(8), 5), (date(9), 2)],
+            },
+            TestCase {
+                name: "DST day is 23 hours",
+                span: Span::new(ny(9, 12), ny(11, 12))?,
+                blocks: vec![Block::new(ny(9, 0), ny(12, 0))?],
+                tz: chrono_tz::America::New_York,
+                expected: vec![(date(9), 12), (date(10), 23), (date(11), 12)],
+            },
+            TestCase {
+                name: "Days of another timezone",
+                span: Span::new(ny(8, 0), ny(9, 0))?,
+                blocks: vec![Block::new(ny(8, 9), ny(8, 12))?],
+                tz: chrono_tz::Asia::Tokyo,
+                expected: vec![(date(8), 1), (date(9), 2)],
+            },
+        ];
+
+        for case in cases {
+            let report = busy_per_day(case.span, case.blocks, case.tz)?;
+            let actual = report
+                .iter()
+                .map(|d| (d.date, d.busy.num_hours()))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_best_days() -> Result<(), PeriodError> {
+        let tz = chrono_tz::America::New_York;
+        let span = Span::new(ny(11, 9), ny(13, 17))?;
+        let blocks = vec![
+            // Monday: 4h + 3h free
+            Block::new(ny(11, 13), ny(11, 14))?,
+            // Tuesday: 6h free, after the night (tied with Wednesday, which is later)
+            Block::new(ny(11, 17), ny(12, 11))?,
+            // Wednesday: 2h + 2h + 2h free, after the night
+            Block::new(ny(12, 17), ny(13, 9))?,
+            Block::new(ny(13, 11), ny(13, 12))?,
+            Block::new(ny(13, 14), ny(13, 15))?,
+        ];
+        let options = FindOptions::new();
+
+        let cases = vec![
+            (
+                DayRanking::TotalFree,
+                3,
+                vec![(date(11), 7, 4), (date(12), 6, 6), (date(13), 6, 2)],
+            ),
+            (
+                DayRanking::LongestFree,
+                2,
+                vec![(date(12), 6, 6), (date(11), 7, 4)],
+            ),
+        ];
+        for (ranking, top, expected) in cases {
+            let actual = best_days(span.clone(), blocks.clone(), &options, tz, ranking, top)?
+                .iter()
+                .map(|d| (d.date, d.total.num_hours(), d.longest.num_hours()))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{:?}", ranking);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_by_iso_week() -> Result<(), PeriodError> {
+        // 2024-03-10 is the Sunday of W10.
+        let slots = vec![
+            Block::new(ny(11, 9), ny(11, 10))?,
+            Block::new(ny(10, 9), ny(10, 10))?,
+            Block::new(ny(10, 23), ny(11, 1))?,
+        ];
+        let weeks = |tz: Tz| {
+            group_by_iso_week(&slots, tz)
+                .iter()
+                .map(|(week, slots)| (week.week(), slots.len()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(weeks(chrono_tz::America::New_York), vec![(10, 2), (11, 1)]);
+        assert_eq!(weeks(chrono_tz::Asia::Tokyo), vec![(10, 1), (11, 2)]);
+        assert!(group_by_iso_week::<Block>(&[], Tz::UTC).is_empty());
+        Ok(())
+    }
+}
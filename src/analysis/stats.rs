@@ -0,0 +1,155 @@
+use chrono::Duration;
+
+use crate::{
+    finder::find::find_slots,
+    periods::{
+        period::{Input, Output, Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+/// Summary of availability within a span.
+#[derive(Debug, Clone)]
+pub struct Stats {
+    /// Total time of the slots.
+    pub total_free: Duration,
+    /// Total time of the span not covered by the slots.
+    pub total_busy: Duration,
+    /// Busy time as a percentage of the span. (0.0 - 100.0)
+    pub utilization: f64,
+    /// The longest slot (the earliest one among ties).
+    pub longest_slot: Option<Slot>,
+    /// Number of slots.
+    pub slot_count: usize,
+}
+
+impl Stats {
+    /// Compute the stats from the slots found in the span.
+    pub fn new<P: Period>(span: &Span, slots: &[P]) -> Self {
+        let length = span.end() - span.start();
+        let total_free = slots
+            .iter()
+            .fold(Duration::zero(), |acc, s| acc + (s.end() - s.start()));
+        let total_busy = length - total_free;
+        let utilization = match length.num_milliseconds() {
+            0 => 0.0,
+            ms => total_busy.num_milliseconds() as f64 * 100.0 / ms as f64,
+        };
+        let longest_slot = slots
+            .iter()
+            .rev()
+            .max_by_key(|s| s.end() - s.start())
+            .and_then(|s| Slot::new(s.start(), s.end()).ok());
+        Stats {
+            total_free,
+            total_busy,
+            utilization,
+            longest_slot,
+            slot_count: slots.len(),
+        }
+    }
+}
+
+// Same as find, and the stats are computed alongside.
+pub fn find_with_stats<In: Input, Out: Output>(
+    span: Span,
+    inputs: Vec<In>,
+) -> Result<(Vec<Out>, Stats), PeriodError> {
+    let slots = find_slots(span.clone(), inputs)?;
+    let stats = Stats::new(&span, &slots);
+    Ok((
+        slots.into_iter().map(Out::create_from_slot).collect(),
+        stats,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    fn dt(now: DateTime<Tz>, hours: i64) -> DateTime<Tz> {
+        now + Duration::hours(hours)
+    }
+
+    fn block(now: DateTime<Tz>, start: i64, end: i64) -> Result<Block, PeriodError> {
+        Block::new(dt(now, start), dt(now, end))
+    }
+
+    #[test]
+    fn test_find_with_stats() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let span = Span::new(dt(now, 0), dt(now, 8))?;
+
+        struct TestCase {
+            name: &'static str,
+            blocks: Vec<Block>,
+            free: i64,
+            busy: i64,
+            utilization: f64,
+            longest: Option<(i64, i64)>,
+            count: usize,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "No blocks",
+                blocks: vec![],
+                free: 8,
+                busy: 0,
+                utilization: 0.0,
+                longest: Some((0, 8)),
+                count: 1,
+            },
+            TestCase {
+                name: "Overlapping blocks",
+                blocks: vec![block(now, 1, 3)?, block(now, 2, 4)?, block(now, 6, 7)?],
+                free: 4,
+                busy: 4,
+                utilization: 50.0,
+                longest: Some((4, 6)),
+                count: 3,
+            },
+            TestCase {
+                name: "Fully booked",
+                blocks: vec![block(now, -1, 9)?],
+                free: 0,
+                busy: 8,
+                utilization: 100.0,
+                longest: None,
+                count: 0,
+            },
+        ];
+
+        for case in cases {
+            let (slots, stats): (Vec<Slot>, Stats) = find_with_stats(span.clone(), case.blocks)?;
+            assert_eq!(slots.len(), case.count, "{}", case.name);
+            assert_eq!(stats.slot_count, case.count, "{}", case.name);
+            assert_eq!(
+                stats.total_free,
+                Duration::hours(case.free),
+                "{}",
+                case.name
+            );
+            assert_eq!(
+                stats.total_busy,
+                Duration::hours(case.busy),
+                "{}",
+                case.name
+            );
+            assert!(
+                (stats.utilization - case.utilization).abs() < 1e-9,
+                "{}",
+                case.name
+            );
+            let longest = stats
+                .longest_slot
+                .map(|s| ((s.start() - now).num_hours(), (s.end() - now).num_hours()));
+            assert_eq!(longest, case.longest, "{}", case.name);
+        }
+        Ok(())
+    }
+}
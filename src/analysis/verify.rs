@@ -0,0 +1,165 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use crate::periods::{
+    period::{Input, Period, PeriodError},
+    span::Span,
+};
+
+/// A broken invariant of the slots found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The slot at the index is not within the span.
+    OutsideSpan { slot: usize },
+    /// The slot overlaps the block (input) at the index.
+    OverlapsBlock { slot: usize, block: usize },
+    /// The slot starts before the previous slot does.
+    NotSorted { slot: usize },
+    /// The slot overlaps the previous slot.
+    NotDisjoint { slot: usize },
+    /// The time is within the span, but neither in a slot nor in a block.
+    Uncovered {
+        start: DateTime<Tz>,
+        end: DateTime<Tz>,
+    },
+}
+
+// Check the invariants of slots found in the span excluding the blocks:
+// slots are within the span, don't overlap any block, are sorted and disjoint,
+// and slots and blocks together cover the span. An empty result means all of them hold.
+pub fn verify<In: Input, P: Period>(
+    span: &Span,
+    inputs: &[In],
+    slots: &[P],
+) -> Result<Vec<Violation>, PeriodError> {
+    let blocks = inputs
+        .iter()
+        .map(|input| input.to_block())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut violations = Vec::new();
+    for (i, slot) in slots.iter().enumerate() {
+        if slot.start() < span.start() || span.end() < slot.end() {
+            violations.push(Violation::OutsideSpan { slot: i });
+        }
+        for (j, block) in blocks.iter().enumerate() {
+            if block.start() < slot.end() && slot.start() < block.end() {
+                violations.push(Violation::OverlapsBlock { slot: i, block: j });
+            }
+        }
+        let Some(prev) = i.checked_sub(1).map(|p| &slots[p]) else {
+            continue;
+        };
+        if slot.start() < prev.start() {
+            violations.push(Violation::NotSorted { slot: i });
+        } else if slot.start() < prev.end() {
+            violations.push(Violation::NotDisjoint { slot: i });
+        }
+    }
+
+    let mut covered = slots
+        .iter()
+        .map(|s| (s.start(), s.end()))
+        .chain(blocks.iter().map(|b| (b.start(), b.end())))
+        .collect::<Vec<_>>();
+    covered.sort();
+    let mut cursor = span.start();
+    for (start, end) in covered {
+        if cursor >= span.end() {
+            break;
+        }
+        if cursor < start {
+            violations.push(Violation::Uncovered {
+                start: cursor,
+                end: start.min(span.end()),
+            });
+        }
+        cursor = cursor.max(end);
+    }
+    if cursor < span.end() {
+        violations.push(Violation::Uncovered {
+            start: cursor,
+            end: span.end(),
+        });
+    }
+    Ok(violations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        finder::find::find,
+        periods::{block::Block, slot::Slot},
+    };
+    use chrono::{Duration, TimeZone};
+
+    fn dt(hour: i64) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            + Duration::hours(hour)
+    }
+
+    fn slot(start: i64, end: i64) -> Result<Slot, PeriodError> {
+        Slot::new(dt(start), dt(end))
+    }
+
+    #[test]
+    fn test_verify() -> Result<(), PeriodError> {
+        let span = Span::new(dt(9), dt(17))?;
+        let blocks = vec![Block::new(dt(10), dt(11))?, Block::new(dt(13), dt(14))?];
+
+        struct TestCase {
+            name: &'static str,
+            slots: Vec<Slot>,
+            expected: Vec<Violation>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Slots found",
+                slots: find(span.clone(), blocks.clone())?,
+                expected: vec![],
+            },
+            TestCase {
+                name: "Outside span",
+                slots: vec![slot(8, 10)?, slot(11, 13)?, slot(14, 17)?],
+                expected: vec![Violation::OutsideSpan { slot: 0 }],
+            },
+            TestCase {
+                name: "Overlaps block",
+                slots: vec![slot(9, 10)?, slot(11, 14)?, slot(14, 17)?],
+                expected: vec![Violation::OverlapsBlock { slot: 1, block: 1 }],
+            },
+            TestCase {
+                name: "Not sorted and not disjoint",
+                slots: vec![slot(11, 13)?, slot(9, 10)?, slot(14, 17)?, slot(15, 16)?],
+                expected: vec![
+                    Violation::NotSorted { slot: 1 },
+                    Violation::NotDisjoint { slot: 3 },
+                ],
+            },
+            TestCase {
+                name: "Uncovered",
+                slots: vec![slot(9, 10)?, slot(11, 12)?, slot(14, 16)?],
+                expected: vec![
+                    Violation::Uncovered {
+                        start: dt(12),
+                        end: dt(13),
+                    },
+                    Violation::Uncovered {
+                        start: dt(16),
+                        end: dt(17),
+                    },
+                ],
+            },
+        ];
+
+        for case in cases {
+            let actual = verify(&span, &blocks, &case.slots)?;
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use chrono_tz::{Tz, TZ_VARIANTS};
+use proptest::{
+    arbitrary::Arbitrary,
+    collection::vec,
+    prelude::*,
+    sample::select,
+    strategy::{BoxedStrategy, Strategy},
+};
+
+use crate::periods::{block::Block, period::Period, slot::Slot, span::Span};
+
+// 2000-01-01T00:00:00Z and 2100-01-01T00:00:00Z in minutes.
+const MIN_MINUTES: i64 = 946_684_800 / 60;
+const MAX_MINUTES: i64 = 4_102_444_800 / 60;
+
+// Any timezone.
+pub fn timezones() -> impl Strategy<Value = Tz> {
+    select(TZ_VARIANTS.to_vec())
+}
+
+// Times between 2000 and 2100 in the timezone, on whole minutes.
+pub fn datetimes(tz: Tz) -> impl Strategy<Value = DateTime<Tz>> {
+    (MIN_MINUTES..MAX_MINUTES)
+        .prop_map(move |m| Utc.timestamp_opt(m * 60, 0).unwrap().with_timezone(&tz))
+}
+
+// Periods in the timezone lasting from 1 minute up to max_minutes.
+fn periods(tz: Tz, max_minutes: i64) -> impl Strategy<Value = (DateTime<Tz>, DateTime<Tz>)> {
+    (datetimes(tz), 1..=max_minutes).prop_map(|(start, m)| (start, start + Duration::minutes(m)))
+}
+
+// Spans in the timezone lasting up to a week.
+pub fn spans(tz: Tz) -> impl Strategy<Value = Span> {
+    periods(tz, 7 * 24 * 60).prop_map(|(start, end)| Span::new(start, end).unwrap())
+}
+
+// Up to max blocks around the span. Blocks may start before or end after the span, and may overlap each other.
+pub fn blocks_around(span: &Span, max: usize) -> impl Strategy<Value = Vec<Block>> {
+    let tz = span.start().timezone();
+    let start = span.start().timestamp() / 60;
+    let length = (span.end() - span.start()).num_minutes().max(1);
+    let block = (-length / 4..length + length / 4, 1..=length / 2 + 1).prop_map(
+        move |(offset, minutes)| {
+            let start = Utc
+                .timestamp_opt((start + offset) * 60, 0)
+                .unwrap()
+                .with_timezone(&tz);
+            Block::new(start, start + Duration::minutes(minutes)).unwrap()
+        },
+    );
+    vec(block, 0..=max)
+}
+
+// A span and up to max blocks around it, in any timezone.
+pub fn calendars(max: usize) -> impl Strategy<Value = (Span, Vec<Block>)> {
+    timezones()
+        .prop_flat_map(spans)
+        .prop_flat_map(move |span| (Just(span.clone()), blocks_around(&span, max)))
+}
+
+impl Arbitrary for Span {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        timezones().prop_flat_map(spans).boxed()
+    }
+}
+
+impl Arbitrary for Block {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        timezones()
+            .prop_flat_map(|tz| periods(tz, 24 * 60))
+            .prop_map(|(start, end)| Block::new(start, end).unwrap())
+            .boxed()
+    }
+}
+
+impl Arbitrary for Slot {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        timezones()
+            .prop_flat_map(|tz| periods(tz, 24 * 60))
+            .prop_map(|(start, end)| Slot::new(start, end).unwrap())
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{analysis::verify::verify, finder::find::find};
+
+    proptest! {
+        #[test]
+        fn test_find_holds_invariants((span, blocks) in calendars(20)) {
+            let slots: Vec<Slot> = find(span.clone(), blocks.clone()).unwrap();
+            prop_assert_eq!(verify(&span, &blocks, &slots).unwrap(), vec![]);
+        }
+
+        #[test]
+        fn test_arbitrary_periods(span in any::<Span>(), block in any::<Block>()) {
+            prop_assert!(span.start() < span.end());
+            prop_assert!(block.start() < block.end());
+        }
+    }
+}
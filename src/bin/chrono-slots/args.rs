@@ -0,0 +1,206 @@
+use std::path::PathBuf;
+
+use chrono::Duration;
+use chrono_tz::Tz;
+
+pub const USAGE: &str = "\
+Usage: chrono-slots [OPTIONS] [FILE]
+
+Print available time slots of a span excluding the events read from FILE (or stdin).
+
+Options:
+  --format <json|csv|ics>  Input format (default: by file extension, json for stdin)
+  --output <text|json>     Output format (default: text)
+  --tz <TZ>                Timezone for times without offset and for output (default: UTC)
+  --start <TIME>           Start of the span (overrides the span of JSON input)
+  --end <TIME>             End of the span (overrides the span of JSON input)
+  --min-duration <MIN>     Drop slots shorter than MIN minutes (or a duration like 1h30m
+                           with the humantime feature)
+  --align <MIN>            Align slot starts to multiples of MIN minutes (or a duration
+                           like 15m with the humantime feature)
+  -h, --help               Print this help";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    Json,
+    Csv,
+    Ics,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug)]
+pub struct Args {
+    pub file: Option<PathBuf>,
+    pub format: InputFormat,
+    pub output: OutputFormat,
+    pub tz: Tz,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub min_duration: Option<Duration>,
+    pub align: Option<Duration>,
+    pub help: bool,
+}
+
+impl Args {
+    // Parse the command line arguments (without the program name).
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        let mut file = None;
+        let mut format = None;
+        let mut output = OutputFormat::Text;
+        let mut tz = Tz::UTC;
+        let mut start = None;
+        let mut end = None;
+        let mut min_duration = None;
+        let mut align = None;
+        let mut help = false;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("missing value for {}", arg))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => help = true,
+                "--format" => format = Some(parse_input_format(&value()?)?),
+                "--output" => output = parse_output_format(&value()?)?,
+                "--tz" => tz = value()?.parse::<Tz>()?,
+                "--start" => start = Some(value()?),
+                "--end" => end = Some(value()?),
+                "--min-duration" => min_duration = Some(parse_length(&value()?)?),
+                "--align" => align = Some(parse_length(&value()?)?),
+                _ if arg.starts_with('-') && arg != "-" => {
+                    return Err(format!("unknown option: {}", arg))
+                }
+                _ => {
+                    if file.is_some() {
+                        return Err(format!("unexpected argument: {}", arg));
+                    }
+                    if arg != "-" {
+                        file = Some(PathBuf::from(arg));
+                    }
+                }
+            }
+        }
+
+        let format = match format {
+            Some(format) => format,
+            None => file
+                .as_ref()
+                .and_then(|f| f.extension())
+                .and_then(|ext| parse_input_format(&ext.to_string_lossy()).ok())
+                .unwrap_or(InputFormat::Json),
+        };
+
+        Ok(Args {
+            file,
+            format,
+            output,
+            tz,
+            start,
+            end,
+            min_duration,
+            align,
+            help,
+        })
+    }
+}
+
+fn parse_input_format(s: &str) -> Result<InputFormat, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "json" => Ok(InputFormat::Json),
+        "csv" => Ok(InputFormat::Csv),
+        "ics" | "ical" => Ok(InputFormat::Ics),
+        _ => Err(format!("unknown input format: {}", s)),
+    }
+}
+
+fn parse_output_format(s: &str) -> Result<OutputFormat, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "text" => Ok(OutputFormat::Text),
+        "json" => Ok(OutputFormat::Json),
+        _ => Err(format!("unknown output format: {}", s)),
+    }
+}
+
+// A positive number of minutes, or with the humantime feature a duration like "1h30m".
+fn parse_length(s: &str) -> Result<Duration, String> {
+    let length = match s.parse::<i64>() {
+        Ok(minutes) => Some(Duration::minutes(minutes)),
+        #[cfg(feature = "humantime")]
+        Err(_) => chrono_slots::parse_duration(s).ok(),
+        #[cfg(not(feature = "humantime"))]
+        Err(_) => None,
+    };
+    match length {
+        Some(length) if length > Duration::zero() => Ok(length),
+        _ => Err(format!("invalid minutes: {}", s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        Args::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_args_parse() -> Result<(), String> {
+        let args = parse(&[
+            "events.ics",
+            "--tz",
+            "Asia/Tokyo",
+            "--output",
+            "json",
+            "--min-duration",
+            "30",
+            "--align",
+            "15",
+        ])?;
+        assert_eq!(args.file, Some(PathBuf::from("events.ics")));
+        assert_eq!(args.format, InputFormat::Ics);
+        assert_eq!(args.output, OutputFormat::Json);
+        assert_eq!(args.tz, chrono_tz::Asia::Tokyo);
+        assert_eq!(args.min_duration, Some(Duration::minutes(30)));
+        assert_eq!(args.align, Some(Duration::minutes(15)));
+
+        let args = parse(&["--format", "csv", "-"])?;
+        assert_eq!(args.file, None);
+        assert_eq!(args.format, InputFormat::Csv);
+        assert_eq!(args.output, OutputFormat::Text);
+
+        let args = parse(&[])?;
+        assert_eq!(args.format, InputFormat::Json);
+        assert_eq!(args.tz, Tz::UTC);
+
+        #[cfg(feature = "humantime")]
+        {
+            let args = parse(&["--min-duration", "1h30m", "--align", "15m"])?;
+            assert_eq!(args.min_duration, Some(Duration::minutes(90)));
+            assert_eq!(args.align, Some(Duration::minutes(15)));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_args_parse_error() {
+        let cases: Vec<(&str, Vec<&str>)> = vec![
+            ("Unknown option", vec!["--unknown"]),
+            ("Missing value", vec!["--tz"]),
+            ("Invalid timezone", vec!["--tz", "Nowhere/City"]),
+            ("Invalid minutes", vec!["--align", "0"]),
+            ("Invalid format", vec!["--format", "xml"]),
+            ("Two files", vec!["a.json", "b.json"]),
+        ];
+        for (name, args) in cases {
+            assert!(parse(&args).is_err(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_slots::Block;
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::args::InputFormat;
+
+/// The span (if the input has one) and the events read from the input.
+#[derive(Debug, Default)]
+pub struct Events {
+    pub span: Option<(DateTime<Tz>, DateTime<Tz>)>,
+    pub blocks: Vec<Block>,
+}
+
+#[derive(Deserialize)]
+struct JsonPeriod {
+    start: String,
+    end: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonInput {
+    Events(Vec<JsonPeriod>),
+    WithSpan {
+        span: Option<JsonPeriod>,
+        #[serde(default)]
+        events: Vec<JsonPeriod>,
+    },
+}
+
+pub fn read(content: &str, format: InputFormat, tz: &Tz) -> Result<Events, String> {
+    match format {
+        InputFormat::Json => read_json(content, tz),
+        InputFormat::Csv => read_csv(content, tz),
+        InputFormat::Ics => read_ics(content, tz),
+    }
+}
+
+/// Parse RFC 3339, or a local time without offset in the timezone.
+pub fn parse_time(s: &str, tz: &Tz) -> Result<DateTime<Tz>, String> {
+    let s = s.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(tz));
+    }
+    const FORMATS: [&str; 4] = [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M",
+    ];
+    FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(s, f).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+        .and_then(|naive| tz.from_local_datetime(&naive).earliest())
+        .ok_or_else(|| format!("invalid time: {}", s))
+}
+
+fn block(start: DateTime<Tz>, end: DateTime<Tz>) -> Result<Block, String> {
+    Block::new(start, end).map_err(|err| {
+        format!(
+            "invalid event {} - {}: {}",
+            start.to_rfc3339(),
+            end.to_rfc3339(),
+            err
+        )
+    })
+}
+
+fn read_json(content: &str, tz: &Tz) -> Result<Events, String> {
+    let input: JsonInput = serde_json::from_str(content).map_err(|err| err.to_string())?;
+    let (span, events) = match input {
+        JsonInput::Events(events) => (None, events),
+        JsonInput::WithSpan { span, events } => (span, events),
+    };
+    let span = match span {
+        Some(span) => Some((parse_time(&span.start, tz)?, parse_time(&span.end, tz)?)),
+        None => None,
+    };
+    let blocks = events
+        .iter()
+        .map(|e| block(parse_time(&e.start, tz)?, parse_time(&e.end, tz)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Events { span, blocks })
+}
+
+// Each line is "start,end". A header line is skipped.
+fn read_csv(content: &str, tz: &Tz) -> Result<Events, String> {
+    let mut blocks = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (i == 0 && line.to_ascii_lowercase().starts_with("start")) {
+            continue;
+        }
+        let columns = line
+            .split(',')
+            .map(|c| c.trim().trim_matches('"'))
+            .collect::<Vec<_>>();
+        let [start, end, ..] = columns.as_slice() else {
+            return Err(format!("line {}: expected start,end", i + 1));
+        };
+        let b = parse_time(start, tz)
+            .and_then(|start| block(start, parse_time(end, tz)?))
+            .map_err(|err| format!("line {}: {}", i + 1, err))?;
+        blocks.push(b);
+    }
+    Ok(Events { span: None, blocks })
+}
+
+#[derive(Default)]
+struct IcsEvent {
+    start: Option<DateTime<Tz>>,
+    end: Option<DateTime<Tz>>,
+    skip: bool,
+}
+
+// Read VEVENTs of an iCalendar. Cancelled and transparent (free) events are skipped.
+fn read_ics(content: &str, tz: &Tz) -> Result<Events, String> {
+    // Unfold continuation lines first.
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.trim_end().to_string()),
+        }
+    }
+
+    let mut blocks = Vec::new();
+    let mut event: Option<IcsEvent> = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut params = name.split(';');
+        let name = params.next().unwrap_or_default().to_ascii_uppercase();
+        match (name.as_str(), value, event.as_mut()) {
+            ("BEGIN", "VEVENT", _) => event = Some(IcsEvent::default()),
+            ("END", "VEVENT", Some(_)) => {
+                let e = event.take().unwrap_or_default();
+                let Some(start) = e.start else {
+                    return Err("VEVENT without DTSTART".to_string());
+                };
+                let end = e.end.unwrap_or(start + Duration::days(1));
+                if !e.skip {
+                    blocks.push(block(start, end)?);
+                }
+            }
+            ("DTSTART", _, Some(e)) => e.start = Some(parse_ics_time(params, value, tz)?),
+            ("DTEND", _, Some(e)) => e.end = Some(parse_ics_time(params, value, tz)?),
+            ("STATUS", "CANCELLED", Some(e)) | ("TRANSP", "TRANSPARENT", Some(e)) => e.skip = true,
+            _ => {}
+        }
+    }
+    Ok(Events { span: None, blocks })
+}
+
+fn parse_ics_time<'a>(
+    params: impl Iterator<Item = &'a str>,
+    value: &str,
+    tz: &Tz,
+) -> Result<DateTime<Tz>, String> {
+    let mut zone = *tz;
+    for param in params {
+        if let Some(id) = param.strip_prefix("TZID=") {
+            zone = id.trim_matches('"').parse::<Tz>()?;
+        }
+    }
+    let invalid = || format!("invalid time: {}", value);
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").map_err(|_| invalid())?;
+        return Ok(Utc.from_utc_datetime(&naive).with_timezone(tz));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y%m%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(invalid)?;
+    zone.from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.with_timezone(tz))
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_slots::Period;
+
+    fn tokyo(d: u32, h: u32, m: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, 1, d, h, m, 0)
+            .unwrap()
+    }
+
+    fn assert_blocks(events: &Events, expected: &[(DateTime<Tz>, DateTime<Tz>)], name: &str) {
+        assert_eq!(events.blocks.len(), expected.len(), "{}", name);
+        for (actual, expected) in events.blocks.iter().zip(expected.iter()) {
+            assert_eq!(actual.start(), expected.0, "{}", name);
+            assert_eq!(actual.end(), expected.1, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let tz = chrono_tz::Asia::Tokyo;
+        let cases = vec![
+            ("2024-01-01T09:30:00+09:00", Some(tokyo(1, 9, 30))),
+            ("2024-01-01T00:30:00Z", Some(tokyo(1, 9, 30))),
+            ("2024-01-01T09:30:00", Some(tokyo(1, 9, 30))),
+            ("2024-01-01 09:30", Some(tokyo(1, 9, 30))),
+            ("2024-01-01", Some(tokyo(1, 0, 0))),
+            ("9:30", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_time(input, &tz).ok(), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_read() -> Result<(), String> {
+        let tz = chrono_tz::Asia::Tokyo;
+
+        let json = r#"{
+            "span": {"start": "2024-01-01T09:00:00+09:00", "end": "2024-01-01T17:00:00+09:00"},
+            "events": [{"start": "2024-01-01 10:00", "end": "2024-01-01 11:00"}]
+        }"#;
+        let events = read(json, InputFormat::Json, &tz)?;
+        assert_eq!(events.span, Some((tokyo(1, 9, 0), tokyo(1, 17, 0))));
+        assert_blocks(&events, &[(tokyo(1, 10, 0), tokyo(1, 11, 0))], "json");
+
+        let json = r#"[{"start": "2024-01-01T01:00:00Z", "end": "2024-01-01T02:00:00Z"}]"#;
+        let events = read(json, InputFormat::Json, &tz)?;
+        assert_eq!(events.span, None);
+        assert_blocks(&events, &[(tokyo(1, 10, 0), tokyo(1, 11, 0))], "json array");
+
+        let csv = "start,end\n2024-01-01 10:00,2024-01-01 11:00\n\n\"2024-01-01 13:00\",\"2024-01-01 14:30\"\n";
+        let events = read(csv, InputFormat::Csv, &tz)?;
+        assert_blocks(
+            &events,
+            &[
+                (tokyo(1, 10, 0), tokyo(1, 11, 0)),
+                (tokyo(1, 13, 0), tokyo(1, 14, 30)),
+            ],
+            "csv",
+        );
+
+        let ics = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\nSUMMARY:Standup\r\nDTSTART:20240101T010000Z\r\nDTEND:20240101T013000Z\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART;TZID=America/New_York:20231231T\r\n 230000\r\nDTEND;TZID=America/New_York:20240101T000000\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20240102\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART:20240101T120000\r\nDTEND:20240101T130000\r\nTRANSP:TRANSPARENT\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART:20240101T140000\r\nDTEND:20240101T150000\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n\
+END:VCALENDAR\r\n";
+        let events = read(ics, InputFormat::Ics, &tz)?;
+        assert_blocks(
+            &events,
+            &[
+                (tokyo(1, 10, 0), tokyo(1, 10, 30)),
+                (tokyo(1, 13, 0), tokyo(1, 14, 0)),
+                (tokyo(2, 0, 0), tokyo(3, 0, 0)),
+            ],
+            "ics",
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_error() {
+        let tz = chrono_tz::Asia::Tokyo;
+        let cases = vec![
+            ("Invalid json", "{", InputFormat::Json),
+            (
+                "Reversed event",
+                r#"[{"start": "2024-01-01 11:00", "end": "2024-01-01 10:00"}]"#,
+                InputFormat::Json,
+            ),
+            ("Missing column", "2024-01-01 10:00\n", InputFormat::Csv),
+            ("Invalid time", "10:00,11:00\n", InputFormat::Csv),
+            (
+                "Missing DTSTART",
+                "BEGIN:VEVENT\nDTEND:20240101T010000Z\nEND:VEVENT\n",
+                InputFormat::Ics,
+            ),
+        ];
+        for (name, content, format) in cases {
+            assert!(read(content, format, &tz).is_err(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::{fs, io::Read, process::ExitCode};
+
+use chrono_slots::{find_with, FindOptions, Slot, Span};
+
+mod args;
+mod input;
+mod output;
+
+use args::{Args, USAGE};
+
+fn run(args: Args) -> Result<String, String> {
+    let content = match &args.file {
+        Some(path) => {
+            fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err))?
+        }
+        None => {
+            let mut content = String::new();
+            std::io::stdin()
+                .read_to_string(&mut content)
+                .map_err(|err| err.to_string())?;
+            content
+        }
+    };
+    let events = input::read(&content, args.format, &args.tz)?;
+
+    let (start, end) = match (&args.start, &args.end, events.span) {
+        (Some(start), Some(end), _) => (
+            input::parse_time(start, &args.tz)?,
+            input::parse_time(end, &args.tz)?,
+        ),
+        (start, end, Some((span_start, span_end))) => (
+            match start {
+                Some(start) => input::parse_time(start, &args.tz)?,
+                None => span_start,
+            },
+            match end {
+                Some(end) => input::parse_time(end, &args.tz)?,
+                None => span_end,
+            },
+        ),
+        _ => return Err("the span is required (--start and --end)".to_string()),
+    };
+    let span = Span::new(start, end).map_err(|err| err.to_string())?;
+
+    let mut options = FindOptions::new();
+    if let Some(min_duration) = args.min_duration {
+        options = options.min_duration(min_duration);
+    }
+    if let Some(align) = args.align {
+        options = options.alignment(align);
+    }
+
+    let slots: Vec<Slot> =
+        find_with(span, events.blocks, &options).map_err(|err| err.to_string())?;
+    Ok(output::render(&slots, args.output))
+}
+
+fn main() -> ExitCode {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
+    if args.help {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+    match run(args) {
+        Ok(output) => {
+            println!("{}", output);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
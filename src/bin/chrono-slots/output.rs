@@ -0,0 +1,61 @@
+use chrono_slots::{Period, PeriodVec, Slot};
+use serde_json::json;
+
+use crate::args::OutputFormat;
+
+pub fn render(slots: &Vec<Slot>, format: OutputFormat) -> String {
+    match format {
+        OutputFormat::Text => slots.to_string().replace("\n ", "\n"),
+        OutputFormat::Json => {
+            let slots = slots
+                .iter()
+                .map(|slot| {
+                    json!({
+                        "start": slot.start().to_rfc3339(),
+                        "end": slot.end().to_rfc3339(),
+                        "minutes": (slot.end() - slot.start()).num_minutes(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            serde_json::to_string_pretty(&slots).unwrap_or_default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_render() -> Result<(), chrono_slots::PeriodError> {
+        let tz = chrono_tz::Asia::Tokyo;
+        let slots = vec![
+            Slot::new(
+                tz.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap(),
+                tz.with_ymd_and_hms(2024, 1, 1, 10, 30, 0).unwrap(),
+            )?,
+            Slot::new(
+                tz.with_ymd_and_hms(2024, 1, 1, 11, 0, 0).unwrap(),
+                tz.with_ymd_and_hms(2024, 1, 1, 12, 0, 0).unwrap(),
+            )?,
+        ];
+
+        assert_eq!(
+            render(&slots, OutputFormat::Text),
+            "start: 2024-01-01 09:00:00, end: 2024-01-01 10:30:00, duration: 1h 30m\n\
+start: 2024-01-01 11:00:00, end: 2024-01-01 12:00:00, duration: 1h 0m"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&render(&slots, OutputFormat::Json))
+            .map_err(|err| chrono_slots::PeriodError::InvalidFormat(err.to_string()))?;
+        assert_eq!(
+            json,
+            json!([
+                {"start": "2024-01-01T09:00:00+09:00", "end": "2024-01-01T10:30:00+09:00", "minutes": 90},
+                {"start": "2024-01-01T11:00:00+09:00", "end": "2024-01-01T12:00:00+09:00", "minutes": 60},
+            ])
+        );
+        Ok(())
+    }
+}
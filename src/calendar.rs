@@ -0,0 +1,11 @@
+pub use self::cache::*;
+pub use self::events::*;
+pub use self::index::*;
+pub use self::shared::*;
+pub use self::simulate::*;
+pub mod cache;
+pub mod events;
+pub mod index;
+pub mod reserve;
+pub mod shared;
+pub mod simulate;
@@ -0,0 +1,196 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use crate::{
+    finder::{filter::clip, find::find_slots, options::FindOptions},
+    periods::{
+        block::Block,
+        period::{Output, Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+use super::{events::CalendarEvent, index::Calendar};
+
+// The timezone is a part of the key, since the slots are returned in the timezone of the span.
+type Key = (DateTime<Tz>, DateTime<Tz>, Tz);
+
+/// A Calendar memoizing the free time of the spans searched, e.g. the same weekly view
+/// requested on every page load. A change of the blocks invalidates only the spans it overlaps.
+/// The free time is cached before the options, and the options are applied on each search,
+/// so options relative to now stay correct.
+#[derive(Debug, Default)]
+pub struct CachedCalendar {
+    calendar: Calendar,
+    gaps: Mutex<HashMap<Key, Vec<Slot>>>,
+}
+
+fn key(span: &Span) -> Key {
+    (span.start(), span.end(), span.start().timezone())
+}
+
+impl CachedCalendar {
+    /// constructor
+    pub fn new(calendar: Calendar) -> Self {
+        CachedCalendar {
+            calendar,
+            gaps: Mutex::default(),
+        }
+    }
+
+    /// The calendar.
+    pub fn calendar(&self) -> &Calendar {
+        &self.calendar
+    }
+
+    /// Number of spans cached.
+    pub fn cached(&self) -> usize {
+        self.gaps.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// Add the block, and invalidate the spans it overlaps.
+    pub fn insert(&mut self, block: Block) -> bool {
+        self.invalidate(&block);
+        self.calendar.insert(block)
+    }
+
+    /// Remove the block, and invalidate the spans it overlaps.
+    pub fn remove<P: Period>(&mut self, block: &P) -> bool {
+        self.invalidate(block);
+        self.calendar.remove(block)
+    }
+
+    /// Apply the event, and invalidate the spans it overlaps.
+    pub fn apply(&mut self, event: &CalendarEvent) -> Result<bool, PeriodError> {
+        let changed = self.calendar.apply(event)?;
+        let times = match *event {
+            CalendarEvent::BlockAdded { start, end }
+            | CalendarEvent::BlockRemoved { start, end } => {
+                vec![(start, end)]
+            }
+            CalendarEvent::BlockMoved {
+                start,
+                end,
+                to_start,
+                to_end,
+            } => vec![(start, end), (to_start, to_end)],
+        };
+        for (start, end) in times {
+            self.invalidate(&Block::new(start, end)?);
+        }
+        Ok(changed)
+    }
+
+    // Drop the cached spans overlapping the period.
+    fn invalidate<P: Period>(&mut self, period: &P) {
+        let gaps = self.gaps.get_mut().unwrap_or_else(|e| e.into_inner());
+        gaps.retain(|(start, end, _), _| period.end() <= *start || *end <= period.start());
+    }
+
+    /// Free time of the span, filtered and adjusted by the options. Same as Calendar::find_with.
+    pub fn find_with<Out: Output>(
+        &self,
+        span: Span,
+        options: &FindOptions,
+    ) -> Result<Vec<Out>, PeriodError> {
+        if options.drops_past_inputs() {
+            // The cached free time has the past blocks in it.
+            return self.calendar.find_with(span, options);
+        }
+        options.validate()?;
+        options.check_inputs(&span, &self.calendar.blocks_in(&span))?;
+        let Some(prepared) = options.prepare(span.clone())? else {
+            return Ok(Vec::new());
+        };
+        options.cancel().check()?;
+        let gaps = self.gaps(span)?;
+        let gaps = gaps.iter().filter_map(|gap| clip(gap, &prepared)).collect();
+        let slots = options.apply(&prepared, gaps);
+        Ok(slots.into_iter().map(Out::create_from_slot).collect())
+    }
+
+    // The free time of the whole span, from the cache if it is there.
+    fn gaps(&self, span: Span) -> Result<Vec<Slot>, PeriodError> {
+        let key = key(&span);
+        if let Some(gaps) = self
+            .gaps
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&key)
+        {
+            return Ok(gaps.clone());
+        }
+        let gaps = find_slots(span.clone(), self.calendar.blocks_in(&span))?;
+        self.gaps
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(key, gaps.clone());
+        Ok(gaps)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn dt(day: u32, hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, day, hour, 0, 0)
+            .unwrap()
+    }
+
+    fn times(slots: &[Slot]) -> Vec<(DateTime<Tz>, DateTime<Tz>)> {
+        slots.iter().map(|s| (s.start(), s.end())).collect()
+    }
+
+    #[test]
+    fn test_cached_calendar() -> Result<(), PeriodError> {
+        let calendar = Calendar::from_inputs(&[Block::new(dt(1, 10), dt(1, 11))?])?;
+        let mut cached = CachedCalendar::new(calendar);
+        let day = |d| Span::new(dt(d, 9), dt(d, 17));
+        let options = FindOptions::new();
+
+        let slots: Vec<Slot> = cached.find_with(day(1)?, &options)?;
+        assert_eq!(
+            times(&slots),
+            vec![(dt(1, 9), dt(1, 10)), (dt(1, 11), dt(1, 17))]
+        );
+        let _: Vec<Slot> = cached.find_with(day(2)?, &options)?;
+        let _: Vec<Slot> = cached.find_with(day(2)?, &options)?;
+        assert_eq!(cached.cached(), 2);
+
+        let in_utc = Span::new(
+            dt(1, 9).with_timezone(&Tz::UTC),
+            dt(1, 17).with_timezone(&Tz::UTC),
+        )?;
+        let slots: Vec<Slot> = cached.find_with(in_utc, &options)?;
+        assert_eq!(slots[0].start().timezone(), Tz::UTC);
+        assert_eq!(cached.cached(), 3);
+
+        cached.insert(Block::new(dt(2, 12), dt(2, 13))?);
+        assert_eq!(cached.cached(), 2, "Only the second day is invalidated");
+        let slots: Vec<Slot> = cached.find_with(day(2)?, &options)?;
+        assert_eq!(slots.len(), 2);
+
+        let with_min = FindOptions::new().min_duration(Duration::hours(2));
+        let slots: Vec<Slot> = cached.find_with(day(1)?, &with_min)?;
+        assert_eq!(
+            times(&slots),
+            vec![(dt(1, 11), dt(1, 17))],
+            "Options are applied to cached gaps"
+        );
+
+        cached.apply(&CalendarEvent::moved(
+            &Block::new(dt(1, 10), dt(1, 11))?,
+            &Block::new(dt(3, 10), dt(3, 11))?,
+        ))?;
+        assert_eq!(cached.cached(), 1, "Both days of the move are invalidated");
+        let slots: Vec<Slot> = cached.find_with(day(1)?, &options)?;
+        assert_eq!(times(&slots), vec![(dt(1, 9), dt(1, 17))]);
+        Ok(())
+    }
+}
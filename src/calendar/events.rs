@@ -0,0 +1,237 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::periods::{
+    block::Block,
+    period::{Period, PeriodError},
+};
+
+use super::index::Calendar;
+
+/// A change of the blocks of a calendar, to rebuild a Calendar from an event log.
+/// With the serde feature, times are serialized as RFC 3339 with the timezone name in brackets,
+/// e.g. "2024-01-01T09:00:00+09:00[Asia/Tokyo]".
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type"))]
+pub enum CalendarEvent {
+    /// A block was added.
+    BlockAdded {
+        #[cfg_attr(feature = "serde", serde(with = "crate::periods::zoned"))]
+        start: DateTime<Tz>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::periods::zoned"))]
+        end: DateTime<Tz>,
+    },
+    /// A block was removed.
+    BlockRemoved {
+        #[cfg_attr(feature = "serde", serde(with = "crate::periods::zoned"))]
+        start: DateTime<Tz>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::periods::zoned"))]
+        end: DateTime<Tz>,
+    },
+    /// A block was moved to new times.
+    BlockMoved {
+        #[cfg_attr(feature = "serde", serde(with = "crate::periods::zoned"))]
+        start: DateTime<Tz>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::periods::zoned"))]
+        end: DateTime<Tz>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::periods::zoned"))]
+        to_start: DateTime<Tz>,
+        #[cfg_attr(feature = "serde", serde(with = "crate::periods::zoned"))]
+        to_end: DateTime<Tz>,
+    },
+}
+
+impl CalendarEvent {
+    /// The event of adding the block.
+    pub fn added<P: Period>(block: &P) -> Self {
+        CalendarEvent::BlockAdded {
+            start: block.start(),
+            end: block.end(),
+        }
+    }
+
+    /// The event of removing the block.
+    pub fn removed<P: Period>(block: &P) -> Self {
+        CalendarEvent::BlockRemoved {
+            start: block.start(),
+            end: block.end(),
+        }
+    }
+
+    /// The event of moving the block to the times of another.
+    pub fn moved<P: Period, Q: Period>(from: &P, to: &Q) -> Self {
+        CalendarEvent::BlockMoved {
+            start: from.start(),
+            end: from.end(),
+            to_start: to.start(),
+            to_end: to.end(),
+        }
+    }
+}
+
+impl Calendar {
+    /// constructor, which applies the events in order.
+    pub fn from_events<'a>(
+        events: impl IntoIterator<Item = &'a CalendarEvent>,
+    ) -> Result<Self, PeriodError> {
+        let mut calendar = Calendar::new();
+        for event in events {
+            calendar.apply(event)?;
+        }
+        Ok(calendar)
+    }
+
+    /// Apply the event. False if it changed nothing: adding a block already in, or removing
+    /// (or moving) a block not in, so replaying an event twice is harmless.
+    /// A moved block that is not in is still added at its new times.
+    pub fn apply(&mut self, event: &CalendarEvent) -> Result<bool, PeriodError> {
+        match *event {
+            CalendarEvent::BlockAdded { start, end } => Ok(self.insert(Block::new(start, end)?)),
+            CalendarEvent::BlockRemoved { start, end } => Ok(self.remove(&Block::new(start, end)?)),
+            CalendarEvent::BlockMoved {
+                start,
+                end,
+                to_start,
+                to_end,
+            } => {
+                let (from, to) = (Block::new(start, end)?, Block::new(to_start, to_end)?);
+                Ok(self.change(|calendar| {
+                    let removed = calendar.remove(&from);
+                    let inserted = calendar.insert(to);
+                    removed || inserted
+                }))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_calendar_apply() -> Result<(), PeriodError> {
+        let a = Block::new(dt(9), dt(10))?;
+        let b = Block::new(dt(11), dt(12))?;
+        let c = Block::new(dt(13), dt(14))?;
+        let d = Block::new(dt(15), dt(16))?;
+
+        struct TestCase {
+            name: &'static str,
+            event: CalendarEvent,
+            expected: bool,
+            expected_starts: Vec<DateTime<Tz>>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Add",
+                event: CalendarEvent::added(&b),
+                expected: true,
+                expected_starts: vec![dt(9), dt(11)],
+            },
+            TestCase {
+                name: "Add again",
+                event: CalendarEvent::added(&b),
+                expected: false,
+                expected_starts: vec![dt(9), dt(11)],
+            },
+            TestCase {
+                name: "Move",
+                event: CalendarEvent::moved(&a, &c),
+                expected: true,
+                expected_starts: vec![dt(11), dt(13)],
+            },
+            TestCase {
+                name: "Remove",
+                event: CalendarEvent::removed(&b),
+                expected: true,
+                expected_starts: vec![dt(13)],
+            },
+            TestCase {
+                name: "Remove missing",
+                event: CalendarEvent::removed(&b),
+                expected: false,
+                expected_starts: vec![dt(13)],
+            },
+            TestCase {
+                name: "Move missing",
+                event: CalendarEvent::moved(&a, &d),
+                expected: true,
+                expected_starts: vec![dt(13), dt(15)],
+            },
+            TestCase {
+                name: "Move onto a block already in",
+                event: CalendarEvent::moved(&c, &d),
+                expected: true,
+                expected_starts: vec![dt(15)],
+            },
+            TestCase {
+                name: "Move missing onto a block already in",
+                event: CalendarEvent::moved(&a, &d),
+                expected: false,
+                expected_starts: vec![dt(15)],
+            },
+        ];
+
+        let mut calendar = Calendar::from_events(&[CalendarEvent::added(&a)])?;
+        for case in cases {
+            assert_eq!(calendar.apply(&case.event)?, case.expected, "{}", case.name);
+            let starts = calendar
+                .blocks()
+                .iter()
+                .map(|b| b.start())
+                .collect::<Vec<_>>();
+            assert_eq!(starts, case.expected_starts, "{}", case.name);
+        }
+
+        let invalid = CalendarEvent::BlockAdded {
+            start: dt(10),
+            end: dt(9),
+        };
+        assert!(calendar.apply(&invalid).is_err());
+        Ok(())
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_calendar_event_serde() -> Result<(), serde_json::Error> {
+        let event = CalendarEvent::BlockAdded {
+            start: dt(9),
+            end: dt(10),
+        };
+        let json = serde_json::to_string(&event)?;
+        assert_eq!(
+            json,
+            r#"{"type":"BlockAdded","start":"2024-01-01T09:00:00+09:00[Asia/Tokyo]","end":"2024-01-01T10:00:00+09:00[Asia/Tokyo]"}"#
+        );
+        let read: CalendarEvent = serde_json::from_str(&json)?;
+        assert_eq!(read, event);
+
+        let utc: CalendarEvent = serde_json::from_str(
+            r#"{"type":"BlockRemoved","start":"2024-01-01T00:00:00Z","end":"2024-01-01T01:00:00Z"}"#,
+        )?;
+        assert_eq!(
+            utc,
+            CalendarEvent::BlockRemoved {
+                start: dt(9).with_timezone(&Tz::UTC),
+                end: dt(10).with_timezone(&Tz::UTC),
+            }
+        );
+        assert!(serde_json::from_str::<CalendarEvent>(
+            r#"{"type":"BlockAdded","start":"2024-01-01T09:00:00+09:00[Nowhere/City]","end":"2024-01-01T10:00:00Z"}"#
+        )
+        .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,312 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use crate::{
+    finder::{find::find_with, options::FindOptions},
+    periods::{
+        block::Block,
+        period::{Input, Output, Period, PeriodError},
+        span::Span,
+    },
+};
+
+/// Blocks kept sorted by start time, to search free time repeatedly.
+/// A Calendar is Send + Sync, so it can be shared as Arc<Calendar> across worker tasks.
+/// Clones are cheap: the blocks are shared until one of the clones is modified (copy on write).
+#[derive(Debug, Clone, Default)]
+pub struct Calendar {
+    blocks: Arc<Vec<Block>>,
+    history: History,
+}
+
+// Versions of the blocks before the last changes (oldest first), and the ones undone.
+#[derive(Debug, Clone, Default)]
+struct History {
+    limit: usize,
+    undo: VecDeque<Arc<Vec<Block>>>,
+    redo: Vec<Arc<Vec<Block>>>,
+}
+
+impl Calendar {
+    /// constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// constructor from inputs. Identical blocks are kept once.
+    pub fn from_inputs<In: Input>(inputs: &[In]) -> Result<Self, PeriodError> {
+        let mut calendar = Calendar::new();
+        for input in inputs {
+            calendar.insert(input.to_block()?);
+        }
+        Ok(calendar)
+    }
+
+    /// Keep up to limit changes of the blocks to undo, e.g. for interactive planning tools.
+    /// With a history, each change copies the blocks once, since the previous version is kept.
+    pub fn with_history(mut self, limit: usize) -> Self {
+        self.history.limit = limit;
+        while self.history.undo.len() > limit {
+            self.history.undo.pop_front();
+        }
+        self
+    }
+
+    // A copy sharing the blocks without the history, to make changes that are thrown away.
+    pub(crate) fn without_history(&self) -> Self {
+        Calendar {
+            blocks: Arc::clone(&self.blocks),
+            history: History::default(),
+        }
+    }
+
+    // Keep the version before a change, which makes the redo history obsolete.
+    fn record(&mut self, before: Arc<Vec<Block>>) {
+        if self.history.limit == 0 {
+            return;
+        }
+        if self.history.undo.len() == self.history.limit {
+            self.history.undo.pop_front();
+        }
+        self.history.undo.push_back(before);
+        self.history.redo.clear();
+    }
+
+    // Make the changes of update a single step of the history.
+    pub(crate) fn change<T>(&mut self, update: impl FnOnce(&mut Calendar) -> T) -> T {
+        let limit = std::mem::take(&mut self.history.limit);
+        let before = (limit > 0).then(|| Arc::clone(&self.blocks));
+        let result = update(self);
+        self.history.limit = limit;
+        if let Some(before) = before.filter(|b| !Arc::ptr_eq(b, &self.blocks)) {
+            self.record(before);
+        }
+        result
+    }
+
+    /// Revert the last change of the blocks. False if there is none.
+    pub fn undo(&mut self) -> bool {
+        match self.history.undo.pop_back() {
+            Some(before) => {
+                let after = std::mem::replace(&mut self.blocks, before);
+                self.history.redo.push(after);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Make the last change undone again. False if there is none, or a change was made since.
+    pub fn redo(&mut self) -> bool {
+        match self.history.redo.pop() {
+            Some(after) => {
+                let before = std::mem::replace(&mut self.blocks, after);
+                self.history.undo.push_back(before);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The blocks, sorted by start and end time.
+    pub fn blocks(&self) -> &[Block] {
+        &self.blocks
+    }
+
+    /// Number of blocks.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Whether there is no block.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// Add the block. False if the same block (same start and end) is already in.
+    pub fn insert(&mut self, block: Block) -> bool {
+        let key = (block.start(), block.end());
+        match self
+            .blocks
+            .binary_search_by_key(&key, |b| (b.start(), b.end()))
+        {
+            Ok(_) => false,
+            Err(i) => {
+                self.record(Arc::clone(&self.blocks));
+                Arc::make_mut(&mut self.blocks).insert(i, block);
+                true
+            }
+        }
+    }
+
+    /// Remove the block with the same start and end. False if there is none.
+    pub fn remove<P: Period>(&mut self, block: &P) -> bool {
+        let key = (block.start(), block.end());
+        match self
+            .blocks
+            .binary_search_by_key(&key, |b| (b.start(), b.end()))
+        {
+            Ok(i) => {
+                self.record(Arc::clone(&self.blocks));
+                Arc::make_mut(&mut self.blocks).remove(i);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// A copy with the changes made by update. The blocks are copied once, and self is unchanged,
+    /// so readers of a shared Calendar are never blocked while the next version is built.
+    pub fn updated(&self, update: impl FnOnce(&mut Calendar)) -> Calendar {
+        let mut next = self.clone();
+        update(&mut next);
+        next
+    }
+
+    /// The blocks overlapping the span.
+    pub fn blocks_in(&self, span: &Span) -> Vec<Block> {
+        let end = self.blocks.partition_point(|b| b.start() < span.end());
+        self.blocks[..end]
+            .iter()
+            .filter(|b| span.start() < b.end())
+            .cloned()
+            .collect()
+    }
+
+    /// Free time of the span. Same as find with all the blocks.
+    pub fn find<Out: Output>(&self, span: Span) -> Result<Vec<Out>, PeriodError> {
+        self.find_with(span, &FindOptions::new())
+    }
+
+    /// Free time of the span, filtered and adjusted by the options. Same as find_with with all the blocks.
+    pub fn find_with<Out: Output>(
+        &self,
+        span: Span,
+        options: &FindOptions,
+    ) -> Result<Vec<Out>, PeriodError> {
+        let blocks = self.blocks_in(&span);
+        find_with(span, blocks, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::slot::Slot;
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+    use std::thread;
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    fn block(start: u32, end: u32) -> Result<Block, PeriodError> {
+        Block::new(dt(start), dt(end))
+    }
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn test_calendar_insert_remove() -> Result<(), PeriodError> {
+        let mut calendar = Calendar::from_inputs(&[block(13, 14)?, block(9, 10)?, block(13, 14)?])?;
+        assert_eq!(calendar.len(), 2);
+        assert!(calendar.insert(block(11, 12)?));
+        assert!(!calendar.insert(block(11, 12)?));
+        let starts = calendar
+            .blocks()
+            .iter()
+            .map(|b| b.start())
+            .collect::<Vec<_>>();
+        assert_eq!(starts, vec![dt(9), dt(11), dt(13)]);
+
+        assert!(calendar.remove(&block(11, 12)?));
+        assert!(!calendar.remove(&block(11, 13)?));
+        assert_eq!(calendar.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_calendar_undo_redo() -> Result<(), PeriodError> {
+        let starts = |calendar: &Calendar| {
+            calendar
+                .blocks()
+                .iter()
+                .map(|b| b.start())
+                .collect::<Vec<_>>()
+        };
+        let mut calendar = Calendar::from_inputs(&[block(9, 10)?])?.with_history(2);
+        assert!(!calendar.undo(), "Changes before the history are kept");
+
+        calendar.insert(block(11, 12)?);
+        calendar.insert(block(13, 14)?);
+        calendar.remove(&block(9, 10)?);
+        assert!(!calendar.insert(block(13, 14)?), "No change, no step");
+        assert!(calendar.undo());
+        assert!(calendar.undo());
+        assert_eq!(starts(&calendar), vec![dt(9), dt(11)]);
+        assert!(!calendar.undo(), "Bounded to 2 changes");
+
+        assert!(calendar.redo());
+        assert_eq!(starts(&calendar), vec![dt(9), dt(11), dt(13)]);
+        calendar.insert(block(15, 16)?);
+        assert!(!calendar.redo(), "A new change drops the redo history");
+
+        // A reservation is a single step.
+        calendar.reserve(&[block(7, 8)?, block(17, 18)?])?;
+        assert!(calendar.undo());
+        assert_eq!(starts(&calendar), vec![dt(9), dt(11), dt(13), dt(15)]);
+        let mut copy = calendar.without_history();
+        assert_eq!(starts(&copy), starts(&calendar));
+        assert!(!copy.undo(), "The history is not copied");
+
+        let mut plain = Calendar::new();
+        plain.insert(block(9, 10)?);
+        assert!(!plain.undo(), "No history by default");
+        Ok(())
+    }
+
+    #[test]
+    fn test_calendar_find() -> Result<(), PeriodError> {
+        let calendar = Calendar::from_inputs(&[block(1, 3)?, block(10, 11)?, block(16, 20)?])?;
+        let span = Span::new(dt(9), dt(17))?;
+        assert_eq!(calendar.blocks_in(&span).len(), 2);
+
+        let slots: Vec<Slot> = calendar.find(span)?;
+        let actual = slots
+            .iter()
+            .map(|s| (s.start(), s.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, vec![(dt(9), dt(10)), (dt(11), dt(16))]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_calendar_shared() -> Result<(), PeriodError> {
+        assert_send_sync::<Calendar>();
+
+        let shared = Arc::new(Calendar::from_inputs(&[block(10, 11)?])?);
+        let next = shared.updated(|c| {
+            c.insert(Block::new(dt(12), dt(13)).unwrap());
+        });
+        assert_eq!((shared.len(), next.len()), (1, 2));
+
+        let copy = (*shared).clone();
+        assert!(Arc::ptr_eq(&copy.blocks, &shared.blocks));
+
+        let handles = (0..4)
+            .map(|_| {
+                let calendar = Arc::clone(&shared);
+                thread::spawn(move || {
+                    let span = Span::new(dt(9), dt(17))?;
+                    calendar.find::<Slot>(span).map(|slots| slots.len())
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap()?, 2);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,109 @@
+use crate::periods::{
+    block::Block,
+    period::{Period, PeriodError},
+    span::Span,
+};
+
+use super::index::Calendar;
+
+impl Calendar {
+    /// Claim the slots as blocks, all or nothing, e.g. the segments of a split booking.
+    /// Fails with Conflict and the index of the first slot overlapping a block or an earlier slot,
+    /// and then nothing is added. Returns the blocks added, which are undone as one change.
+    /// On a SyncCalendar, reserve within update, so no other update comes between the check and the claim.
+    pub fn reserve<P: Period>(&mut self, slots: &[P]) -> Result<Vec<Block>, PeriodError> {
+        let mut claimed: Vec<Block> = Vec::with_capacity(slots.len());
+        for (index, slot) in slots.iter().enumerate() {
+            let span = Span::new(slot.start(), slot.end())?;
+            let taken = !self.blocks_in(&span).is_empty()
+                || claimed
+                    .iter()
+                    .any(|b| b.start() < slot.end() && slot.start() < b.end());
+            if taken {
+                return Err(PeriodError::Conflict { index });
+            }
+            claimed.push(Block::new(slot.start(), slot.end())?);
+        }
+        self.change(|calendar| {
+            for block in &claimed {
+                calendar.insert(block.clone());
+            }
+        });
+        Ok(claimed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{calendar::shared::SyncCalendar, periods::slot::Slot};
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    fn slot(start: u32, end: u32) -> Result<Slot, PeriodError> {
+        Slot::new(dt(start), dt(end))
+    }
+
+    #[test]
+    fn test_calendar_reserve() -> Result<(), PeriodError> {
+        let calendar = || Calendar::from_inputs(&[Block::new(dt(10), dt(11))?]);
+
+        struct TestCase {
+            name: &'static str,
+            slots: Vec<Slot>,
+            expected: Result<usize, usize>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Non-contiguous slots claimed together",
+                slots: vec![slot(9, 10)?, slot(11, 12)?, slot(14, 15)?],
+                expected: Ok(4),
+            },
+            TestCase {
+                name: "A slot overlaps a block",
+                slots: vec![slot(8, 9)?, slot(10, 12)?],
+                expected: Err(1),
+            },
+            TestCase {
+                name: "Slots overlap each other",
+                slots: vec![slot(12, 14)?, slot(8, 9)?, slot(13, 15)?],
+                expected: Err(2),
+            },
+        ];
+
+        for case in cases {
+            let mut calendar = calendar()?;
+            match (calendar.reserve(&case.slots), case.expected) {
+                (Ok(blocks), Ok(len)) => {
+                    assert_eq!(blocks.len(), case.slots.len(), "{}", case.name);
+                    assert_eq!(calendar.len(), len, "{}", case.name);
+                }
+                (Err(err), Err(index)) => {
+                    assert_eq!(
+                        err.to_string(),
+                        PeriodError::Conflict { index }.to_string(),
+                        "{}",
+                        case.name
+                    );
+                    assert_eq!(calendar.len(), 1, "Nothing added: {}", case.name);
+                }
+                (actual, _) => panic!("{}: {:?}", case.name, actual),
+            }
+        }
+
+        let shared = SyncCalendar::new(calendar()?);
+        shared.update(|c| c.reserve(&[slot(9, 10)?, slot(11, 12)?]))?;
+        assert!(shared
+            .update(|c| c.reserve(&[slot(12, 13)?, slot(9, 10)?]))
+            .is_err());
+        assert_eq!(shared.snapshot().len(), 3);
+        Ok(())
+    }
+}
@@ -0,0 +1,298 @@
+use std::{
+    fmt::{self, Debug},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use crate::{
+    finder::options::FindOptions,
+    periods::{
+        period::{Output, Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+use super::index::Calendar;
+
+/// How the slots of a watched span changed by an update.
+#[derive(Debug, Clone, Default)]
+pub struct SlotDelta {
+    /// Slots found after the update but not before.
+    pub opened: Vec<Slot>,
+    /// Slots found before the update but not after.
+    pub closed: Vec<Slot>,
+}
+
+impl SlotDelta {
+    // The slots only in after are opened, and the slots only in before are closed.
+    pub(crate) fn between(before: &[Slot], after: &[Slot]) -> Self {
+        let missing = |slots: &[Slot], other: &[Slot]| -> Vec<Slot> {
+            slots
+                .iter()
+                .filter(|s| !other.iter().any(|o| same_times(s, o)))
+                .cloned()
+                .collect()
+        };
+        SlotDelta {
+            opened: missing(after, before),
+            closed: missing(before, after),
+        }
+    }
+
+    /// Whether nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.opened.is_empty() && self.closed.is_empty()
+    }
+}
+
+fn same_times(a: &Slot, b: &Slot) -> bool {
+    a.start() == b.start() && a.end() == b.end()
+}
+
+/// Identifies a watcher to remove it with unwatch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchId(usize);
+
+type Callback = Arc<dyn Fn(&SlotDelta) + Send + Sync>;
+
+struct Watcher {
+    id: WatchId,
+    span: Span,
+    options: FindOptions,
+    callback: Callback,
+}
+
+impl Debug for Watcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Watcher")
+            .field("id", &self.id)
+            .field("span", &self.span)
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+/// A Calendar that can be read and updated concurrently, e.g. queried by web handlers
+/// while a sync job ingests updates. Readers search a snapshot, and are blocked only
+/// while an update swaps in the next version, never while it is built.
+#[derive(Debug, Default)]
+pub struct SyncCalendar {
+    current: RwLock<Arc<Calendar>>,
+    // Serializes updates, so none of them is lost, and holds the watchers notified by them.
+    writer: Mutex<Watchers>,
+}
+
+#[derive(Debug, Default)]
+struct Watchers {
+    next_id: usize,
+    watchers: Vec<Watcher>,
+}
+
+impl SyncCalendar {
+    /// constructor
+    pub fn new(calendar: Calendar) -> Self {
+        SyncCalendar {
+            current: RwLock::new(Arc::new(calendar)),
+            writer: Mutex::new(Watchers::default()),
+        }
+    }
+
+    /// The current version. It does not change while it is held, even if the calendar is updated.
+    pub fn snapshot(&self) -> Arc<Calendar> {
+        Arc::clone(&self.current.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Update the calendar. The blocks are copied once, and the new version replaces the current one.
+    /// Then the watchers whose slots changed are called. They are called after the update is done,
+    /// so they may update or watch the calendar, but deltas of concurrent updates may arrive out of order.
+    /// A watcher whose search fails (e.g. an option rejecting the span) is skipped for the update.
+    pub fn update<T>(&self, update: impl FnOnce(&mut Calendar) -> T) -> T {
+        let writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let previous = self.snapshot();
+        let mut next = (*previous).clone();
+        let result = update(&mut next);
+        let next = Arc::new(next);
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = Arc::clone(&next);
+
+        let mut notifications = Vec::new();
+        for watcher in &writer.watchers {
+            let find = |calendar: &Calendar| -> Result<Vec<Slot>, PeriodError> {
+                calendar.find_with(watcher.span.clone(), &watcher.options)
+            };
+            let delta = match (find(&previous), find(&next)) {
+                (Ok(before), Ok(after)) => SlotDelta::between(&before, &after),
+                (Err(_err), _) | (_, Err(_err)) => {
+                    warn!(watcher = watcher.id.0, error = %_err, "watcher skipped");
+                    continue;
+                }
+            };
+            if !delta.is_empty() {
+                notifications.push((Arc::clone(&watcher.callback), delta));
+            }
+        }
+        drop(writer);
+        for (callback, delta) in notifications {
+            callback(&delta);
+        }
+        result
+    }
+
+    /// Call the callback with the delta of the slots of the span whenever an update changes them,
+    /// e.g. to push "a new slot opened up" notifications. Send to a channel in the callback to
+    /// receive the deltas elsewhere.
+    pub fn watch(
+        &self,
+        span: Span,
+        options: FindOptions,
+        callback: impl Fn(&SlotDelta) + Send + Sync + 'static,
+    ) -> WatchId {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let id = WatchId(writer.next_id);
+        writer.next_id += 1;
+        writer.watchers.push(Watcher {
+            id,
+            span,
+            options,
+            callback: Arc::new(callback),
+        });
+        id
+    }
+
+    /// Stop calling the watcher. False if it is not watching.
+    pub fn unwatch(&self, id: WatchId) -> bool {
+        let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+        let len = writer.watchers.len();
+        writer.watchers.retain(|w| w.id != id);
+        writer.watchers.len() < len
+    }
+
+    /// Free time of the span in the current version.
+    pub fn find<Out: Output>(&self, span: Span) -> Result<Vec<Out>, PeriodError> {
+        self.snapshot().find(span)
+    }
+
+    /// Free time of the span in the current version, filtered and adjusted by the options.
+    pub fn find_with<Out: Output>(
+        &self,
+        span: Span,
+        options: &FindOptions,
+    ) -> Result<Vec<Out>, PeriodError> {
+        self.snapshot().find_with(span, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{finder::options::HorizonPolicy, periods::block::Block};
+    use chrono::{DateTime, Duration, TimeZone};
+    use chrono_tz::Tz;
+    use std::{sync::mpsc, thread};
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sync_calendar() -> Result<(), PeriodError> {
+        let calendar = Arc::new(SyncCalendar::default());
+        let before = calendar.snapshot();
+
+        let writers = (0..8)
+            .map(|i| {
+                let calendar = Arc::clone(&calendar);
+                thread::spawn(move || {
+                    let start = dt(9) + Duration::minutes(30 * i);
+                    let block = Block::new(start, start + Duration::minutes(15))?;
+                    Ok(calendar.update(|c| c.insert(block)))
+                })
+            })
+            .collect::<Vec<_>>();
+        let readers = (0..8)
+            .map(|_| {
+                let calendar = Arc::clone(&calendar);
+                thread::spawn(move || {
+                    let slots: Vec<Slot> = calendar.find(Span::new(dt(9), dt(17))?)?;
+                    Ok(slots.len())
+                })
+            })
+            .collect::<Vec<_>>();
+        for writer in writers {
+            assert!(writer.join().unwrap()?);
+        }
+        for reader in readers {
+            let found: Result<usize, PeriodError> = reader.join().unwrap();
+            assert!((1..=9).contains(&found?));
+        }
+
+        assert_eq!(calendar.snapshot().len(), 8, "No update is lost");
+        assert!(before.is_empty(), "A snapshot does not change");
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_calendar_watch() -> Result<(), PeriodError> {
+        let calendar = SyncCalendar::new(Calendar::from_inputs(&[Block::new(dt(10), dt(11))?])?);
+        let (sender, receiver) = mpsc::channel();
+        let id = calendar.watch(
+            Span::new(dt(9), dt(12))?,
+            FindOptions::new(),
+            move |delta| {
+                let times = |slots: &[Slot]| {
+                    slots
+                        .iter()
+                        .map(|s| (s.start(), s.end()))
+                        .collect::<Vec<_>>()
+                };
+                sender
+                    .send((times(&delta.opened), times(&delta.closed)))
+                    .unwrap();
+            },
+        );
+
+        calendar.update(|c| c.remove(&Block::new(dt(10), dt(11)).unwrap()));
+        assert_eq!(
+            receiver.try_recv().ok(),
+            Some((
+                vec![(dt(9), dt(12))],
+                vec![(dt(9), dt(10)), (dt(11), dt(12))]
+            ))
+        );
+
+        calendar.update(|c| c.insert(Block::new(dt(13), dt(14)).unwrap()));
+        assert!(receiver.try_recv().is_err(), "Outside of the span");
+
+        assert!(calendar.unwatch(id));
+        assert!(!calendar.unwatch(id));
+        calendar.update(|c| c.insert(Block::new(dt(9), dt(10)).unwrap()));
+        assert!(receiver.try_recv().is_err(), "Unwatched");
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_calendar_watch_reentrant() -> Result<(), PeriodError> {
+        let calendar = Arc::new(SyncCalendar::default());
+        let (sender, receiver) = mpsc::channel();
+        let inner = Arc::clone(&calendar);
+        calendar.watch(Span::new(dt(9), dt(12))?, FindOptions::new(), move |_| {
+            // Updating and unwatching from a callback does not deadlock.
+            if inner.snapshot().len() == 1 {
+                inner.update(|c| c.insert(Block::new(dt(11), dt(12)).unwrap()));
+            }
+            sender.send(inner.unwatch(WatchId(usize::MAX))).unwrap();
+        });
+        // Fails for any span, so it must be skipped instead of told that all slots closed.
+        calendar.watch(
+            Span::new(dt(9), dt(12))?,
+            FindOptions::new().horizon(Duration::minutes(1), HorizonPolicy::Reject),
+            |_| panic!("called with a failed search"),
+        );
+
+        calendar.update(|c| c.insert(Block::new(dt(9), dt(10)).unwrap()));
+        assert_eq!(receiver.try_iter().count(), 2);
+        assert_eq!(calendar.snapshot().len(), 2);
+        Ok(())
+    }
+}
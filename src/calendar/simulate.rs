@@ -0,0 +1,80 @@
+use crate::periods::{
+    period::{Input, PeriodError},
+    slot::Slot,
+    span::Span,
+};
+
+use super::{index::Calendar, shared::SlotDelta};
+
+/// The free time of a span as if tentative events were accepted.
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    /// Slots of the span with the tentative events.
+    pub slots: Vec<Slot>,
+    /// How the slots differ from the current ones.
+    pub delta: SlotDelta,
+}
+
+impl Calendar {
+    /// Preview the impact of accepting the tentative events on the free time of the span.
+    /// The calendar is not changed, and its undo history is not copied.
+    pub fn simulate_with<In: Input>(
+        &self,
+        extra_blocks: &[In],
+        span: Span,
+    ) -> Result<Simulation, PeriodError> {
+        let mut tentative = self.without_history();
+        for input in extra_blocks {
+            tentative.insert(input.to_block()?);
+        }
+        let current: Vec<Slot> = self.find(span.clone())?;
+        let slots: Vec<Slot> = tentative.find(span)?;
+        Ok(Simulation {
+            delta: SlotDelta::between(&current, &slots),
+            slots,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::{block::Block, period::Period};
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_calendar_simulate_with() -> Result<(), PeriodError> {
+        let mut calendar = Calendar::from_inputs(&[Block::new(dt(12), dt(13))?])?.with_history(1);
+        let span = Span::new(dt(9), dt(17))?;
+        let times = |slots: &[Slot]| {
+            slots
+                .iter()
+                .map(|s| (s.start(), s.end()))
+                .collect::<Vec<_>>()
+        };
+
+        let simulation = calendar.simulate_with(&[Block::new(dt(10), dt(11))?], span.clone())?;
+        assert_eq!(
+            times(&simulation.slots),
+            vec![(dt(9), dt(10)), (dt(11), dt(12)), (dt(13), dt(17))]
+        );
+        assert_eq!(times(&simulation.delta.closed), vec![(dt(9), dt(12))]);
+        assert_eq!(
+            times(&simulation.delta.opened),
+            vec![(dt(9), dt(10)), (dt(11), dt(12))]
+        );
+        assert_eq!(calendar.len(), 1, "Not changed");
+
+        let simulation = calendar.simulate_with(&[Block::new(dt(18), dt(19))?], span)?;
+        assert!(simulation.delta.is_empty());
+        assert!(!calendar.undo(), "The history is not changed");
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use alloc::sync::Arc;
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use crate::periods::period::PeriodError;
+
+/// Cancels long computations from another thread, e.g. when the client of a request disconnects.
+/// Clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    /// constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the computations watching this token.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether cancel has been called.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// When a computation is abandoned: by a token, at a deadline (with std), or both. Never by default.
+#[derive(Debug, Clone, Default)]
+pub struct Cancellation {
+    token: Option<CancelToken>,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+}
+
+impl Cancellation {
+    /// constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Abandon the computation when the token is cancelled.
+    pub fn token(mut self, token: CancelToken) -> Self {
+        self.token = Some(token);
+        self
+    }
+
+    /// Abandon the computation at the deadline.
+    #[cfg(feature = "std")]
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Abandon the computation after the timeout from now.
+    #[cfg(feature = "std")]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        self.deadline(Instant::now() + timeout)
+    }
+
+    /// Fail with Cancelled or TimedOut if the computation should be abandoned.
+    pub fn check(&self) -> Result<(), PeriodError> {
+        if self.token.as_ref().is_some_and(CancelToken::is_cancelled) {
+            return Err(PeriodError::Cancelled);
+        }
+        #[cfg(feature = "std")]
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(PeriodError::TimedOut);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        finder::{find::find_with, options::FindOptions},
+        periods::{block::Block, slot::Slot, span::Span},
+    };
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_cancellation_check() {
+        let token = CancelToken::new();
+        let cancellation = Cancellation::new().token(token.clone());
+        assert_eq!(cancellation.check(), Ok(()));
+        token.clone().cancel();
+        assert!(token.is_cancelled());
+        assert_eq!(cancellation.check(), Err(PeriodError::Cancelled));
+
+        let past = Cancellation::new().deadline(Instant::now());
+        assert_eq!(past.check(), Err(PeriodError::TimedOut));
+        let future = Cancellation::new().timeout(Duration::from_secs(60));
+        assert_eq!(future.check(), Ok(()));
+        assert_eq!(Cancellation::default().check(), Ok(()));
+    }
+
+    #[test]
+    fn test_find_with_cancellation() -> Result<(), PeriodError> {
+        let dt = |hour| {
+            chrono_tz::Japan
+                .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+                .unwrap()
+        };
+        let span = || Span::new(dt(9), dt(17));
+        let blocks = || Block::new(dt(10), dt(11)).map(|b| vec![b]);
+
+        let token = CancelToken::new();
+        let options = FindOptions::new().cancellation(Cancellation::new().token(token.clone()));
+        let slots: Vec<Slot> = find_with(span()?, blocks()?, &options)?;
+        assert_eq!(slots.len(), 2);
+
+        token.cancel();
+        let actual = find_with::<Block, Slot>(span()?, blocks()?, &options);
+        assert_eq!(actual.err(), Some(PeriodError::Cancelled));
+        Ok(())
+    }
+}
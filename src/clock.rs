@@ -0,0 +1,124 @@
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Duration, Utc};
+use chrono_tz::Tz;
+
+/// Source of the current time. Features relative to "now" read it from a Clock,
+/// so they can be made deterministic in tests and replayed in simulations.
+pub trait Clock: Debug + Send + Sync {
+    /// Current time.
+    fn now(&self) -> DateTime<Tz>;
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> DateTime<Tz> {
+        (**self).now()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn now(&self) -> DateTime<Tz> {
+        (**self).now()
+    }
+}
+
+/// The system time, in the timezone.
+#[derive(Debug, Clone, Copy)]
+pub struct SystemClock {
+    tz: Tz,
+}
+
+impl SystemClock {
+    /// constructor
+    pub fn new(tz: Tz) -> Self {
+        SystemClock { tz }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock::new(Tz::UTC)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Tz> {
+        Utc::now().with_timezone(&self.tz)
+    }
+}
+
+/// A clock that stays at the given time until it is set or advanced.
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<DateTime<Tz>>,
+}
+
+impl FixedClock {
+    /// constructor
+    pub fn new(now: DateTime<Tz>) -> Self {
+        FixedClock {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// Move the clock to the given time.
+    pub fn set(&self, now: DateTime<Tz>) {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner()) = now;
+    }
+
+    /// Move the clock forward by the duration.
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|e| e.into_inner());
+        *now += duration;
+    }
+}
+
+impl Clone for FixedClock {
+    fn clone(&self) -> Self {
+        FixedClock::new(self.now())
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Tz> {
+        *self.now.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_fixed_clock() {
+        let start = chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, 9, 0, 0)
+            .unwrap();
+        let clock = FixedClock::new(start);
+        assert_eq!(clock.now(), start);
+        assert_eq!(clock.now(), start, "FixedClock should not move by itself");
+
+        clock.advance(Duration::minutes(30));
+        assert_eq!(clock.now(), start + Duration::minutes(30));
+
+        let shared: Arc<dyn Clock> = Arc::new(clock.clone());
+        clock.set(start);
+        assert_eq!(clock.now(), start);
+        assert_eq!(shared.now(), start + Duration::minutes(30));
+    }
+
+    #[test]
+    fn test_system_clock() {
+        let clock = SystemClock::new(chrono_tz::Japan);
+        let before = Utc::now();
+        let now = clock.now();
+        let after = Utc::now();
+        assert!(before <= now && now <= after);
+        assert_eq!(now.timezone(), chrono_tz::Japan);
+        assert_eq!(SystemClock::default().now().timezone(), Tz::UTC);
+    }
+}
@@ -0,0 +1,117 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::periods::period::PeriodError;
+
+// Encode a value to MessagePack, e.g. a Span, a Vec<Block>, or a FindReport to send to another service.
+// Structs are encoded as maps with field names, so readers can ignore fields added later
+// as with the JSON of FindReport.
+#[cfg(feature = "msgpack")]
+pub fn to_msgpack<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, PeriodError> {
+    rmp_serde::to_vec_named(value).map_err(|err| PeriodError::Conversion(err.to_string()))
+}
+
+// Decode a value from MessagePack. Periods are checked as by their constructors.
+#[cfg(feature = "msgpack")]
+pub fn from_msgpack<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, PeriodError> {
+    rmp_serde::from_slice(bytes).map_err(|err| PeriodError::InvalidFormat(err.to_string()))
+}
+
+// Encode a value to CBOR (RFC 8949).
+#[cfg(feature = "cbor")]
+pub fn to_cbor<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, PeriodError> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(value, &mut bytes)
+        .map_err(|err| PeriodError::Conversion(err.to_string()))?;
+    Ok(bytes)
+}
+
+// Decode a value from CBOR. Periods are checked as by their constructors.
+#[cfg(feature = "cbor")]
+pub fn from_cbor<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, PeriodError> {
+    ciborium::from_reader(bytes).map_err(|err| PeriodError::InvalidFormat(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        finder::{metrics::Metrics, schema::FindReport},
+        periods::{block::Block, period::Period, slot::Slot, span::Span},
+    };
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    type Value = (Span, Vec<Block>, FindReport);
+
+    // Times of a period as written by another service, not checked.
+    #[derive(Serialize)]
+    struct Raw {
+        start: String,
+        end: String,
+    }
+
+    #[test]
+    fn test_codec_round_trip() -> Result<(), PeriodError> {
+        struct TestCase {
+            name: &'static str,
+            encode: fn(&Value) -> Result<Vec<u8>, PeriodError>,
+            decode: fn(&[u8]) -> Result<Value, PeriodError>,
+            encode_raw: fn(&Raw) -> Result<Vec<u8>, PeriodError>,
+            decode_block: fn(&[u8]) -> Result<Block, PeriodError>,
+        }
+
+        let cases = vec![
+            #[cfg(feature = "msgpack")]
+            TestCase {
+                name: "MessagePack",
+                encode: to_msgpack,
+                decode: from_msgpack,
+                encode_raw: to_msgpack,
+                decode_block: from_msgpack,
+            },
+            #[cfg(feature = "cbor")]
+            TestCase {
+                name: "CBOR",
+                encode: to_cbor,
+                decode: from_cbor,
+                encode_raw: to_cbor,
+                decode_block: from_cbor,
+            },
+        ];
+
+        let span = Span::new(dt(9), dt(17))?;
+        let blocks = vec![
+            Block::new(dt(10), dt(11))?,
+            Block::new(
+                dt(13).with_timezone(&Tz::UTC),
+                dt(14).with_timezone(&Tz::UTC),
+            )?,
+        ];
+        let report = FindReport::new(&[Slot::new(dt(9), dt(10))?], &Metrics::default());
+        let times = |p: &dyn Period| (p.start(), p.end(), p.start().timezone());
+
+        for case in cases {
+            let value = (span.clone(), blocks.clone(), report.clone());
+            let (read_span, read_blocks, read_report) = (case.decode)(&(case.encode)(&value)?)?;
+            assert_eq!(times(&read_span), times(&span), "{}", case.name);
+            let read_times = read_blocks.iter().map(|b| times(b)).collect::<Vec<_>>();
+            let expected_times = blocks.iter().map(|b| times(b)).collect::<Vec<_>>();
+            assert_eq!(read_times, expected_times, "{}", case.name);
+            assert_eq!(read_report, report, "{}", case.name);
+
+            let backwards = Raw {
+                start: "2024-01-01T11:00:00+09:00[Asia/Tokyo]".to_string(),
+                end: "2024-01-01T10:00:00+09:00[Asia/Tokyo]".to_string(),
+            };
+            let read = (case.decode_block)(&(case.encode_raw)(&backwards)?);
+            assert!(read.is_err(), "{}: checked as by Block::new", case.name);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+use std::{ptr, slice};
+
+use chrono::{DateTime, TimeZone};
+use chrono_tz::Tz;
+
+use crate::{
+    finder::find,
+    periods::{
+        block::Block,
+        period::{Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+/// A period expressed in Unix epoch seconds.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChronoSlotsPeriod {
+    pub start: i64,
+    pub end: i64,
+}
+
+/// Slots allocated by the library. Release it with `chrono_slots_free`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct ChronoSlotsSlotArray {
+    pub data: *mut ChronoSlotsPeriod,
+    pub len: usize,
+}
+
+/// Result code of the C functions.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChronoSlotsStatus {
+    Ok = 0,
+    NullPointer = 1,
+    InvalidTime = 2,
+    OutOfRange = 3,
+    InvalidArgument = 4,
+}
+
+fn to_datetime(secs: i64) -> Result<DateTime<Tz>, ChronoSlotsStatus> {
+    Tz::UTC
+        .timestamp_opt(secs, 0)
+        .single()
+        .ok_or(ChronoSlotsStatus::OutOfRange)
+}
+
+impl From<PeriodError> for ChronoSlotsStatus {
+    fn from(err: PeriodError) -> Self {
+        match err {
+            PeriodError::StartNotBeforeEnd { .. } => ChronoSlotsStatus::InvalidTime,
+            PeriodError::OutOfRange(_) => ChronoSlotsStatus::OutOfRange,
+            PeriodError::InvalidDuration(_)
+            | PeriodError::InvalidTimezone(_)
+            | PeriodError::InvalidFormat(_)
+            | PeriodError::SpanTooLong { .. }
+            | PeriodError::SubSecond(_)
+            | PeriodError::Cancelled
+            | PeriodError::TimedOut
+            | PeriodError::OutOfSpan { .. }
+            | PeriodError::Conflict { .. }
+            | PeriodError::Conversion(_) => ChronoSlotsStatus::InvalidArgument,
+        }
+    }
+}
+
+impl From<Slot> for ChronoSlotsPeriod {
+    fn from(slot: Slot) -> Self {
+        ChronoSlotsPeriod {
+            start: slot.start().timestamp(),
+            end: slot.end().timestamp(),
+        }
+    }
+}
+
+fn find_slots(
+    span: ChronoSlotsPeriod,
+    blocks: &[ChronoSlotsPeriod],
+) -> Result<Vec<ChronoSlotsPeriod>, ChronoSlotsStatus> {
+    let span = Span::new(to_datetime(span.start)?, to_datetime(span.end)?)?;
+    let blocks = blocks
+        .iter()
+        .map(|b| Ok(Block::new(to_datetime(b.start)?, to_datetime(b.end)?)?))
+        .collect::<Result<Vec<Block>, ChronoSlotsStatus>>()?;
+    let slots: Vec<Slot> = find(span, blocks)?;
+    Ok(slots.into_iter().map(ChronoSlotsPeriod::from).collect())
+}
+
+/// Find available slots between `span_start` and `span_end` excluding `blocks`.
+///
+/// On success, `out` receives the slots, which must be released with `chrono_slots_free`.
+///
+/// # Safety
+///
+/// `blocks` must point to `blocks_len` readable periods (it may be null when `blocks_len` is 0),
+/// and `out` must point to writable memory.
+#[no_mangle]
+pub unsafe extern "C" fn chrono_slots_find(
+    span_start: i64,
+    span_end: i64,
+    blocks: *const ChronoSlotsPeriod,
+    blocks_len: usize,
+    out: *mut ChronoSlotsSlotArray,
+) -> ChronoSlotsStatus {
+    if out.is_null() || (blocks.is_null() && blocks_len > 0) {
+        return ChronoSlotsStatus::NullPointer;
+    }
+    let blocks = if blocks_len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(blocks, blocks_len)
+    };
+    let span = ChronoSlotsPeriod {
+        start: span_start,
+        end: span_end,
+    };
+    match find_slots(span, blocks) {
+        Ok(slots) => {
+            let slots = slots.into_boxed_slice();
+            let len = slots.len();
+            let data = Box::into_raw(slots) as *mut ChronoSlotsPeriod;
+            out.write(ChronoSlotsSlotArray { data, len });
+            ChronoSlotsStatus::Ok
+        }
+        Err(status) => {
+            out.write(ChronoSlotsSlotArray {
+                data: ptr::null_mut(),
+                len: 0,
+            });
+            status
+        }
+    }
+}
+
+/// Release slots returned by `chrono_slots_find`.
+///
+/// # Safety
+///
+/// `slots` must have been returned by `chrono_slots_find` and not released yet.
+#[no_mangle]
+pub unsafe extern "C" fn chrono_slots_free(slots: ChronoSlotsSlotArray) {
+    if slots.data.is_null() {
+        return;
+    }
+    drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+        slots.data, slots.len,
+    )));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3600;
+
+    fn period(start: i64, end: i64) -> ChronoSlotsPeriod {
+        ChronoSlotsPeriod {
+            start: start * HOUR,
+            end: end * HOUR,
+        }
+    }
+
+    #[test]
+    fn test_chrono_slots_find() {
+        struct TestCase {
+            name: &'static str,
+            span: ChronoSlotsPeriod,
+            blocks: Vec<ChronoSlotsPeriod>,
+            expected: Result<Vec<ChronoSlotsPeriod>, ChronoSlotsStatus>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "No blocks",
+                span: period(0, 8),
+                blocks: vec![],
+                expected: Ok(vec![period(0, 8)]),
+            },
+            TestCase {
+                name: "Two blocks are contained in span",
+                span: period(0, 8),
+                blocks: vec![period(6, 7), period(1, 2)],
+                expected: Ok(vec![period(0, 1), period(2, 6), period(7, 8)]),
+            },
+            TestCase {
+                name: "Invalid span",
+                span: period(8, 0),
+                blocks: vec![],
+                expected: Err(ChronoSlotsStatus::InvalidTime),
+            },
+            TestCase {
+                name: "Invalid block",
+                span: period(0, 8),
+                blocks: vec![period(3, 3)],
+                expected: Err(ChronoSlotsStatus::InvalidTime),
+            },
+            TestCase {
+                name: "Out of range",
+                span: ChronoSlotsPeriod {
+                    start: 0,
+                    end: i64::MAX,
+                },
+                blocks: vec![],
+                expected: Err(ChronoSlotsStatus::OutOfRange),
+            },
+        ];
+
+        for case in cases {
+            let mut out = ChronoSlotsSlotArray {
+                data: ptr::null_mut(),
+                len: 0,
+            };
+            let status = unsafe {
+                chrono_slots_find(
+                    case.span.start,
+                    case.span.end,
+                    case.blocks.as_ptr(),
+                    case.blocks.len(),
+                    &mut out,
+                )
+            };
+            match case.expected {
+                Ok(expected) => {
+                    assert_eq!(status, ChronoSlotsStatus::Ok, "{}", case.name);
+                    let actual = unsafe { slice::from_raw_parts(out.data, out.len) };
+                    assert_eq!(actual, expected.as_slice(), "{}", case.name);
+                }
+                Err(expected) => {
+                    assert_eq!(status, expected, "{}", case.name);
+                    assert!(out.data.is_null(), "{}", case.name);
+                }
+            }
+            unsafe { chrono_slots_free(out) };
+        }
+    }
+
+    #[test]
+    fn test_chrono_slots_find_null_pointer() {
+        let status = unsafe { chrono_slots_find(0, HOUR, ptr::null(), 1, ptr::null_mut()) };
+        assert_eq!(status, ChronoSlotsStatus::NullPointer);
+    }
+}
@@ -1,2 +1,86 @@
+#[cfg(feature = "std")]
+pub use self::batch::*;
+#[cfg(feature = "std")]
+pub use self::billing::*;
+#[cfg(feature = "std")]
+pub use self::bounded::*;
+#[cfg(feature = "std")]
+pub use self::capacity::*;
+#[cfg(feature = "std")]
+pub use self::conflict::*;
+#[cfg(feature = "std")]
+pub use self::coverage::*;
+#[cfg(feature = "std")]
+pub use self::epoch::*;
+#[cfg(feature = "std")]
+pub use self::explain::*;
 pub use self::find::*;
+#[cfg(feature = "std")]
+pub use self::identify::*;
+#[cfg(feature = "std")]
+pub use self::keyed::*;
+#[cfg(feature = "std")]
+pub use self::longest::*;
+pub use self::metrics::*;
+#[cfg(feature = "std")]
+pub use self::multi::*;
+#[cfg(feature = "std")]
+pub use self::options::*;
+#[cfg(feature = "std")]
+pub use self::page::*;
+#[cfg(feature = "std")]
+pub use self::partial::*;
+#[cfg(feature = "std")]
+pub use self::rfc3339::*;
+#[cfg(feature = "serde")]
+pub use self::schema::*;
+#[cfg(feature = "std")]
+pub use self::stream::*;
+#[cfg(feature = "std")]
+pub use self::travel::*;
+#[cfg(feature = "std")]
+pub use self::utc::*;
+#[cfg(feature = "std")]
+pub mod batch;
+#[cfg(feature = "std")]
+pub mod billing;
+#[cfg(feature = "std")]
+pub mod bounded;
+#[cfg(feature = "std")]
+pub mod capacity;
+#[cfg(feature = "std")]
+pub mod conflict;
+#[cfg(feature = "std")]
+pub mod coverage;
+#[cfg(feature = "std")]
+pub mod epoch;
+#[cfg(feature = "std")]
+pub mod explain;
+#[cfg(feature = "std")]
+pub(crate) mod filter;
 pub mod find;
+#[cfg(feature = "std")]
+pub mod identify;
+#[cfg(feature = "std")]
+pub mod keyed;
+#[cfg(feature = "std")]
+pub mod longest;
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod multi;
+#[cfg(feature = "std")]
+pub mod options;
+#[cfg(feature = "std")]
+pub mod page;
+#[cfg(feature = "std")]
+pub mod partial;
+#[cfg(feature = "std")]
+pub mod rfc3339;
+#[cfg(feature = "serde")]
+pub mod schema;
+#[cfg(feature = "std")]
+pub mod stream;
+#[cfg(feature = "std")]
+pub mod travel;
+#[cfg(feature = "std")]
+pub mod utc;
@@ -0,0 +1,170 @@
+use crate::periods::{
+    period::{Input, Output, Period, PeriodError},
+    span::Span,
+};
+
+use super::{filter::clip, find::sweep, options::FindOptions};
+
+// Same as find_with for each of the spans, but the inputs are swept once over the union of the spans
+// (e.g. per-day views of one week), and the free time is sliced per span.
+// The results are in the order of the spans, and the slots are in the timezone of their span.
+pub fn find_batch<In: Input, Out: Output>(
+    spans: &[Span],
+    inputs: Vec<In>,
+    options: &FindOptions,
+) -> Result<Vec<Vec<Out>>, PeriodError> {
+    options.validate()?;
+    let (Some(start), Some(end)) = (
+        spans.iter().map(|s| s.start()).min(),
+        spans.iter().map(|s| s.end()).max(),
+    ) else {
+        return Ok(Vec::new());
+    };
+    // An input of another span of the batch is not outside, so inputs are checked against the union.
+    options.check_inputs(&Span::new(start, end)?, &inputs)?;
+    for span in spans {
+        options.check_precision([span.start(), span.end()])?;
+    }
+    // Clamped to now and bounded by the horizon before the sweep, as by find_with.
+    let prepared = spans
+        .iter()
+        .map(|span| options.prepare(span.clone()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let (Some(start), Some(end)) = (
+        prepared.iter().flatten().map(|s| s.start()).min(),
+        prepared.iter().flatten().map(|s| s.end()).max(),
+    ) else {
+        return Ok(spans.iter().map(|_| Vec::new()).collect());
+    };
+    let inputs = options.drop_past(inputs, &mut ());
+    let gaps = sweep(Span::new(start, end)?, inputs, &mut (), options.cancel())?;
+
+    let mut results = Vec::with_capacity(spans.len());
+    for span in prepared {
+        let Some(span) = span else {
+            results.push(Vec::new());
+            continue;
+        };
+        let from = gaps.partition_point(|gap| gap.end() <= span.start());
+        let sliced = gaps[from..]
+            .iter()
+            .take_while(|gap| gap.start() < span.end())
+            .filter_map(|gap| clip(gap, &span))
+            .collect();
+        let slots = options.apply(&span, sliced);
+        results.push(slots.into_iter().map(Out::create_from_slot).collect());
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clock::FixedClock,
+        finder::{
+            find::find_with,
+            options::{HorizonPolicy, OutOfSpanPolicy},
+        },
+        periods::{block::Block, slot::Slot},
+    };
+    use chrono::{DateTime, Duration, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(day: u32, hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, day, hour, 0, 0)
+            .unwrap()
+    }
+
+    fn times(slots: &[Slot]) -> Vec<(DateTime<Tz>, DateTime<Tz>)> {
+        slots.iter().map(|s| (s.start(), s.end())).collect()
+    }
+
+    #[test]
+    fn test_find_batch() -> Result<(), PeriodError> {
+        let blocks = || -> Result<Vec<Block>, PeriodError> {
+            Ok(vec![
+                Block::new(dt(1, 10), dt(1, 11))?,
+                Block::new(dt(1, 16), dt(2, 10))?,
+                Block::new(dt(3, 12), dt(3, 13))?,
+            ])
+        };
+        let spans = vec![
+            Span::new(dt(1, 9), dt(1, 17))?,
+            Span::new(dt(2, 9), dt(2, 17))?,
+            Span::new(dt(3, 9), dt(3, 17))?,
+            Span::new(dt(1, 9), dt(3, 17))?,
+            Span::new(
+                dt(3, 9).with_timezone(&Tz::UTC),
+                dt(3, 17).with_timezone(&Tz::UTC),
+            )?,
+        ];
+        let options = FindOptions::new().min_duration(Duration::hours(1));
+
+        let actual: Vec<Vec<Slot>> = find_batch(&spans, blocks()?, &options)?;
+        assert_eq!(actual.len(), spans.len());
+        for (span, actual) in spans.iter().zip(actual.iter()) {
+            let expected: Vec<Slot> = find_with(span.clone(), blocks()?, &options)?;
+            assert_eq!(times(actual), times(&expected), "{:?}", span);
+            for slot in actual {
+                assert_eq!(slot.start().timezone(), span.start().timezone());
+                assert_eq!(slot.end().timezone(), span.start().timezone());
+            }
+        }
+
+        let none: Vec<Vec<Slot>> = find_batch(&[], blocks()?, &options)?;
+        assert!(none.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_batch_prepare() -> Result<(), PeriodError> {
+        let blocks = || Block::new(dt(2, 10), dt(2, 11)).map(|b| vec![b]);
+        let spans = vec![
+            Span::new(dt(1, 9), dt(1, 17))?,
+            Span::new(dt(2, 9), dt(2, 17))?,
+            Span::new(dt(2, 9), dt(9, 17))?,
+        ];
+        let options = FindOptions::new()
+            .clock(FixedClock::new(dt(2, 9)))
+            .clamp_to_now()
+            .horizon(Duration::hours(4), HorizonPolicy::Truncate);
+
+        let actual: Vec<Vec<Slot>> = find_batch(&spans, blocks()?, &options)?;
+        let actual = actual.iter().map(|slots| times(slots)).collect::<Vec<_>>();
+        // Span 1 is in the past, and the others are clamped to now and truncated to 4 hours.
+        let open = vec![(dt(2, 9), dt(2, 10)), (dt(2, 11), dt(2, 13))];
+        assert_eq!(actual, vec![vec![], open.clone(), open]);
+
+        let past = vec![Span::new(dt(1, 9), dt(1, 17))?];
+        let actual: Vec<Vec<Slot>> = find_batch(&past, blocks()?, &options)?;
+        assert_eq!(actual.len(), 1);
+        assert!(actual[0].is_empty(), "All spans are in the past");
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_batch_out_of_span() -> Result<(), PeriodError> {
+        let days = vec![
+            Span::new(dt(1, 9), dt(1, 17))?,
+            Span::new(dt(2, 9), dt(2, 17))?,
+        ];
+        let options = FindOptions::new().out_of_span(OutOfSpanPolicy::Reject);
+
+        let inputs = vec![
+            Block::new(dt(1, 10), dt(1, 11))?,
+            Block::new(dt(2, 10), dt(2, 11))?,
+        ];
+        let actual: Result<Vec<Vec<Slot>>, _> = find_batch(&days, inputs, &options);
+        assert!(actual.is_ok(), "Each input is within one of the spans");
+
+        let inputs = vec![
+            Block::new(dt(1, 10), dt(1, 11))?,
+            Block::new(dt(3, 10), dt(3, 11))?,
+        ];
+        let actual: Result<Vec<Vec<Slot>>, _> = find_batch(&days, inputs, &options);
+        assert_eq!(actual.err(), Some(PeriodError::OutOfSpan { index: 1 }));
+        Ok(())
+    }
+}
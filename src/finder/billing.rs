@@ -0,0 +1,99 @@
+use chrono::Duration;
+
+use crate::periods::{
+    period::{Input, Period, PeriodError},
+    slot::Slot,
+    span::Span,
+};
+
+use super::{filter::billable_units, find::find_with, options::FindOptions};
+
+/// A slot rounded down to whole billing units, with what can be billed for it.
+#[derive(Debug, Clone)]
+pub struct BillableSlot {
+    pub slot: Slot,
+    /// Number of billing units, at least 1.
+    pub units: i64,
+    /// Billable time, units times the billing unit (the length of the slot).
+    pub billable: Duration,
+}
+
+// Same as find_with with the billing unit set, and the billable units reported for each slot.
+pub fn find_billable<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+    options: &FindOptions,
+    unit: Duration,
+) -> Result<Vec<BillableSlot>, PeriodError> {
+    let options = options.clone().billing_unit(unit);
+    let slots: Vec<Slot> = find_with(span, inputs, &options)?;
+    Ok(slots
+        .into_iter()
+        .map(|slot| {
+            let billable = slot.end() - slot.start();
+            BillableSlot {
+                units: billable_units(billable, unit),
+                billable,
+                slot,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        finder::explain::{explain, Reason},
+        periods::block::Block,
+    };
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32, min: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_find_billable() -> Result<(), PeriodError> {
+        let span = Span::new(dt(9, 0), dt(12, 0))?;
+        // Free: 9:00-9:10, 9:30-10:40, 11:00-12:00
+        let blocks = vec![
+            Block::new(dt(9, 10), dt(9, 30))?,
+            Block::new(dt(10, 40), dt(11, 0))?,
+        ];
+        let unit = Duration::minutes(15);
+        let slots = find_billable(span.clone(), blocks.clone(), &FindOptions::new(), unit)?;
+        let actual = slots
+            .iter()
+            .map(|s| {
+                (
+                    s.slot.start(),
+                    s.slot.end(),
+                    s.units,
+                    s.billable.num_minutes(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![
+                (dt(9, 30), dt(10, 30), 4, 60),
+                (dt(11, 0), dt(12, 0), 4, 60),
+            ]
+        );
+
+        let options = FindOptions::new().billing_unit(unit);
+        let reasons = explain(
+            span.clone(),
+            blocks.clone(),
+            &options,
+            &Slot::new(dt(10, 30), dt(10, 40))?,
+        )?;
+        assert_eq!(reasons, vec![Reason::Billing]);
+        assert!(find_billable(span, blocks, &FindOptions::new(), Duration::zero()).is_err());
+        Ok(())
+    }
+}
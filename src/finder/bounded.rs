@@ -0,0 +1,207 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use crate::periods::{
+    period::{Input, Period, PeriodError},
+    slot::Slot,
+    span::Span,
+};
+
+use super::find::find_slots;
+
+/// A slot with the blocks on each side of it.
+#[derive(Debug, Clone)]
+pub struct BoundedSlot {
+    pub slot: Slot,
+    /// Index of the input ending when the slot starts. None at the start of the span.
+    pub before: Option<usize>,
+    /// Index of the input starting when the slot ends. None at the end of the span.
+    pub after: Option<usize>,
+}
+
+/// A slot with the inputs on each side of it, so their payloads can be read.
+#[derive(Debug, Clone)]
+pub struct AnnotatedSlot<'a, In> {
+    pub slot: Slot,
+    /// Inputs ending when the slot starts. Empty at the start of the span.
+    pub before: Vec<&'a In>,
+    /// Inputs starting when the slot ends. Empty at the end of the span.
+    pub after: Vec<&'a In>,
+}
+
+// A slot, and indices of the inputs before and after it.
+type Adjacent = (Slot, Vec<usize>, Vec<usize>);
+
+// Find slots with the indices of all inputs ending when each slot starts, and starting when it ends.
+// Indices are in the order of the inputs.
+fn find_adjacent<In: Input>(span: Span, inputs: &[In]) -> Result<Vec<Adjacent>, PeriodError> {
+    let blocks = inputs
+        .iter()
+        .map(|input| input.to_block())
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut ends = blocks
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.end(), i))
+        .collect::<Vec<_>>();
+    ends.sort();
+    let mut starts = blocks
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.start(), i))
+        .collect::<Vec<_>>();
+    starts.sort();
+    let all_at = |sorted: &[(DateTime<Tz>, usize)], time| {
+        let from = sorted.partition_point(|(t, _)| *t < time);
+        let to = sorted.partition_point(|(t, _)| *t <= time);
+        sorted[from..to].iter().map(|(_, i)| *i).collect::<Vec<_>>()
+    };
+
+    let slots = find_slots(span.clone(), blocks)?;
+    Ok(slots
+        .into_iter()
+        .map(|slot| {
+            let before = match slot.start() > span.start() {
+                true => all_at(&ends, slot.start()),
+                false => Vec::new(),
+            };
+            let after = match slot.end() < span.end() {
+                true => all_at(&starts, slot.end()),
+                false => Vec::new(),
+            };
+            (slot, before, after)
+        })
+        .collect())
+}
+
+// Same as find, and each slot tells which inputs bound it (e.g. free between Standup and 1:1).
+// If several inputs end (or start) at the same time, the first of them in the inputs is used.
+pub fn find_bounded<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+) -> Result<Vec<BoundedSlot>, PeriodError> {
+    Ok(find_adjacent(span, &inputs)?
+        .into_iter()
+        .map(|(slot, before, after)| BoundedSlot {
+            slot,
+            before: before.first().copied(),
+            after: after.first().copied(),
+        })
+        .collect())
+}
+
+// Same as find, and each slot refers to all inputs bounding it, with their payloads (see Tagged).
+pub fn find_annotated<In: Input>(
+    span: Span,
+    inputs: &[In],
+) -> Result<Vec<AnnotatedSlot<'_, In>>, PeriodError> {
+    Ok(find_adjacent(span, inputs)?
+        .into_iter()
+        .map(|(slot, before, after)| AnnotatedSlot {
+            slot,
+            before: before.into_iter().map(|i| &inputs[i]).collect(),
+            after: after.into_iter().map(|i| &inputs[i]).collect(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::{block::Block, tagged::Tagged};
+    use chrono::{Duration, Utc};
+
+    fn block(now: DateTime<Tz>, start: i64, end: i64) -> Result<Block, PeriodError> {
+        Block::new(now + Duration::hours(start), now + Duration::hours(end))
+    }
+
+    #[test]
+    fn test_find_bounded() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let span = || Span::new(now, now + Duration::hours(8));
+
+        struct TestCase {
+            name: &'static str,
+            blocks: Vec<Block>,
+            expected: Vec<(i64, Option<usize>, Option<usize>)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "No blocks",
+                blocks: vec![],
+                expected: vec![(0, None, None)],
+            },
+            TestCase {
+                name: "Between blocks",
+                blocks: vec![block(now, 5, 6)?, block(now, 1, 2)?],
+                expected: vec![
+                    (0, None, Some(1)),
+                    (2, Some(1), Some(0)),
+                    (6, Some(0), None),
+                ],
+            },
+            TestCase {
+                name: "Blocks across the edges and nested",
+                blocks: vec![
+                    block(now, -1, 1)?,
+                    block(now, 3, 7)?,
+                    block(now, 4, 5)?,
+                    block(now, 7, 9)?,
+                ],
+                expected: vec![(1, Some(0), Some(1))],
+            },
+            TestCase {
+                name: "First of blocks ending together",
+                blocks: vec![block(now, 0, 2)?, block(now, 1, 3)?, block(now, 2, 3)?],
+                expected: vec![(3, Some(1), None)],
+            },
+        ];
+
+        for case in cases {
+            let actual = find_bounded(span()?, case.blocks)?
+                .iter()
+                .map(|b| ((b.slot.start() - now).num_hours(), b.before, b.after))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_annotated() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let span = Span::new(now, now + Duration::hours(8))?;
+        let tagged = |start, end, payload| {
+            Tagged::new(
+                now + Duration::hours(start),
+                now + Duration::hours(end),
+                payload,
+            )
+        };
+        let events = vec![
+            tagged(1, 2, "standup")?,
+            tagged(4, 5, "1:1")?,
+            tagged(0, 2, "focus")?,
+        ];
+
+        let actual = find_annotated(span, &events)?
+            .iter()
+            .map(|a| {
+                (
+                    (a.slot.start() - now).num_hours(),
+                    a.before.iter().map(|e| e.payload).collect::<Vec<_>>(),
+                    a.after.iter().map(|e| e.payload).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![
+                (2, vec!["standup", "focus"], vec!["1:1"]),
+                (5, vec!["1:1"], vec![])
+            ]
+        );
+        Ok(())
+    }
+}
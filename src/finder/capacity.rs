@@ -0,0 +1,113 @@
+use crate::periods::{
+    period::{Input, Output, Period, PeriodError},
+    slot::Slot,
+    span::Span,
+};
+
+use super::coverage::headcount;
+
+// Same as find, but the resource holds up to capacity concurrent bookings (e.g. a room with 3 desks).
+// Time is free while fewer than capacity inputs overlap it. With capacity 1 this is the same as find.
+pub fn find_with_capacity<In: Input, Out: Output>(
+    span: Span,
+    inputs: Vec<In>,
+    capacity: usize,
+) -> Result<Vec<Out>, PeriodError> {
+    let counts = headcount(&span, &inputs)?;
+    let ends = counts
+        .iter()
+        .skip(1)
+        .map(|(time, _)| *time)
+        .chain(std::iter::once(span.end()));
+    let mut slots: Vec<Slot> = Vec::new();
+    for ((start, count), end) in counts.iter().zip(ends) {
+        if *count >= capacity || *start >= end {
+            continue;
+        }
+        match slots.last_mut() {
+            Some(last) if last.end() == *start => *last = Slot::new(last.start(), end)?,
+            _ => slots.push(Slot::new(*start, end)?),
+        }
+    }
+    Ok(slots.into_iter().map(Out::create_from_slot).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{finder::find::find, periods::block::Block};
+    use chrono::{DateTime, Duration, Utc};
+    use chrono_tz::Tz;
+
+    fn block(now: DateTime<Tz>, start: i64, end: i64) -> Result<Block, PeriodError> {
+        Block::new(now + Duration::hours(start), now + Duration::hours(end))
+    }
+
+    #[test]
+    fn test_find_with_capacity() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let span = || Span::new(now, now + Duration::hours(8));
+        // Concurrent bookings: 0-1: 0, 1-2: 1, 2-3: 2, 3-4: 3, 4-5: 1, 5-8: 0
+        let blocks = || -> Result<Vec<Block>, PeriodError> {
+            Ok(vec![
+                block(now, 1, 4)?,
+                block(now, 2, 5)?,
+                block(now, 3, 4)?,
+            ])
+        };
+
+        struct TestCase {
+            name: &'static str,
+            capacity: usize,
+            expected: Vec<(i64, i64)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "No capacity",
+                capacity: 0,
+                expected: vec![],
+            },
+            TestCase {
+                name: "Capacity of one",
+                capacity: 1,
+                expected: vec![(0, 1), (5, 8)],
+            },
+            TestCase {
+                name: "Capacity of two",
+                capacity: 2,
+                expected: vec![(0, 2), (4, 8)],
+            },
+            TestCase {
+                name: "Capacity of three",
+                capacity: 3,
+                expected: vec![(0, 3), (4, 8)],
+            },
+            TestCase {
+                name: "Capacity never reached",
+                capacity: 4,
+                expected: vec![(0, 8)],
+            },
+        ];
+
+        for case in cases {
+            let slots: Vec<Slot> = find_with_capacity(span()?, blocks()?, case.capacity)?;
+            let actual = slots
+                .iter()
+                .map(|s| ((s.start() - now).num_hours(), (s.end() - now).num_hours()))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+
+        let single: Vec<Slot> = find(span()?, blocks()?)?;
+        let capacity_one: Vec<Slot> = find_with_capacity(span()?, blocks()?, 1)?;
+        assert_eq!(
+            single.iter().map(|s| s.epoch_millis()).collect::<Vec<_>>(),
+            capacity_one
+                .iter()
+                .map(|s| s.epoch_millis())
+                .collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+}
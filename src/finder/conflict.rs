@@ -0,0 +1,88 @@
+use crate::periods::{
+    block::Block,
+    period::{Input, Period, PeriodError},
+};
+
+// Detect double-booking. Each pair of inputs that overlap is reported as (i, j, overlap),
+// where i < j are the indices of the inputs and overlap is the time both occupy.
+// Inputs that only touch (one ends when the other starts) do not conflict.
+// Conflicts are ordered by the start of the overlap.
+pub fn find_conflicts<In: Input>(inputs: &[In]) -> Result<Vec<(usize, usize, Block)>, PeriodError> {
+    let mut blocks = inputs
+        .iter()
+        .map(|input| input.to_block())
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .enumerate()
+        .collect::<Vec<_>>();
+    blocks.sort_by_key(|(i, b)| (b.start(), *i));
+
+    let mut conflicts = Vec::new();
+    let mut active: Vec<(usize, Block)> = Vec::new();
+    for (j, block) in blocks {
+        active.retain(|(_, a)| a.end() > block.start());
+        for (i, a) in &active {
+            let overlap = Block::new(block.start(), a.end().min(block.end()))?;
+            conflicts.push(((*i).min(j), (*i).max(j), overlap));
+        }
+        active.push((j, block));
+    }
+    conflicts.sort_by_key(|(i, j, b)| (b.start(), *i, *j));
+    Ok(conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration, Utc};
+    use chrono_tz::Tz;
+
+    fn block(now: DateTime<Tz>, start: i64, end: i64) -> Result<Block, PeriodError> {
+        Block::new(now + Duration::hours(start), now + Duration::hours(end))
+    }
+
+    #[test]
+    fn test_find_conflicts() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+
+        struct TestCase {
+            name: &'static str,
+            blocks: Vec<Block>,
+            expected: Vec<(usize, usize, i64, i64)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "No conflicts",
+                blocks: vec![block(now, 0, 1)?, block(now, 1, 2)?, block(now, 3, 4)?],
+                expected: vec![],
+            },
+            TestCase {
+                name: "Unsorted inputs",
+                blocks: vec![block(now, 5, 7)?, block(now, 0, 3)?, block(now, 2, 6)?],
+                expected: vec![(1, 2, 2, 3), (0, 2, 5, 6)],
+            },
+            TestCase {
+                name: "Contained and triple-booked",
+                blocks: vec![block(now, 0, 8)?, block(now, 1, 2)?, block(now, 1, 3)?],
+                expected: vec![(0, 1, 1, 2), (0, 2, 1, 3), (1, 2, 1, 2)],
+            },
+        ];
+
+        for case in cases {
+            let actual = find_conflicts(&case.blocks)?
+                .iter()
+                .map(|(i, j, b)| {
+                    (
+                        *i,
+                        *j,
+                        (b.start() - now).num_hours(),
+                        (b.end() - now).num_hours(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+}
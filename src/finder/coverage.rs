@@ -0,0 +1,148 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use crate::periods::{
+    period::{Input, Period, PeriodError},
+    slot::Slot,
+    span::Span,
+};
+
+/// A period with fewer people on duty than required.
+#[derive(Debug, Clone)]
+pub struct CoverageGap {
+    pub slot: Slot,
+    /// Number of shifts covering the whole period.
+    pub on_duty: usize,
+}
+
+// Headcount of the shifts over the span, as (time, count from the time) in time order.
+// Each time appears once, and the first one is the start of the span.
+pub(crate) fn headcount<In: Input>(
+    span: &Span,
+    shifts: &[In],
+) -> Result<Vec<(DateTime<Tz>, usize)>, PeriodError> {
+    let mut changes = Vec::new();
+    for shift in shifts {
+        let block = shift.to_block()?;
+        let start = block.start().max(span.start());
+        let end = block.end().min(span.end());
+        if start < end {
+            changes.push((start, 1));
+            changes.push((end, -1));
+        }
+    }
+    changes.sort_by_key(|(time, _)| *time);
+
+    let mut counts: Vec<(DateTime<Tz>, usize)> = vec![(span.start(), 0)];
+    let mut count: i64 = 0;
+    for (time, change) in changes {
+        count += change;
+        match counts.last_mut() {
+            Some(last) if last.0 == time => last.1 = count as usize,
+            _ => counts.push((time, count as usize)),
+        }
+    }
+    Ok(counts)
+}
+
+// Calculate periods of the span where fewer than required shifts (Input) are on duty.
+// A gap is split where the headcount changes, so each gap tells how many are on duty.
+pub fn find_coverage_gaps<In: Input>(
+    span: Span,
+    shifts: Vec<In>,
+    required: usize,
+) -> Result<Vec<CoverageGap>, PeriodError> {
+    let counts = headcount(&span, &shifts)?;
+    let ends = counts
+        .iter()
+        .skip(1)
+        .map(|(time, _)| *time)
+        .chain(std::iter::once(span.end()));
+    let mut gaps: Vec<CoverageGap> = Vec::new();
+    for ((start, on_duty), end) in counts.iter().zip(ends) {
+        if *on_duty >= required || *start >= end {
+            continue;
+        }
+        match gaps.last_mut() {
+            Some(last) if last.slot.end() == *start && last.on_duty == *on_duty => {
+                last.slot = Slot::new(last.slot.start(), end)?;
+            }
+            _ => gaps.push(CoverageGap {
+                slot: Slot::new(*start, end)?,
+                on_duty: *on_duty,
+            }),
+        }
+    }
+    Ok(gaps)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{Duration, TimeZone};
+
+    fn dt(hour: i64) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            + Duration::hours(hour)
+    }
+
+    fn shift(start: i64, end: i64) -> Result<Block, PeriodError> {
+        Block::new(dt(start), dt(end))
+    }
+
+    #[test]
+    fn test_find_coverage_gaps() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(8), dt(20));
+        // On duty: 8-10: 1, 10-12: 2, 12-14: 1, 14-16: 2, 16-17: 1, 17-18: 1, 18-20: 0
+        let shifts = || -> Result<Vec<Block>, PeriodError> {
+            Ok(vec![
+                shift(6, 12)?,
+                shift(10, 16)?,
+                shift(14, 17)?,
+                shift(17, 18)?,
+            ])
+        };
+
+        struct TestCase {
+            name: &'static str,
+            required: usize,
+            expected: Vec<(i64, i64, usize)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Nobody required",
+                required: 0,
+                expected: vec![],
+            },
+            TestCase {
+                name: "One required",
+                required: 1,
+                expected: vec![(18, 20, 0)],
+            },
+            TestCase {
+                name: "Two required",
+                required: 2,
+                expected: vec![(8, 10, 1), (12, 14, 1), (16, 18, 1), (18, 20, 0)],
+            },
+        ];
+
+        for case in cases {
+            let actual = find_coverage_gaps(span()?, shifts()?, case.required)?
+                .iter()
+                .map(|g| {
+                    (
+                        (g.slot.start() - dt(0)).num_hours(),
+                        (g.slot.end() - dt(0)).num_hours(),
+                        g.on_duty,
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use chrono_tz::Tz;
+
+use crate::periods::{block::Block, period::PeriodError, slot::Slot, span::Span};
+
+use super::find::find;
+
+// Same as find, but the span and the scheduled events are Unix epoch milliseconds (start, end).
+// Read the result with Slot::epoch_millis().
+pub fn find_epoch(span: (i64, i64), events: &[(i64, i64)]) -> Result<Vec<Slot>, PeriodError> {
+    let span = Span::from_epoch_millis(span.0, span.1, Tz::UTC)?;
+    let blocks = events
+        .iter()
+        .map(|(start, end)| Block::from_epoch_millis(*start, *end, Tz::UTC))
+        .collect::<Result<Vec<_>, _>>()?;
+    find(span, blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::period::Period;
+    use chrono::TimeZone;
+
+    const HOUR: i64 = 3_600_000;
+
+    #[test]
+    fn test_find_epoch() {
+        struct TestCase {
+            name: &'static str,
+            span: (i64, i64),
+            events: Vec<(i64, i64)>,
+            expected: Result<Vec<(i64, i64)>, PeriodError>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "No events",
+                span: (0, 8 * HOUR),
+                events: vec![],
+                expected: Ok(vec![(0, 8 * HOUR)]),
+            },
+            TestCase {
+                name: "Events are contained in span",
+                span: (0, 8 * HOUR),
+                events: vec![(6 * HOUR, 7 * HOUR), (HOUR, 2 * HOUR + 500)],
+                expected: Ok(vec![
+                    (0, HOUR),
+                    (2 * HOUR + 500, 6 * HOUR),
+                    (7 * HOUR, 8 * HOUR),
+                ]),
+            },
+            TestCase {
+                name: "Invalid span",
+                span: (8 * HOUR, 0),
+                events: vec![],
+                expected: Err(PeriodError::StartNotBeforeEnd {
+                    start: Tz::UTC.timestamp_millis_opt(8 * HOUR).unwrap(),
+                    end: Tz::UTC.timestamp_millis_opt(0).unwrap(),
+                }),
+            },
+            TestCase {
+                name: "Out of range",
+                span: (0, i64::MAX),
+                events: vec![],
+                expected: Err(PeriodError::OutOfRange(format!(
+                    "{} ms since the epoch",
+                    i64::MAX
+                ))),
+            },
+        ];
+
+        for case in cases {
+            match find_epoch(case.span, &case.events) {
+                Ok(slots) => {
+                    let actual = slots.iter().map(|s| s.epoch_millis()).collect::<Vec<_>>();
+                    assert_eq!(actual, case.expected.unwrap(), "{}", case.name);
+                }
+                Err(err) => {
+                    assert_eq!(
+                        err.to_string(),
+                        case.expected.unwrap_err().to_string(),
+                        "{}",
+                        case.name
+                    );
+                }
+            }
+        }
+    }
+}
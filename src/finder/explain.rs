@@ -0,0 +1,307 @@
+use crate::periods::{
+    block::Block,
+    period::{Input, Period, PeriodError},
+    span::Span,
+};
+
+use super::{find::sweep, options::FindOptions};
+
+/// Why a time was not offered by find_with.
+#[derive(Debug, Clone)]
+pub enum Reason {
+    /// The time is not within the span.
+    OutsideSpan,
+    /// The time is in the past and the span is clamped to now.
+    Past,
+    /// The time is beyond the horizon.
+    Horizon,
+    /// The time overlaps the input at the index.
+    Blocked { index: usize, block: Block },
+    /// The sub-second part of the free time is truncated away by the precision.
+    Precision,
+    /// The free time is rounded away by the granularity.
+    Granularity,
+    /// The time is within the lead time.
+    LeadTime,
+    /// No aligned start time is left in the free time.
+    Alignment,
+    /// The time is a break between work intervals.
+    Break,
+    /// The time crosses a boundary where the free time is split by the maximum duration.
+    MaxDuration,
+    /// The time is left over after rounding the free time down to whole billing units.
+    Billing,
+    /// The free time is shorter than the minimum duration.
+    MinDuration,
+    /// The free time is protected as focus time.
+    FocusTime,
+    /// The time is not within one of the candidates of the stride.
+    Stride,
+    /// The day already has the maximum number of slots.
+    MaxPerDay,
+    /// The result already has the maximum number of slots.
+    MaxResults,
+}
+
+impl PartialEq for Reason {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Reason::Blocked { index, block },
+                Reason::Blocked {
+                    index: other_index,
+                    block: other_block,
+                },
+            ) => {
+                index == other_index
+                    && block.start() == other_block.start()
+                    && block.end() == other_block.end()
+            }
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+// Explain why the candidate is not offered by find_with with the same arguments.
+// An empty result means the candidate is within one of the slots found.
+// To ask about an instant, pass a short candidate starting at it.
+pub fn explain<In: Input, P: Period>(
+    span: Span,
+    inputs: Vec<In>,
+    options: &FindOptions,
+    candidate: &P,
+) -> Result<Vec<Reason>, PeriodError> {
+    options.validate()?;
+    options.check_inputs(&span, &inputs)?;
+    let (start, end) = (candidate.start(), candidate.end());
+    if start < span.start() || span.end() < end {
+        return Ok(vec![Reason::OutsideSpan]);
+    }
+    let Some(span) = options.prepare(span)? else {
+        return Ok(vec![Reason::Past]);
+    };
+    if start < span.start() {
+        return Ok(vec![Reason::Past]);
+    }
+    if span.end() < end {
+        return Ok(vec![Reason::Horizon]);
+    }
+
+    // Past inputs are dropped after numbering, so the indices are of the inputs given.
+    let now = options.drops_past_inputs().then(|| options.now());
+    let blocks = inputs
+        .iter()
+        .map(|input| input.to_block())
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .enumerate()
+        .filter(|(_, b)| now.is_none_or(|now| now <= b.end()))
+        .collect::<Vec<_>>();
+    let busy = blocks.iter().map(|(_, b)| b.clone()).collect();
+    let gaps = sweep(span.clone(), busy, &mut (), options.cancel())?;
+    // Blocks overlapping the candidate by up to the overlap tolerance do not make it busy.
+    let tolerated = options
+        .tolerate(&span, gaps.clone())
+        .iter()
+        .any(|g| g.start() <= start && end <= g.end());
+    let blocked = blocks
+        .iter()
+        .filter(|(_, b)| !tolerated && b.start() < end && start < b.end())
+        .map(|(index, block)| Reason::Blocked {
+            index: *index,
+            block: block.clone(),
+        })
+        .collect::<Vec<_>>();
+    if !blocked.is_empty() {
+        return Ok(blocked);
+    }
+
+    // The first option after which no slot contains the candidate excluded it.
+    let mut reason = None;
+    options.apply_each(&span, gaps, |r, slots| {
+        let offered = slots.iter().any(|s| s.start() <= start && end <= s.end());
+        if reason.is_none() && !offered {
+            reason = Some(r);
+        }
+    });
+    Ok(reason.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clock::FixedClock,
+        finder::options::{HorizonPolicy, LeadTimePolicy, Rounding},
+        periods::{period::Precision, slot::Slot},
+    };
+    use chrono::{DateTime, Duration, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32, min: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, min, 0)
+            .unwrap()
+    }
+
+    fn slot(start: (u32, u32), end: (u32, u32)) -> Result<Slot, PeriodError> {
+        Slot::new(dt(start.0, start.1), dt(end.0, end.1))
+    }
+
+    #[test]
+    fn test_explain() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(18, 0));
+        // Free: 9:00-10:00, 11:00-11:20, 12:00-18:00
+        let blocks = || -> Result<Vec<Block>, PeriodError> {
+            Ok(vec![
+                Block::new(dt(10, 0), dt(11, 0))?,
+                Block::new(dt(11, 20), dt(12, 0))?,
+                Block::new(dt(11, 30), dt(11, 45))?,
+            ])
+        };
+
+        struct TestCase {
+            name: &'static str,
+            options: FindOptions,
+            candidate: Slot,
+            expected: Vec<Reason>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Offered",
+                options: FindOptions::new(),
+                candidate: slot((15, 0), (16, 0))?,
+                expected: vec![],
+            },
+            TestCase {
+                name: "Busy time within the overlap tolerance",
+                options: FindOptions::new().overlap_tolerance(Duration::minutes(61)),
+                candidate: slot((9, 30), (11, 10))?,
+                expected: vec![],
+            },
+            TestCase {
+                name: "Outside span",
+                options: FindOptions::new(),
+                candidate: slot((17, 30), (18, 30))?,
+                expected: vec![Reason::OutsideSpan],
+            },
+            TestCase {
+                name: "Past",
+                options: FindOptions::new()
+                    .clock(FixedClock::new(dt(13, 0)))
+                    .clamp_to_now(),
+                candidate: slot((12, 0), (12, 30))?,
+                expected: vec![Reason::Past],
+            },
+            TestCase {
+                name: "Horizon",
+                options: FindOptions::new().horizon(Duration::hours(6), HorizonPolicy::Truncate),
+                candidate: slot((15, 0), (15, 30))?,
+                expected: vec![Reason::Horizon],
+            },
+            TestCase {
+                name: "Blocked by every overlapping input",
+                options: FindOptions::new(),
+                candidate: slot((11, 15), (11, 40))?,
+                expected: vec![
+                    Reason::Blocked {
+                        index: 1,
+                        block: Block::new(dt(11, 20), dt(12, 0))?,
+                    },
+                    Reason::Blocked {
+                        index: 2,
+                        block: Block::new(dt(11, 30), dt(11, 45))?,
+                    },
+                ],
+            },
+            TestCase {
+                name: "Blocked, numbered among the inputs given when past ones are dropped",
+                options: FindOptions::new()
+                    .clock(FixedClock::new(dt(11, 10)))
+                    .drop_past_inputs(),
+                candidate: slot((11, 15), (11, 40))?,
+                expected: vec![
+                    Reason::Blocked {
+                        index: 1,
+                        block: Block::new(dt(11, 20), dt(12, 0))?,
+                    },
+                    Reason::Blocked {
+                        index: 2,
+                        block: Block::new(dt(11, 30), dt(11, 45))?,
+                    },
+                ],
+            },
+            TestCase {
+                name: "Granularity",
+                options: FindOptions::new().granularity(Duration::minutes(30), Rounding::Outward),
+                candidate: slot((11, 0), (11, 10))?,
+                expected: vec![Reason::Granularity],
+            },
+            TestCase {
+                name: "Lead time",
+                options: FindOptions::new()
+                    .clock(FixedClock::new(dt(11, 0)))
+                    .lead_time(Duration::hours(2), LeadTimePolicy::Trim),
+                candidate: slot((12, 30), (13, 30))?,
+                expected: vec![Reason::LeadTime],
+            },
+            TestCase {
+                name: "Min duration",
+                options: FindOptions::new().min_duration(Duration::minutes(30)),
+                candidate: slot((11, 0), (11, 10))?,
+                expected: vec![Reason::MinDuration],
+            },
+            TestCase {
+                name: "Alignment",
+                options: FindOptions::new()
+                    .alignment(Duration::minutes(45))
+                    .min_duration(Duration::minutes(30)),
+                candidate: slot((11, 0), (11, 10))?,
+                expected: vec![Reason::Alignment],
+            },
+            TestCase {
+                name: "Max duration",
+                options: FindOptions::new().max_duration(Duration::hours(2)),
+                candidate: slot((13, 30), (14, 30))?,
+                expected: vec![Reason::MaxDuration],
+            },
+            TestCase {
+                name: "Stride",
+                options: FindOptions::new().stride(Duration::minutes(30), Duration::minutes(30)),
+                candidate: slot((9, 15), (9, 45))?,
+                expected: vec![Reason::Stride],
+            },
+            TestCase {
+                name: "Max per day",
+                options: FindOptions::new().max_per_day(1),
+                candidate: slot((9, 0), (9, 30))?,
+                expected: vec![Reason::MaxPerDay],
+            },
+        ];
+
+        for case in cases {
+            let actual = explain(span()?, blocks()?, &case.options, &case.candidate)?;
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_explain_precision() -> Result<(), PeriodError> {
+        let span = Span::new(dt(9, 0), dt(12, 0))?;
+        // Free until 9:30:00.5, truncated to 9:30.
+        let start = dt(9, 30) + Duration::milliseconds(500);
+        let blocks = vec![Block::new(start, dt(10, 0))?];
+        let candidate = Slot::new(
+            dt(9, 30) + Duration::milliseconds(200),
+            dt(9, 30) + Duration::milliseconds(400),
+        )?;
+        let options = FindOptions::new().precision(Precision::Truncate);
+        assert_eq!(
+            explain(span, blocks, &options, &candidate)?,
+            vec![Reason::Precision]
+        );
+        Ok(())
+    }
+}
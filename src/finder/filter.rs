@@ -0,0 +1,344 @@
+use chrono::{DateTime, Duration, Timelike};
+use chrono_tz::Tz;
+
+use super::options::{LeadTimePolicy, Rounding};
+use crate::periods::{period::Period, slot::Slot, span::Span};
+
+// Drop or trim the slot if it starts before the cutoff.
+pub(crate) fn lead(slot: Slot, cutoff: DateTime<Tz>, policy: LeadTimePolicy) -> Option<Slot> {
+    if slot.start() >= cutoff {
+        return Some(slot);
+    }
+    match policy {
+        LeadTimePolicy::Drop => None,
+        LeadTimePolicy::Trim => slot.trim_start(cutoff - slot.start()).ok(),
+    }
+}
+
+// Indices of n items picked evenly out of count: the center of each of n equal parts.
+fn evenly(count: usize, n: usize) -> impl Iterator<Item = usize> {
+    (0..n).map(move |k| (2 * k + 1) * count / (2 * n))
+}
+
+// Group the slots by local day. (This assumes the slots are sorted)
+fn by_day(slots: Vec<Slot>) -> Vec<Vec<Slot>> {
+    let mut days: Vec<Vec<Slot>> = Vec::new();
+    for slot in slots {
+        match days.last_mut() {
+            Some(day) if day[0].start().date_naive() == slot.start().date_naive() => day.push(slot),
+            _ => days.push(vec![slot]),
+        }
+    }
+    days
+}
+
+// Keep at most max slots per local day, picking evenly spaced ones. (This assumes the slots are sorted)
+pub(crate) fn limit_per_day(slots: Vec<Slot>, max: usize) -> Vec<Slot> {
+    by_day(slots)
+        .into_iter()
+        .flat_map(|day| {
+            let count = day.len();
+            if count <= max {
+                return day;
+            }
+            evenly(count, max).map(|i| day[i].clone()).collect()
+        })
+        .collect()
+}
+
+// Keep max slots spread evenly over the days, and evenly within each day. Days get equal shares,
+// and the share a day cannot use goes to the others. (This assumes the slots are sorted)
+pub(crate) fn spread(slots: Vec<Slot>, max: usize) -> Vec<Slot> {
+    let days = by_day(slots);
+    let mut shares = vec![0; days.len()];
+    let mut left = max;
+    while left > 0 {
+        let open = (0..days.len())
+            .filter(|i| shares[*i] < days[*i].len())
+            .collect::<Vec<_>>();
+        if open.is_empty() {
+            break;
+        }
+        if left >= open.len() {
+            open.iter().for_each(|i| shares[*i] += 1);
+            left -= open.len();
+        } else {
+            evenly(open.len(), left).for_each(|k| shares[open[k]] += 1);
+            left = 0;
+        }
+    }
+    days.into_iter()
+        .zip(shares)
+        .flat_map(|(day, n)| {
+            evenly(day.len(), n)
+                .map(|i| day[i].clone())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+// The time elapsed since the last multiple of the step in local time.
+fn local_remainder(dt: DateTime<Tz>, step: Duration) -> Option<Duration> {
+    let local = dt.naive_local();
+    let elapsed = Duration::seconds(local.and_utc().timestamp())
+        + Duration::nanoseconds(local.nanosecond() as i64);
+    let rem = elapsed
+        .num_nanoseconds()?
+        .rem_euclid(step.num_nanoseconds()?);
+    Some(Duration::nanoseconds(rem))
+}
+
+// Move the start time of the slot forward to the next multiple of the step in local time.
+pub(crate) fn align(slot: &Slot, step: Duration) -> Option<Slot> {
+    let rem = local_remainder(slot.start(), step)?;
+    if rem.is_zero() {
+        return Some(slot.clone());
+    }
+    slot.trim_start(step - rem).ok()
+}
+
+// Round the ends of the slot to multiples of the step in local time. None if nothing remains.
+pub(crate) fn quantize(slot: &Slot, step: Duration, rounding: Rounding) -> Option<Slot> {
+    let round = |dt: DateTime<Tz>, up: bool| {
+        let rem = local_remainder(dt, step)?;
+        let up = match rounding {
+            Rounding::Outward => up,
+            Rounding::Nearest => rem * 2 >= step,
+        };
+        Some(match (rem.is_zero(), up) {
+            (true, _) => dt,
+            (false, true) => dt + (step - rem),
+            (false, false) => dt - rem,
+        })
+    };
+    // Rounding blocks outward is rounding the free time between them inward.
+    Slot::new(round(slot.start(), true)?, round(slot.end(), false)?).ok()
+}
+
+// The part of the slot within the span, in the timezone of the span.
+pub(crate) fn clip(slot: &Slot, span: &Span) -> Option<Slot> {
+    slot.clip_to(span)
+}
+
+// Let the gaps overlap the busy time next to them by up to the tolerance, within the span,
+// so a block overlapping a slot by a few seconds of jitter does not trim it. Gaps separated by
+// busy time no longer than twice the tolerance are joined. (This assumes the gaps are sorted)
+pub(crate) fn tolerate(gaps: Vec<Slot>, span: &Span, tolerance: Duration) -> Vec<Slot> {
+    let mut joined: Vec<Slot> = Vec::with_capacity(gaps.len());
+    for gap in gaps {
+        let start = (gap.start() - tolerance).max(span.start());
+        let end = (gap.end() + tolerance).min(span.end());
+        match joined.last_mut() {
+            Some(last) if start <= last.end() => {
+                if let Ok(merged) = Slot::new(last.start(), end.max(last.end())) {
+                    *last = merged;
+                }
+            }
+            _ => joined.extend(Slot::new(start, end).ok()),
+        }
+    }
+    joined
+}
+
+// Split the slot into consecutive pieces of at most max.
+pub(crate) fn split(slot: &Slot, max: Duration) -> Vec<Slot> {
+    let mut pieces = Vec::new();
+    let mut start = slot.start();
+    while start < slot.end() {
+        let end = (start + max).min(slot.end());
+        pieces.extend(Slot::new(start, end).ok());
+        start = end;
+    }
+    pieces
+}
+
+// Split the slot into work intervals of at most work, separated by breaks of rest.
+pub(crate) fn work_intervals(slot: &Slot, work: Duration, rest: Duration) -> Vec<Slot> {
+    let mut intervals = Vec::new();
+    let mut start = slot.start();
+    while start < slot.end() {
+        let end = start
+            .checked_add_signed(work)
+            .map_or(slot.end(), |end| end.min(slot.end()));
+        intervals.extend(Slot::new(start, end).ok());
+        match end.checked_add_signed(rest) {
+            Some(next) => start = next,
+            None => break,
+        }
+    }
+    intervals
+}
+
+// Number of whole billing units in the duration.
+pub(crate) fn billable_units(duration: Duration, unit: Duration) -> i64 {
+    match (duration.num_nanoseconds(), unit.num_nanoseconds()) {
+        (Some(d), Some(u)) if u > 0 => d / u,
+        _ => duration.num_seconds() / unit.num_seconds().max(1),
+    }
+}
+
+// Round the slot down to whole billing units from its start. None if not even a unit fits.
+pub(crate) fn bill(slot: &Slot, unit: Duration) -> Option<Slot> {
+    let units = billable_units(slot.end() - slot.start(), unit);
+    if units < 1 {
+        return None;
+    }
+    slot.trim_to(unit * i32::try_from(units).ok()?).ok()
+}
+
+// Candidates of the duration starting at every step from the start of the slot.
+pub(crate) fn stride(slot: &Slot, duration: Duration, step: Duration) -> Vec<Slot> {
+    let mut candidates = Vec::new();
+    let mut start = slot.start();
+    while start + duration <= slot.end() {
+        candidates.extend(Slot::new(start, start + duration).ok());
+        start += step;
+    }
+    candidates
+}
+
+// Drop slots shorter than min_slot, and slots leaving free time shorter than min_fragment in their gap.
+// (This assumes the gaps are sorted and each slot is contained in a gap)
+pub(crate) fn protect_focus_time(
+    slots: Vec<Slot>,
+    gaps: &[Slot],
+    min_slot: Duration,
+    min_fragment: Duration,
+) -> Vec<Slot> {
+    let fragmented = |d: Duration| Duration::zero() < d && d < min_fragment;
+    slots
+        .into_iter()
+        .filter(|slot| {
+            if slot.end() - slot.start() < min_slot {
+                return false;
+            }
+            let i = gaps.partition_point(|gap| gap.start() <= slot.start());
+            let Some(gap) = i.checked_sub(1).map(|i| &gaps[i]) else {
+                return true;
+            };
+            !fragmented(slot.start() - gap.start()) && !fragmented(gap.end() - slot.end())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::period::PeriodError;
+    use chrono::TimeZone;
+
+    fn slot(start: (u32, u32), end: (u32, u32)) -> Result<Slot, PeriodError> {
+        let dt = |(h, m)| {
+            chrono_tz::Japan
+                .with_ymd_and_hms(2024, 1, 1, h, m, 0)
+                .unwrap()
+        };
+        Slot::new(dt(start), dt(end))
+    }
+
+    #[test]
+    fn test_quantize() -> Result<(), PeriodError> {
+        let at = |h, m, s| {
+            chrono_tz::Japan
+                .with_ymd_and_hms(2024, 1, 1, h, m, s)
+                .unwrap()
+        };
+
+        struct TestCase {
+            name: &'static str,
+            slot: Slot,
+            rounding: Rounding,
+            expected: Option<(DateTime<Tz>, DateTime<Tz>)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Outward blocks shrink the slot",
+                slot: Slot::new(at(9, 0, 40), at(9, 30, 20))?,
+                rounding: Rounding::Outward,
+                expected: Some((at(9, 1, 0), at(9, 30, 0))),
+            },
+            TestCase {
+                name: "Sub-minute jitter is dropped",
+                slot: Slot::new(at(9, 0, 0), at(9, 0, 23))?,
+                rounding: Rounding::Outward,
+                expected: None,
+            },
+            TestCase {
+                name: "Nearest",
+                slot: Slot::new(at(9, 0, 40), at(9, 30, 20))?,
+                rounding: Rounding::Nearest,
+                expected: Some((at(9, 1, 0), at(9, 30, 0))),
+            },
+            TestCase {
+                name: "Nearest rounds half up",
+                slot: Slot::new(at(9, 0, 20), at(9, 30, 30))?,
+                rounding: Rounding::Nearest,
+                expected: Some((at(9, 0, 0), at(9, 31, 0))),
+            },
+            TestCase {
+                name: "Already on the granularity",
+                slot: Slot::new(at(9, 0, 0), at(9, 30, 0))?,
+                rounding: Rounding::Outward,
+                expected: Some((at(9, 0, 0), at(9, 30, 0))),
+            },
+        ];
+
+        for case in cases {
+            let actual = quantize(&case.slot, Duration::minutes(1), case.rounding);
+            let actual = actual.map(|s| (s.start(), s.end()));
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_protect_focus_time() -> Result<(), PeriodError> {
+        let gaps = vec![slot((9, 0), (12, 0))?, slot((13, 0), (14, 0))?];
+
+        struct TestCase {
+            name: &'static str,
+            slot: Slot,
+            expected: bool,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Whole gap",
+                slot: slot((13, 0), (14, 0))?,
+                expected: true,
+            },
+            TestCase {
+                name: "Leaves enough time on both sides",
+                slot: slot((10, 0), (11, 0))?,
+                expected: true,
+            },
+            TestCase {
+                name: "Leaves a short fragment before",
+                slot: slot((9, 10), (10, 0))?,
+                expected: false,
+            },
+            TestCase {
+                name: "Leaves a short fragment after",
+                slot: slot((11, 0), (11, 50))?,
+                expected: false,
+            },
+            TestCase {
+                name: "Too short",
+                slot: slot((9, 0), (9, 20))?,
+                expected: false,
+            },
+        ];
+
+        for case in cases {
+            let actual = protect_focus_time(
+                vec![case.slot],
+                &gaps,
+                Duration::minutes(30),
+                Duration::minutes(15),
+            );
+            assert_eq!(actual.len() == 1, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+}
@@ -1,56 +1,146 @@
-use crate::periods::{
-    period::{Input, Output, PeriodError},
-    slot::Slot,
-    span::Span,
+use alloc::vec::Vec;
+
+use crate::{
+    cancel::Cancellation,
+    periods::{
+        block::dedup_blocks,
+        period::{Input, Output, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
 };
 
+use super::metrics::Counter;
+#[cfg(feature = "std")]
+use super::{explain::Reason, options::FindOptions};
+
 // Calculate available time slots (Output). Provide the scheduled block (Input) and the target period (Span).
-pub fn find<In: Input, Out: Output>(
+// Slots are sorted by start time.
+pub fn find<In: Input, Out: Output>(span: Span, inputs: Vec<In>) -> Result<Vec<Out>, PeriodError> {
+    let slots = find_slots(span, inputs)?;
+    Ok(slots.into_iter().map(Out::create_from_slot).collect())
+}
+
+// Same as find, but the slots found are filtered and adjusted by the options.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn find_with<In: Input, Out: Output>(
     span: Span,
-    mut inputs: Vec<In>,
+    inputs: Vec<In>,
+    options: &FindOptions,
 ) -> Result<Vec<Out>, PeriodError> {
-    inputs.sort_by_key(|p| p.start());
+    let slots = search(span, inputs, options, &mut (), |_, _| {})?
+        .map_or_else(Vec::new, |(_, slots)| slots);
+    Ok(slots.into_iter().map(Out::create_from_slot).collect())
+}
+
+// The search of find_with and its variants: the options are validated, the inputs checked and
+// the past ones dropped, then the span is prepared, swept and the options applied to the gaps.
+// The counter records the work, and inspect is called as by apply_each.
+// Returns the prepared span with the slots, None if nothing of the span is left to search.
+#[cfg(feature = "std")]
+pub(crate) fn search<In: Input, C: Counter>(
+    span: Span,
+    inputs: Vec<In>,
+    options: &FindOptions,
+    counter: &mut C,
+    inspect: impl FnMut(Reason, &[Slot]),
+) -> Result<Option<(Span, Vec<Slot>)>, PeriodError> {
+    options.validate()?;
+    options.check_inputs(&span, &inputs)?;
+    let inputs = options.drop_past(inputs, counter);
+    let Some(span) = options.prepare(span)? else {
+        return Ok(None);
+    };
+    let gaps = sweep(span.clone(), inputs, counter, options.cancel())?;
+    let slots = options.apply_each(&span, gaps, inspect);
+    Ok(Some((span, slots)))
+}
+
+// Calculate available time slots as Slot.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(inputs = inputs.len()))
+)]
+pub(crate) fn find_slots<In: Input>(span: Span, inputs: Vec<In>) -> Result<Vec<Slot>, PeriodError> {
+    sweep(span, inputs, &mut (), &Cancellation::default())
+}
+
+// The number of blocks swept between checks of the cancellation.
+pub(crate) const CANCEL_INTERVAL: usize = 1024;
+
+// The sweep of find_slots. The counter records the work done, and costs nothing if it is ().
+pub(crate) fn sweep<In: Input, C: Counter>(
+    span: Span,
+    inputs: Vec<In>,
+    counter: &mut C,
+    cancel: &Cancellation,
+) -> Result<Vec<Slot>, PeriodError> {
+    cancel.check()?;
+    let blocks = inputs
+        .iter()
+        .map(|input| input.to_block())
+        .collect::<Result<Vec<_>, _>>()?;
+    let blocks = dedup_blocks(blocks);
+    counter.deduplicated(inputs.len() - blocks.len());
 
+    let total = blocks.len();
     let mut slots = Vec::new();
     let mut target = span.clone();
-    for input in inputs {
-        let block = input.to_block()?;
+    for (i, block) in blocks.into_iter().enumerate() {
+        if i % CANCEL_INTERVAL == 0 {
+            cancel.check()?;
+        }
+        counter.examined();
+        let rest = total - i - 1;
 
+        counter.compared();
         if block.contains(&target) {
+            trace!(block = ?block, "block covers the rest of the span");
             target.eliminate();
+            counter.skipped(rest);
             break;
         }
 
+        counter.compared();
         if block.overlaps_at_start(&target) {
+            trace!(block = ?block, "block overlaps the start");
             target.shorten(&block);
             continue;
         }
 
+        counter.compared();
         if block.is_contained_in(&target) {
+            trace!(block = ?block, "block is within the span");
             let slot = Slot::create_from(&target, &block)?;
-            slots.push(Out::create_from_slot(slot));
+            slots.push(slot);
             target.shorten(&block);
             continue;
         }
 
+        counter.compared();
         if block.overlaps_at_end(&target) {
+            trace!(block = ?block, "block overlaps the end");
             let slot = Slot::create_from(&target, &block)?;
-            slots.push(Out::create_from_slot(slot));
+            slots.push(slot);
             target.eliminate();
+            counter.skipped(rest);
             break;
         }
-    }
 
-    if !target.remain() {
-        return Ok(slots);
+        // The block is outside of what remains of the span.
+        counter.skipped(1);
     }
 
-    let slot = target.to_slot()?;
-    slots.push(Out::create_from_slot(slot));
+    if target.remain() {
+        slots.push(target.to_slot()?);
+    }
+    debug!(slots = slots.len(), "slots found");
     Ok(slots)
 }
 
 #[cfg(test)]
+#[allow(clippy::unit_arg)]
 mod tests {
     use crate::{Block, Period};
 
@@ -271,4 +361,31 @@ mod tests {
             }
         })
     }
+
+    #[test]
+    fn test_find_with() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let span = Span::new(now, now + Duration::hours(8))?;
+        let inputs = vec![
+            MockInput::new(now, 1, 2),
+            MockInput {
+                start_at: now + Duration::hours(2) + Duration::minutes(30),
+                end_at: now + Duration::hours(3),
+            },
+        ];
+
+        let options = FindOptions::new().min_duration(Duration::hours(1));
+        let slots: Vec<MockOutput> = find_with(span.clone(), inputs.clone(), &options)?;
+        let expected = [MockOutput::new(now, 0, 1), MockOutput::new(now, 3, 8)];
+        assert_eq!(slots.len(), expected.len());
+        for (actual, expected) in slots.iter().zip(expected.iter()) {
+            assert_eq!(actual.start(), expected.start());
+            assert_eq!(actual.end(), expected.end());
+        }
+
+        let options = FindOptions::new().min_duration(Duration::zero());
+        let result: Result<Vec<MockOutput>, PeriodError> = find_with(span, inputs, &options);
+        assert!(result.is_err(), "Invalid options should fail");
+        Ok(())
+    }
 }
@@ -0,0 +1,102 @@
+use crate::periods::{
+    period::{Input, Period, PeriodError},
+    slot::Slot,
+    span::Span,
+};
+
+use super::{find::find_with, options::FindOptions};
+
+/// A slot with its position in the result and a stable identifier.
+#[derive(Debug, Clone)]
+pub struct IdentifiedSlot {
+    /// Position of the slot in the result, from 1.
+    pub ordinal: usize,
+    /// Identifier derived from the span and the slot. The same span and slot always get the same id,
+    /// across calls, processes, and versions of this crate.
+    pub id: String,
+    pub slot: Slot,
+}
+
+// FNV-1a, which is fixed unlike the hasher of std.
+fn fnv1a(values: &[i64]) -> u64 {
+    values
+        .iter()
+        .flat_map(|v| v.to_be_bytes())
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+        })
+}
+
+// Stable identifier of the slot within the span: 16 hex digits of a hash of their times.
+// Timezones don't matter; only the instants are hashed.
+pub fn slot_id<P: Period>(span: &Span, slot: &P) -> String {
+    let (span_start, span_end) = span.epoch_millis();
+    let (start, end) = slot.epoch_millis();
+    format!("{:016x}", fnv1a(&[span_start, span_end, start, end]))
+}
+
+// Same as find_with, and each slot gets its ordinal and a stable identifier.
+pub fn find_identified<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+    options: &FindOptions,
+) -> Result<Vec<IdentifiedSlot>, PeriodError> {
+    let slots: Vec<Slot> = find_with(span.clone(), inputs, options)?;
+    Ok(slots
+        .into_iter()
+        .enumerate()
+        .map(|(i, slot)| IdentifiedSlot {
+            ordinal: i + 1,
+            id: slot_id(&span, &slot),
+            slot,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_find_identified() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9), dt(17));
+        let blocks = || -> Result<Vec<Block>, PeriodError> {
+            Ok(vec![
+                Block::new(dt(10), dt(11))?,
+                Block::new(dt(13), dt(14))?,
+            ])
+        };
+
+        let first = find_identified(span()?, blocks()?, &FindOptions::new())?;
+        let again = find_identified(span()?, blocks()?, &FindOptions::new())?;
+        assert_eq!(
+            first.iter().map(|s| s.ordinal).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        for (a, b) in first.iter().zip(again.iter()) {
+            assert_eq!(a.id, b.id, "Ids should be stable across calls");
+        }
+        assert_ne!(first[0].id, first[1].id);
+        // Fixed, so ids stay valid across versions.
+        assert_eq!(first[0].id, "4d65a4df488393d6");
+        assert_eq!(first[0].id.len(), 16);
+
+        let utc = Span::new(
+            dt(9).with_timezone(&Tz::UTC),
+            dt(17).with_timezone(&Tz::UTC),
+        )?;
+        assert_eq!(slot_id(&utc, &first[0].slot), first[0].id);
+        let other = Span::new(dt(9), dt(18))?;
+        assert_ne!(slot_id(&other, &first[0].slot), first[0].id);
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use std::{collections::HashMap, hash::Hash, thread};
+
+use crate::{
+    clock::FixedClock,
+    periods::{
+        period::{Input, Output, PeriodError},
+        span::Span,
+    },
+};
+
+use super::{find::search, options::FindOptions};
+
+// Same as find_with for the inputs of each key (per employee, per room, ...). The options are
+// validated once, and the clock is read once so that all keys are searched at the same now.
+pub fn find_for_all<K: Eq + Hash, In: Input, Out: Output>(
+    span: Span,
+    inputs: HashMap<K, Vec<In>>,
+    options: &FindOptions,
+) -> Result<HashMap<K, Vec<Out>>, PeriodError> {
+    options.validate()?;
+    let options = &fixed(options);
+    inputs
+        .into_iter()
+        .map(|(key, inputs)| Ok((key, find_one(&span, inputs, options)?)))
+        .collect()
+}
+
+// Same as find_for_all, but the keys are split across threads, as many as the available parallelism
+// and no more than the keys.
+pub fn find_for_all_parallel<K, In, Out>(
+    span: Span,
+    inputs: HashMap<K, Vec<In>>,
+    options: &FindOptions,
+) -> Result<HashMap<K, Vec<Out>>, PeriodError>
+where
+    K: Eq + Hash + Send,
+    In: Input + Send,
+    Out: Output + Send,
+{
+    options.validate()?;
+    let options = &fixed(options);
+    let threads = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(inputs.len());
+    let mut chunks: Vec<Vec<(K, Vec<In>)>> = (0..threads).map(|_| Vec::new()).collect();
+    for (i, entry) in inputs.into_iter().enumerate() {
+        chunks[i % threads].push(entry);
+    }
+    let span = &span;
+    thread::scope(|scope| {
+        let handles = chunks
+            .into_iter()
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .into_iter()
+                        .map(|(key, inputs)| Ok((key, find_one(span, inputs, options)?)))
+                        .collect::<Result<Vec<_>, PeriodError>>()
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut results = HashMap::new();
+        for handle in handles {
+            let found = handle
+                .join()
+                .unwrap_or_else(|e| std::panic::resume_unwind(e))?;
+            results.extend(found);
+        }
+        Ok(results)
+    })
+}
+
+// The options with the clock stopped at its current time.
+fn fixed(options: &FindOptions) -> FindOptions {
+    options.clone().clock(FixedClock::new(options.now()))
+}
+
+// find_with for one key.
+fn find_one<In: Input, Out: Output>(
+    span: &Span,
+    inputs: Vec<In>,
+    options: &FindOptions,
+) -> Result<Vec<Out>, PeriodError> {
+    let slots = search(span.clone(), inputs, options, &mut (), |_, _| {})?
+        .map_or_else(Vec::new, |(_, slots)| slots);
+    Ok(slots.into_iter().map(Out::create_from_slot).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        finder::find::find_with,
+        periods::{block::Block, period::Period, slot::Slot},
+    };
+    use chrono::{DateTime, Duration, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    fn times(slots: &[Slot]) -> Vec<(DateTime<Tz>, DateTime<Tz>)> {
+        slots.iter().map(|s| (s.start(), s.end())).collect()
+    }
+
+    #[test]
+    fn test_find_for_all() -> Result<(), PeriodError> {
+        let calendars = || -> Result<HashMap<String, Vec<Block>>, PeriodError> {
+            (0..20)
+                .map(|i| {
+                    let start = dt(9) + Duration::minutes(15 * i);
+                    let block = Block::new(start, start + Duration::hours(1))?;
+                    Ok((format!("room-{}", i), vec![block]))
+                })
+                .collect()
+        };
+        let span = || Span::new(dt(9), dt(17));
+        let options = FindOptions::new().min_duration(Duration::minutes(30));
+
+        let sequential: HashMap<String, Vec<Slot>> = find_for_all(span()?, calendars()?, &options)?;
+        let parallel: HashMap<String, Vec<Slot>> =
+            find_for_all_parallel(span()?, calendars()?, &options)?;
+        assert_eq!(sequential.len(), 20);
+        assert_eq!(parallel.len(), 20);
+        for (key, blocks) in calendars()? {
+            let expected: Vec<Slot> = find_with(span()?, blocks, &options)?;
+            assert_eq!(times(&sequential[&key]), times(&expected), "{}", key);
+            assert_eq!(times(&parallel[&key]), times(&expected), "{}", key);
+        }
+
+        let empty: HashMap<String, Vec<Slot>> =
+            find_for_all_parallel(span()?, HashMap::<String, Vec<Block>>::new(), &options)?;
+        assert!(empty.is_empty());
+        Ok(())
+    }
+}
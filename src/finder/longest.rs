@@ -0,0 +1,85 @@
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+use crate::periods::{
+    period::{Input, Output, Period, PeriodError},
+    slot::Slot,
+    span::Span,
+};
+
+use super::page::find_iter;
+
+// The k longest slots of the span excluding the inputs, the longest first (earlier among ties).
+// The slots are scanned lazily keeping only the best k, so a long span is not collected.
+pub fn find_top_k_longest<In: Input, Out: Output>(
+    span: Span,
+    inputs: &[In],
+    k: usize,
+) -> Result<Vec<Out>, PeriodError> {
+    if k == 0 {
+        return Ok(Vec::new());
+    }
+    // A min-heap of (length, earlier start first), so the worst of the best k is on top.
+    let mut best = BinaryHeap::with_capacity(k + 1);
+    for slot in find_iter(span, inputs)? {
+        best.push(Reverse((
+            slot.end() - slot.start(),
+            Reverse(slot.start()),
+            slot.end(),
+        )));
+        if best.len() > k {
+            best.pop();
+        }
+    }
+    best.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse((_, Reverse(start), end))| Slot::new(start, end).map(Out::create_from_slot))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_find_top_k_longest() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(0), dt(20));
+        // Free: 0-1, 2-5, 6-8, 9-12, 13-20
+        let blocks = vec![
+            Block::new(dt(1), dt(2))?,
+            Block::new(dt(5), dt(6))?,
+            Block::new(dt(8), dt(9))?,
+            Block::new(dt(12), dt(13))?,
+        ];
+        let cases = vec![
+            ("Top 3 with a tie", 3, vec![(13, 20), (2, 5), (9, 12)]),
+            (
+                "More than the slots",
+                9,
+                vec![(13, 20), (2, 5), (9, 12), (6, 8), (0, 1)],
+            ),
+            ("None", 0, vec![]),
+        ];
+        for (name, k, expected) in cases {
+            let slots: Vec<Slot> = find_top_k_longest(span()?, &blocks, k)?;
+            let expected = expected
+                .into_iter()
+                .map(|(s, e)| (dt(s), dt(e)))
+                .collect::<Vec<_>>();
+            let actual = slots
+                .iter()
+                .map(|s| (s.start(), s.end()))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{}", name);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,202 @@
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "std")]
+use crate::periods::{
+    period::{Input, Output, PeriodError},
+    span::Span,
+};
+
+#[cfg(feature = "std")]
+use super::{find::search, options::FindOptions};
+
+/// Work done by a search, to monitor how it scales as calendars grow.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Blocks converted and compared with the span.
+    pub blocks_examined: usize,
+    /// Blocks that did not change the result: outside of what remained of the span,
+    /// or never examined because the span was used up.
+    pub blocks_skipped: usize,
+    /// Inputs dropped as duplicates of another input.
+    pub duplicates: usize,
+    /// Inputs dropped as ended before now (drop_past_inputs).
+    pub past_inputs: usize,
+    /// Comparisons of a block with the span.
+    pub comparisons: usize,
+    /// Wall time of the whole search, including the options.
+    pub elapsed: Duration,
+}
+
+/// Records the work done by the sweep. () records nothing.
+pub(crate) trait Counter {
+    fn examined(&mut self) {}
+    fn skipped(&mut self, _count: usize) {}
+    fn deduplicated(&mut self, _count: usize) {}
+    fn compared(&mut self) {}
+    #[cfg(feature = "std")]
+    fn dropped_past(&mut self, _count: usize) {}
+}
+
+impl Counter for () {}
+
+impl Counter for Metrics {
+    fn examined(&mut self) {
+        self.blocks_examined += 1;
+    }
+
+    fn skipped(&mut self, count: usize) {
+        self.blocks_skipped += count;
+    }
+
+    fn deduplicated(&mut self, count: usize) {
+        self.duplicates += count;
+    }
+
+    fn compared(&mut self) {
+        self.comparisons += 1;
+    }
+
+    #[cfg(feature = "std")]
+    fn dropped_past(&mut self, count: usize) {
+        self.past_inputs += count;
+    }
+}
+
+// Same as find_with, and the metrics of the search are returned alongside.
+#[cfg(feature = "std")]
+pub fn find_with_metrics<In: Input, Out: Output>(
+    span: Span,
+    inputs: Vec<In>,
+    options: &FindOptions,
+) -> Result<(Vec<Out>, Metrics), PeriodError> {
+    let started = Instant::now();
+    let mut metrics = Metrics::default();
+    let slots = search(span, inputs, options, &mut metrics, |_, _| {})?
+        .map_or_else(Vec::new, |(_, slots)| slots);
+    metrics.elapsed = started.elapsed();
+    Ok((
+        slots.into_iter().map(Out::create_from_slot).collect(),
+        metrics,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clock::FixedClock,
+        periods::{block::Block, period::Period, slot::Slot},
+    };
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    fn block(now: DateTime<Tz>, start: i64, end: i64) -> Result<Block, PeriodError> {
+        Block::new(
+            now + chrono::Duration::hours(start),
+            now + chrono::Duration::hours(end),
+        )
+    }
+
+    #[test]
+    fn test_find_with_metrics() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let span = || Span::new(now, now + chrono::Duration::hours(8));
+
+        struct TestCase {
+            name: &'static str,
+            blocks: Vec<Block>,
+            slots: usize,
+            examined: usize,
+            skipped: usize,
+            duplicates: usize,
+            comparisons: usize,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "No blocks",
+                blocks: vec![],
+                slots: 1,
+                examined: 0,
+                skipped: 0,
+                duplicates: 0,
+                comparisons: 0,
+            },
+            TestCase {
+                name: "Blocks within the span",
+                blocks: vec![block(now, 1, 2)?, block(now, 3, 4)?],
+                slots: 3,
+                examined: 2,
+                skipped: 0,
+                duplicates: 0,
+                comparisons: 6,
+            },
+            TestCase {
+                name: "Blocks after the span is used up",
+                blocks: vec![block(now, -1, 9)?, block(now, 1, 2)?, block(now, 3, 4)?],
+                slots: 0,
+                examined: 1,
+                skipped: 2,
+                duplicates: 0,
+                comparisons: 1,
+            },
+            TestCase {
+                name: "Block before the span",
+                blocks: vec![block(now, -3, -2)?, block(now, 7, 9)?],
+                slots: 1,
+                examined: 2,
+                skipped: 1,
+                duplicates: 0,
+                comparisons: 8,
+            },
+            TestCase {
+                name: "Duplicated blocks",
+                blocks: vec![block(now, 1, 2)?, block(now, 3, 4)?, block(now, 1, 2)?],
+                slots: 3,
+                examined: 2,
+                skipped: 0,
+                duplicates: 1,
+                comparisons: 6,
+            },
+        ];
+
+        for case in cases {
+            let (slots, metrics): (Vec<Slot>, Metrics) =
+                find_with_metrics(span()?, case.blocks, &FindOptions::new())?;
+            assert_eq!(slots.len(), case.slots, "{}", case.name);
+            assert_eq!(metrics.blocks_examined, case.examined, "{}", case.name);
+            assert_eq!(metrics.blocks_skipped, case.skipped, "{}", case.name);
+            assert_eq!(metrics.duplicates, case.duplicates, "{}", case.name);
+            assert_eq!(metrics.comparisons, case.comparisons, "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_with_metrics_past_inputs() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let span = Span::new(now, now + chrono::Duration::hours(8))?;
+        // History ending before now, and one block ending after it.
+        let blocks = vec![
+            block(now, -48, -47)?,
+            block(now, -2, -1)?,
+            block(now, -1, 1)?,
+        ];
+        let options = FindOptions::new()
+            .clock(FixedClock::new(now))
+            .clamp_to_now()
+            .drop_past_inputs();
+        let (slots, metrics): (Vec<Slot>, Metrics) =
+            find_with_metrics(span.clone(), blocks.clone(), &options)?;
+        assert_eq!(metrics.past_inputs, 2);
+        assert_eq!(metrics.blocks_examined, 1);
+        assert_eq!(slots[0].start(), now + chrono::Duration::hours(1));
+
+        let (_, metrics): (Vec<Slot>, Metrics) =
+            find_with_metrics(span, blocks, &FindOptions::new())?;
+        assert_eq!(metrics.past_inputs, 0, "Kept unless set");
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use crate::periods::{
+    period::{Input, Period, PeriodError},
+    slot::Slot,
+    span::Span,
+};
+
+use super::find::find_slots;
+
+/// A slot with the span it was found in.
+#[derive(Debug, Clone)]
+pub struct LabeledSlot<L> {
+    /// Label of the span given by the caller.
+    pub label: L,
+    /// Index of the span.
+    pub span: usize,
+    pub slot: Slot,
+}
+
+// Calculate available time slots of each span (e.g. Mon/Tue/Wed windows) excluding the same inputs.
+// Slots are returned in the order of the spans, then by start time, and are labeled with their span.
+// Spans may overlap; each span is searched on its own.
+pub fn find_multi<L: Clone, In: Input>(
+    spans: Vec<(L, Span)>,
+    inputs: Vec<In>,
+) -> Result<Vec<LabeledSlot<L>>, PeriodError> {
+    let blocks = inputs
+        .iter()
+        .map(|input| input.to_block())
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut labeled = Vec::new();
+    for (i, (label, span)) in spans.into_iter().enumerate() {
+        let within = blocks
+            .iter()
+            .filter(|b| b.start() < span.end() && span.start() < b.end())
+            .cloned()
+            .collect::<Vec<_>>();
+        labeled.extend(
+            find_slots(span, within)?
+                .into_iter()
+                .map(|slot| LabeledSlot {
+                    label: label.clone(),
+                    span: i,
+                    slot,
+                }),
+        );
+    }
+    Ok(labeled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(day: u32, hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, day, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_find_multi() -> Result<(), PeriodError> {
+        let spans = vec![
+            ("Mon", Span::new(dt(1, 9), dt(1, 12))?),
+            ("Tue", Span::new(dt(2, 9), dt(2, 12))?),
+            ("Mon afternoon", Span::new(dt(1, 11), dt(1, 14))?),
+        ];
+        let blocks = vec![
+            Block::new(dt(1, 10), dt(1, 11))?,
+            Block::new(dt(1, 13), dt(2, 10))?,
+        ];
+
+        let actual = find_multi(spans, blocks)?
+            .iter()
+            .map(|s| (s.label, s.span, s.slot.start(), s.slot.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![
+                ("Mon", 0, dt(1, 9), dt(1, 10)),
+                ("Mon", 0, dt(1, 11), dt(1, 12)),
+                ("Tue", 1, dt(2, 10), dt(2, 12)),
+                ("Mon afternoon", 2, dt(1, 11), dt(1, 13)),
+            ]
+        );
+
+        let empty = find_multi::<&str, Block>(vec![], vec![Block::new(dt(1, 9), dt(1, 10))?])?;
+        assert!(empty.is_empty());
+        Ok(())
+    }
+}
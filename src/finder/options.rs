@@ -0,0 +1,1545 @@
+use std::{
+    cmp::Reverse,
+    fmt::{self, Debug},
+    sync::Arc,
+};
+
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+
+use super::{
+    explain::Reason,
+    filter::{
+        align, bill, lead, limit_per_day, protect_focus_time, quantize, split, spread, stride,
+        tolerate, work_intervals,
+    },
+    metrics::Counter,
+};
+#[cfg(feature = "humantime")]
+use crate::periods::duration::parse_duration;
+use crate::{
+    cancel::Cancellation,
+    clock::{Clock, SystemClock},
+    periods::{
+        period::{Input, Period, PeriodError, Precision},
+        slot::Slot,
+        span::Span,
+    },
+    scheduler::score::{rank_slots, SlotScorer},
+};
+
+/// How slots starting within the lead time are handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LeadTimePolicy {
+    /// Drop the whole slot.
+    Drop,
+    /// Keep the part of the slot after the lead time.
+    Trim,
+}
+
+/// How times are rounded to the granularity.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Rounding {
+    /// Block starts are rounded down and ends up, so free time is never overstated. (default)
+    #[default]
+    Outward,
+    /// To the nearest multiple. Halves are rounded up.
+    Nearest,
+}
+
+/// How a span longer than the horizon is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HorizonPolicy {
+    /// Fail with SpanTooLong.
+    Reject,
+    /// Search only the horizon from the start of the span.
+    Truncate,
+}
+
+/// How inputs entirely outside the span are handled, e.g. to catch a wrong date range queried.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutOfSpanPolicy {
+    /// Ignore them. (default)
+    #[default]
+    Allow,
+    /// Log a warning with the index of each one (with the tracing feature), and search.
+    Warn,
+    /// Fail with OutOfSpan and the index of the first one.
+    Reject,
+}
+
+/// What find_with returns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    /// The contiguous free time as is. The options applied to slots are ignored.
+    Gaps,
+    /// Slots that can be booked, after all options are applied. (default)
+    #[default]
+    Bookable,
+}
+
+/// Which slots max_results keeps when there are more.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Selection {
+    /// The first ones in the order. (default)
+    #[default]
+    First,
+    /// Ones spread evenly over the local days, and evenly within each day, so suggestions
+    /// are not all on the first morning. They are returned in the order.
+    Spread,
+}
+
+/// Order of the slots returned.
+#[derive(Clone, Default)]
+pub enum SlotOrder {
+    /// By start time. (default)
+    #[default]
+    Start,
+    /// By duration, the longest first. Ties are ordered by start time.
+    LongestFirst,
+    /// By the score, the highest first. Ties are ordered by start time.
+    Score(Arc<dyn SlotScorer + Send + Sync>),
+}
+
+impl Debug for SlotOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlotOrder::Start => write!(f, "Start"),
+            SlotOrder::LongestFirst => write!(f, "LongestFirst"),
+            SlotOrder::Score(_) => write!(f, "Score"),
+        }
+    }
+}
+
+/// Options for find_with. Each option is disabled unless it is set.
+#[derive(Debug, Clone, Default)]
+pub struct FindOptions {
+    precision: Precision,
+    out_of_span: OutOfSpanPolicy,
+    tolerance: Option<Duration>,
+    granularity: Option<(Duration, Rounding)>,
+    min_duration: Option<Duration>,
+    max_duration: Option<Duration>,
+    work_intervals: Option<(Duration, Duration)>,
+    billing_unit: Option<Duration>,
+    alignment: Option<Duration>,
+    clock: Option<Arc<dyn Clock>>,
+    clamp_to_now: bool,
+    drop_past_inputs: bool,
+    horizon: Option<(Duration, HorizonPolicy)>,
+    lead_time: Option<(Duration, LeadTimePolicy)>,
+    max_per_day: Option<usize>,
+    focus_time: Option<(Duration, Duration)>,
+    stride: Option<(Duration, Duration)>,
+    order: SlotOrder,
+    max_results: Option<usize>,
+    selection: Selection,
+    mode: OutputMode,
+    cancellation: Cancellation,
+}
+
+impl FindOptions {
+    /// constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How inputs with sub-second precision are handled. Reject fails the search,
+    /// and Truncate truncates the ends of the free time (same as truncating the span and inputs).
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// How inputs entirely outside the span are handled. Strict callers use Warn or Reject.
+    pub fn out_of_span(mut self, policy: OutOfSpanPolicy) -> Self {
+        self.out_of_span = policy;
+        self
+    }
+
+    /// Overlaps of blocks with free time up to the tolerance (e.g. 60 seconds) are ignored,
+    /// so second-level jitter in the inputs does not trim or split otherwise clean free time.
+    /// Free time extends into the busy time next to it by the tolerance, and busy time no longer
+    /// than twice the tolerance no longer splits it. Use granularity to round the ends back.
+    pub fn overlap_tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = Some(tolerance);
+        self
+    }
+
+    /// Quantize times to multiples of the granularity in local time (e.g. a minute, 5 minutes),
+    /// so sub-minute jitter in the inputs does not produce slots of a few seconds.
+    /// This is applied after overlap_tolerance and precision, before the other options, also to gaps.
+    pub fn granularity(mut self, granularity: Duration, rounding: Rounding) -> Self {
+        self.granularity = Some((granularity, rounding));
+        self
+    }
+
+    /// Drop slots shorter than the duration.
+    pub fn min_duration(mut self, duration: Duration) -> Self {
+        self.min_duration = Some(duration);
+        self
+    }
+
+    /// Split slots longer than the duration into consecutive pieces of at most the duration
+    /// (e.g. never offer more than 4 hours in a row). This is applied before min_duration,
+    /// so a short remainder can be dropped by it.
+    pub fn max_duration(mut self, duration: Duration) -> Self {
+        self.max_duration = Some(duration);
+        self
+    }
+
+    /// Split slots into work intervals of at most work separated by breaks of rest, e.g. 50 minutes
+    /// of work and 10 minutes of break, and offer only the work intervals. A last interval shorter
+    /// than work is kept. This is applied before max_duration, so the breaks are always kept.
+    pub fn work_intervals(mut self, work: Duration, rest: Duration) -> Self {
+        self.work_intervals = Some((work, rest));
+        self
+    }
+
+    /// Round slots down to whole billing units (e.g. 15 minutes) from their start, and drop
+    /// slots shorter than a unit. This is applied after max_duration and before min_duration.
+    pub fn billing_unit(mut self, unit: Duration) -> Self {
+        self.billing_unit = Some(unit);
+        self
+    }
+
+    /// Align the start time of slots to multiples of the step in local time (e.g. every 15 minutes from midnight).
+    pub fn alignment(mut self, step: Duration) -> Self {
+        self.alignment = Some(step);
+        self
+    }
+
+    /// The clock used by options relative to now. (The system clock by default)
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Never search before now. The start of the span is moved to now if it is in the past.
+    pub fn clamp_to_now(mut self) -> Self {
+        self.clamp_to_now = true;
+        self
+    }
+
+    /// Drop inputs that ended before now, so history in the inputs costs nothing.
+    /// Time before now is then free unless clamp_to_now is also set.
+    /// The number of inputs dropped is reported by find_with_metrics.
+    pub fn drop_past_inputs(mut self) -> Self {
+        self.drop_past_inputs = true;
+        self
+    }
+
+    /// Guard against searching too far: a span longer than the horizon is rejected or truncated.
+    /// This is checked after clamp_to_now.
+    pub fn horizon(mut self, max: Duration, policy: HorizonPolicy) -> Self {
+        self.horizon = Some((max, policy));
+        self
+    }
+
+    /// Slots must not start within the duration from now (e.g. no bookings less than 2 hours ahead).
+    /// This is applied before alignment and min_duration.
+    pub fn lead_time(mut self, duration: Duration, policy: LeadTimePolicy) -> Self {
+        self.lead_time = Some((duration, policy));
+        self
+    }
+
+    /// Limit the number of slots starting on each local calendar day.
+    /// The slots kept are spread across the day instead of taking the earliest ones.
+    pub fn max_per_day(mut self, max: usize) -> Self {
+        self.max_per_day = Some(max);
+        self
+    }
+
+    /// Protect focus time: drop slots shorter than min_slot, and slots that would leave
+    /// free time shorter than min_fragment before or after them, so a day is not shredded into fragments.
+    pub fn focus_time(mut self, min_slot: Duration, min_fragment: Duration) -> Self {
+        self.focus_time = Some((min_slot, min_fragment));
+        self
+    }
+
+    /// Offer candidates of the duration starting at every step within each slot, e.g. a 1 hour
+    /// appointment at 9:00, 9:15, 9:30, ... for a step of 15 minutes. The candidates overlap,
+    /// and slots shorter than the duration are dropped. This is applied after focus_time.
+    pub fn stride(mut self, duration: Duration, step: Duration) -> Self {
+        self.stride = Some((duration, step));
+        self
+    }
+
+    /// Order of the slots returned. Slots are sorted by start time unless this is set.
+    /// This is applied after all other options.
+    pub fn order(mut self, order: SlotOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Return at most max slots, the first ones in the order unless the selection is set.
+    /// This is applied last.
+    pub fn max_results(mut self, max: usize) -> Self {
+        self.max_results = Some(max);
+        self
+    }
+
+    /// Which slots max_results keeps when there are more.
+    pub fn selection(mut self, selection: Selection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Whether raw gaps or bookable slots are returned. Only clamp_to_now, overlap_tolerance,
+    /// precision and granularity are applied to gaps.
+    pub fn mode(mut self, mode: OutputMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Abandon the search with Cancelled or TimedOut, e.g. when the client disconnects.
+    pub fn cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Current time of the clock.
+    pub fn now(&self) -> DateTime<Tz> {
+        match &self.clock {
+            Some(clock) => clock.now(),
+            None => SystemClock::default().now(),
+        }
+    }
+
+    /// Check that the options are consistent.
+    pub fn validate(&self) -> Result<(), PeriodError> {
+        let focus_time = self.focus_time.map(|(slot, fragment)| slot.min(fragment));
+        let stride = self.stride.map(|(duration, step)| duration.min(step));
+        let durations = [
+            self.tolerance,
+            self.horizon.map(|(d, _)| d),
+            self.granularity.map(|(d, _)| d),
+            self.min_duration,
+            self.max_duration,
+            self.work_intervals.map(|(work, rest)| work.min(rest)),
+            self.billing_unit,
+            self.alignment,
+            focus_time,
+            stride,
+        ];
+        if let Some(d) = durations
+            .into_iter()
+            .flatten()
+            .find(|d| *d <= Duration::zero())
+        {
+            return Err(PeriodError::InvalidDuration(d));
+        }
+        match self.lead_time {
+            Some((d, _)) if d < Duration::zero() => Err(PeriodError::InvalidDuration(d)),
+            _ => Ok(()),
+        }
+    }
+
+    /// The cancellation of the search.
+    pub(crate) fn cancel(&self) -> &Cancellation {
+        &self.cancellation
+    }
+
+    /// Check the span and inputs against the out-of-span and precision policies.
+    pub(crate) fn check_inputs<P: Period>(
+        &self,
+        span: &Span,
+        inputs: &[P],
+    ) -> Result<(), PeriodError> {
+        match self.out_of_span {
+            OutOfSpanPolicy::Allow => {}
+            OutOfSpanPolicy::Warn => {
+                let outside = span.outside(inputs);
+                if !outside.is_empty() {
+                    warn!(?outside, "inputs outside the span");
+                }
+            }
+            OutOfSpanPolicy::Reject => {
+                if let Some(&index) = span.outside(inputs).first() {
+                    return Err(PeriodError::OutOfSpan { index });
+                }
+            }
+        }
+        let times = inputs.iter().flat_map(|p| [p.start(), p.end()]);
+        self.check_precision([span.start(), span.end()].into_iter().chain(times))
+    }
+
+    /// Check the times against the precision policy.
+    pub(crate) fn check_precision(
+        &self,
+        times: impl IntoIterator<Item = DateTime<Tz>>,
+    ) -> Result<(), PeriodError> {
+        if self.precision != Precision::Reject {
+            return Ok(());
+        }
+        times
+            .into_iter()
+            .try_for_each(|dt| self.precision.apply(dt).map(|_| ()))
+    }
+
+    /// The policy of inputs outside the span.
+    #[cfg(feature = "serde")]
+    pub(crate) fn out_of_span_policy(&self) -> OutOfSpanPolicy {
+        self.out_of_span
+    }
+
+    /// Whether drop_past_inputs is set.
+    pub(crate) fn drops_past_inputs(&self) -> bool {
+        self.drop_past_inputs
+    }
+
+    /// Drop the inputs that ended before now if drop_past_inputs is set.
+    pub(crate) fn drop_past<In: Input, C: Counter>(
+        &self,
+        mut inputs: Vec<In>,
+        counter: &mut C,
+    ) -> Vec<In> {
+        if self.drop_past_inputs {
+            let now = self.now();
+            let total = inputs.len();
+            inputs.retain(|input| now <= input.end());
+            counter.dropped_past(total - inputs.len());
+        }
+        inputs
+    }
+
+    /// Adjust the span before searching. None if nothing remains to search.
+    pub(crate) fn prepare(&self, mut span: Span) -> Result<Option<Span>, PeriodError> {
+        if self.clamp_to_now {
+            let now = self.now().with_timezone(&span.start().timezone());
+            if span.start() < now {
+                match Span::new(now, span.end()) {
+                    Ok(clamped) => span = clamped,
+                    Err(_) => return Ok(None),
+                }
+            }
+        }
+        match self.horizon {
+            Some((max, HorizonPolicy::Reject)) => {
+                Span::new_limited(span.start(), span.end(), max).map(Some)
+            }
+            Some((max, HorizonPolicy::Truncate)) => {
+                Span::new_clamped(span.start(), span.end(), max).map(Some)
+            }
+            None => Ok(Some(span)),
+        }
+    }
+
+    /// Extend the gaps of the span into the busy time next to them by the overlap tolerance.
+    pub(crate) fn tolerate(&self, span: &Span, gaps: Vec<Slot>) -> Vec<Slot> {
+        match self.tolerance {
+            Some(tolerance) => tolerate(gaps, span, tolerance),
+            None => gaps,
+        }
+    }
+
+    /// Apply the options to the slots found in the span. (This assumes the slots are sorted)
+    pub(crate) fn apply(&self, span: &Span, gaps: Vec<Slot>) -> Vec<Slot> {
+        self.apply_each(span, gaps, |_, _| {})
+    }
+
+    /// Same as apply, and inspect is called with the slots remaining after each option.
+    pub(crate) fn apply_each(
+        &self,
+        span: &Span,
+        gaps: Vec<Slot>,
+        mut inspect: impl FnMut(Reason, &[Slot]),
+    ) -> Vec<Slot> {
+        let mut inspect = |reason: Reason, slots: &[Slot]| {
+            trace!(option = ?reason, remaining = slots.len(), "option applied");
+            inspect(reason, slots);
+        };
+        let gaps = self.tolerate(span, gaps);
+        let gaps = match self.precision {
+            Precision::Truncate => {
+                let gaps: Vec<Slot> = gaps
+                    .iter()
+                    .filter_map(|gap| {
+                        Slot::new_with_precision(gap.start(), gap.end(), self.precision).ok()
+                    })
+                    .collect();
+                inspect(Reason::Precision, &gaps);
+                gaps
+            }
+            _ => gaps,
+        };
+        let gaps = match self.granularity {
+            Some((step, rounding)) => {
+                let gaps: Vec<Slot> = gaps
+                    .iter()
+                    .filter_map(|gap| quantize(gap, step, rounding))
+                    .collect();
+                inspect(Reason::Granularity, &gaps);
+                gaps
+            }
+            None => gaps,
+        };
+        if self.mode == OutputMode::Gaps {
+            return gaps;
+        }
+        let mut slots = gaps.clone();
+        if let Some((d, policy)) = self.lead_time {
+            let cutoff = self.now() + d;
+            slots = slots
+                .into_iter()
+                .filter_map(|slot| lead(slot, cutoff, policy))
+                .collect();
+            inspect(Reason::LeadTime, &slots);
+        }
+        if let Some(step) = self.alignment {
+            slots = slots.iter().filter_map(|slot| align(slot, step)).collect();
+            inspect(Reason::Alignment, &slots);
+        }
+        if let Some((work, rest)) = self.work_intervals {
+            slots = slots
+                .iter()
+                .flat_map(|slot| work_intervals(slot, work, rest))
+                .collect();
+            inspect(Reason::Break, &slots);
+        }
+        if let Some(max) = self.max_duration {
+            slots = slots.iter().flat_map(|slot| split(slot, max)).collect();
+            inspect(Reason::MaxDuration, &slots);
+        }
+        if let Some(unit) = self.billing_unit {
+            slots = slots.iter().filter_map(|slot| bill(slot, unit)).collect();
+            inspect(Reason::Billing, &slots);
+        }
+        if let Some(min) = self.min_duration {
+            slots.retain(|slot| slot.end() - slot.start() >= min);
+            inspect(Reason::MinDuration, &slots);
+        }
+        if let Some((min_slot, min_fragment)) = self.focus_time {
+            slots = protect_focus_time(slots, &gaps, min_slot, min_fragment);
+            inspect(Reason::FocusTime, &slots);
+        }
+        if let Some((duration, step)) = self.stride {
+            slots = slots
+                .iter()
+                .flat_map(|slot| stride(slot, duration, step))
+                .collect();
+            inspect(Reason::Stride, &slots);
+        }
+        if let Some(max) = self.max_per_day {
+            slots = limit_per_day(slots, max);
+            inspect(Reason::MaxPerDay, &slots);
+        }
+        let mut slots = match &self.order {
+            SlotOrder::Start => slots,
+            SlotOrder::LongestFirst => {
+                slots.sort_by_key(|slot| Reverse(slot.end() - slot.start()));
+                slots
+            }
+            SlotOrder::Score(scorer) => rank_slots(slots, scorer.as_ref()),
+        };
+        // Inspected only when slots are cut, so callers can tell the result was truncated.
+        if let Some(max) = self.max_results.filter(|max| slots.len() > *max) {
+            match self.selection {
+                Selection::First => slots.truncate(max),
+                Selection::Spread => {
+                    let mut sorted = slots.clone();
+                    sorted.sort_by_key(|slot| (slot.start(), slot.end()));
+                    let mut picked = spread(sorted, max);
+                    slots.retain(|slot| {
+                        let same = |p: &Slot| p.start() == slot.start() && p.end() == slot.end();
+                        match picked.iter().position(same) {
+                            Some(i) => {
+                                picked.swap_remove(i);
+                                true
+                            }
+                            None => false,
+                        }
+                    });
+                }
+            }
+            inspect(Reason::MaxResults, &slots);
+        }
+        slots
+    }
+}
+
+/// Setters taking durations like "90m" or "1h30m", e.g. from env vars or config files.
+#[cfg(feature = "humantime")]
+impl FindOptions {
+    /// Same as min_duration, parsed by parse_duration.
+    pub fn min_duration_str(self, duration: &str) -> Result<Self, PeriodError> {
+        Ok(self.min_duration(parse_duration(duration)?))
+    }
+
+    /// Same as max_duration, parsed by parse_duration.
+    pub fn max_duration_str(self, duration: &str) -> Result<Self, PeriodError> {
+        Ok(self.max_duration(parse_duration(duration)?))
+    }
+
+    /// Same as alignment, parsed by parse_duration.
+    pub fn alignment_str(self, step: &str) -> Result<Self, PeriodError> {
+        Ok(self.alignment(parse_duration(step)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock::FixedClock, finder::find::find_with, periods::block::Block};
+    use chrono::TimeZone;
+
+    fn day_slots(day: u32, hours: &[u32]) -> Result<Vec<Slot>, PeriodError> {
+        hours
+            .iter()
+            .map(|h| {
+                let start = chrono_tz::Japan
+                    .with_ymd_and_hms(2024, 1, day, *h, 0, 0)
+                    .unwrap();
+                Slot::new(start, start + Duration::minutes(30))
+            })
+            .collect()
+    }
+
+    fn dt(hour: u32, min: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, min, 0)
+            .unwrap()
+    }
+
+    // A span around all the slots of the tests.
+    fn days() -> Result<Span, PeriodError> {
+        Span::new(dt(0, 0) - Duration::days(7), dt(0, 0) + Duration::days(7))
+    }
+
+    fn slot(start: (u32, u32), end: (u32, u32)) -> Result<Slot, PeriodError> {
+        Slot::new(dt(start.0, start.1), dt(end.0, end.1))
+    }
+
+    fn times(slots: &[Slot]) -> Vec<(DateTime<Tz>, DateTime<Tz>)> {
+        slots.iter().map(|s| (s.start(), s.end())).collect()
+    }
+
+    #[test]
+    fn test_options_apply() -> Result<(), PeriodError> {
+        struct TestCase {
+            name: &'static str,
+            options: FindOptions,
+            slots: Vec<Slot>,
+            expected: Vec<Slot>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Overlap tolerance joins slots split by short busy time",
+                options: FindOptions::new()
+                    .overlap_tolerance(Duration::minutes(1))
+                    .min_duration(Duration::minutes(30)),
+                slots: vec![
+                    slot((9, 0), (9, 20))?,
+                    Slot::new(dt(9, 20) + Duration::seconds(30), dt(9, 40))?,
+                    slot((9, 45), (10, 30))?,
+                ],
+                expected: vec![slot((8, 59), (9, 41))?, slot((9, 44), (10, 31))?],
+            },
+            TestCase {
+                name: "No options",
+                options: FindOptions::new(),
+                slots: vec![slot((9, 7), (9, 20))?],
+                expected: vec![slot((9, 7), (9, 20))?],
+            },
+            TestCase {
+                name: "Min duration drops short slots",
+                options: FindOptions::new().min_duration(Duration::minutes(30)),
+                slots: vec![slot((9, 0), (9, 29))?, slot((10, 0), (10, 30))?],
+                expected: vec![slot((10, 0), (10, 30))?],
+            },
+            TestCase {
+                name: "Alignment moves start forward",
+                options: FindOptions::new().alignment(Duration::minutes(15)),
+                slots: vec![slot((9, 7), (10, 0))?, slot((11, 0), (11, 30))?],
+                expected: vec![slot((9, 15), (10, 0))?, slot((11, 0), (11, 30))?],
+            },
+            TestCase {
+                name: "Alignment drops slots without an aligned start",
+                options: FindOptions::new().alignment(Duration::minutes(30)),
+                slots: vec![slot((9, 31), (9, 59))?],
+                expected: vec![],
+            },
+            TestCase {
+                name: "Min duration is checked after alignment",
+                options: FindOptions::new()
+                    .alignment(Duration::minutes(15))
+                    .min_duration(Duration::minutes(30)),
+                slots: vec![slot((9, 5), (9, 40))?, slot((10, 5), (10, 45))?],
+                expected: vec![slot((10, 15), (10, 45))?],
+            },
+            TestCase {
+                name: "Lead time drops slots starting too soon",
+                options: FindOptions::new()
+                    .clock(FixedClock::new(dt(8, 0)))
+                    .lead_time(Duration::hours(2), LeadTimePolicy::Drop),
+                slots: vec![slot((9, 0), (11, 0))?, slot((10, 0), (11, 0))?],
+                expected: vec![slot((10, 0), (11, 0))?],
+            },
+            TestCase {
+                name: "Lead time trims slots starting too soon",
+                options: FindOptions::new()
+                    .clock(FixedClock::new(dt(8, 0)))
+                    .lead_time(Duration::hours(2), LeadTimePolicy::Trim),
+                slots: vec![slot((7, 0), (9, 0))?, slot((9, 0), (11, 0))?],
+                expected: vec![slot((10, 0), (11, 0))?],
+            },
+            TestCase {
+                name: "Lead time is applied before alignment and min duration",
+                options: FindOptions::new()
+                    .clock(FixedClock::new(dt(8, 7)))
+                    .lead_time(Duration::hours(1), LeadTimePolicy::Trim)
+                    .alignment(Duration::minutes(15))
+                    .min_duration(Duration::minutes(45)),
+                slots: vec![slot((8, 0), (10, 0))?, slot((10, 0), (10, 40))?],
+                expected: vec![slot((9, 15), (10, 0))?],
+            },
+            TestCase {
+                name: "Max per day spreads slots across each day",
+                options: FindOptions::new().max_per_day(3),
+                slots: [
+                    day_slots(1, &[9, 10, 11, 12, 13, 14, 15, 16, 17, 18])?,
+                    day_slots(2, &[9, 10])?,
+                ]
+                .concat(),
+                expected: [day_slots(1, &[10, 14, 17])?, day_slots(2, &[9, 10])?].concat(),
+            },
+            TestCase {
+                name: "Focus time drops short slots and slots leaving short fragments",
+                options: FindOptions::new()
+                    .alignment(Duration::minutes(30))
+                    .focus_time(Duration::minutes(30), Duration::minutes(15)),
+                slots: vec![
+                    slot((8, 50), (9, 20))?,
+                    slot((9, 50), (11, 0))?,
+                    slot((11, 10), (12, 0))?,
+                    slot((13, 0), (13, 40))?,
+                ],
+                expected: vec![slot((11, 30), (12, 0))?, slot((13, 0), (13, 40))?],
+            },
+            TestCase {
+                name: "Max duration splits long slots",
+                options: FindOptions::new().max_duration(Duration::hours(4)),
+                slots: vec![slot((8, 0), (17, 0))?, slot((18, 0), (19, 0))?],
+                expected: vec![
+                    slot((8, 0), (12, 0))?,
+                    slot((12, 0), (16, 0))?,
+                    slot((16, 0), (17, 0))?,
+                    slot((18, 0), (19, 0))?,
+                ],
+            },
+            TestCase {
+                name: "Min duration drops the remainder of splitting",
+                options: FindOptions::new()
+                    .max_duration(Duration::hours(4))
+                    .min_duration(Duration::hours(2)),
+                slots: vec![slot((8, 0), (17, 0))?],
+                expected: vec![slot((8, 0), (12, 0))?, slot((12, 0), (16, 0))?],
+            },
+            TestCase {
+                name: "Stride offers overlapping candidates",
+                options: FindOptions::new().stride(Duration::hours(1), Duration::minutes(15)),
+                slots: vec![slot((9, 0), (10, 30))?, slot((11, 0), (11, 45))?],
+                expected: vec![
+                    slot((9, 0), (10, 0))?,
+                    slot((9, 15), (10, 15))?,
+                    slot((9, 30), (10, 30))?,
+                ],
+            },
+            TestCase {
+                name: "Stride starts from aligned starts",
+                options: FindOptions::new()
+                    .alignment(Duration::minutes(30))
+                    .stride(Duration::minutes(30), Duration::minutes(30)),
+                slots: vec![slot((9, 10), (10, 30))?],
+                expected: vec![slot((9, 30), (10, 0))?, slot((10, 0), (10, 30))?],
+            },
+            TestCase {
+                name: "Longest first",
+                options: FindOptions::new().order(SlotOrder::LongestFirst),
+                slots: vec![
+                    slot((9, 0), (9, 30))?,
+                    slot((10, 0), (11, 0))?,
+                    slot((12, 0), (12, 30))?,
+                ],
+                expected: vec![
+                    slot((10, 0), (11, 0))?,
+                    slot((9, 0), (9, 30))?,
+                    slot((12, 0), (12, 30))?,
+                ],
+            },
+            TestCase {
+                name: "By score",
+                options: FindOptions::new().order(SlotOrder::Score(Arc::new(|s: &Slot| {
+                    s.start().timestamp() as f64
+                }))),
+                slots: vec![slot((9, 0), (9, 30))?, slot((10, 0), (11, 0))?],
+                expected: vec![slot((10, 0), (11, 0))?, slot((9, 0), (9, 30))?],
+            },
+            TestCase {
+                name: "Max results keeps the first slots in the order",
+                options: FindOptions::new()
+                    .order(SlotOrder::LongestFirst)
+                    .max_results(2),
+                slots: vec![
+                    slot((9, 0), (9, 30))?,
+                    slot((10, 0), (11, 0))?,
+                    slot((12, 0), (12, 45))?,
+                ],
+                expected: vec![slot((10, 0), (11, 0))?, slot((12, 0), (12, 45))?],
+            },
+            TestCase {
+                name: "Spread selection shares max results over the days",
+                options: FindOptions::new()
+                    .max_results(6)
+                    .selection(Selection::Spread),
+                slots: [
+                    day_slots(1, &[9, 10, 11, 12, 13, 14, 15, 16])?,
+                    day_slots(2, &[9, 10])?,
+                    day_slots(3, &[9, 10, 11, 12, 13, 14, 15, 16])?,
+                ]
+                .concat(),
+                expected: [
+                    day_slots(1, &[11, 15])?,
+                    day_slots(2, &[9, 10])?,
+                    day_slots(3, &[11, 15])?,
+                ]
+                .concat(),
+            },
+            TestCase {
+                name: "Spread selection gives unused shares to other days",
+                options: FindOptions::new()
+                    .max_results(5)
+                    .selection(Selection::Spread),
+                slots: [
+                    day_slots(1, &[9, 10, 11, 12, 13, 14, 15, 16])?,
+                    day_slots(2, &[9])?,
+                ]
+                .concat(),
+                expected: [day_slots(1, &[10, 12, 14, 16])?, day_slots(2, &[9])?].concat(),
+            },
+            TestCase {
+                name: "Spread selection of fewer than the days picks the middle ones",
+                options: FindOptions::new()
+                    .max_results(1)
+                    .selection(Selection::Spread),
+                slots: [
+                    day_slots(1, &[9])?,
+                    day_slots(2, &[9])?,
+                    day_slots(3, &[9])?,
+                ]
+                .concat(),
+                expected: day_slots(2, &[9])?,
+            },
+            TestCase {
+                name: "Spread selection keeps the order",
+                options: FindOptions::new()
+                    .order(SlotOrder::LongestFirst)
+                    .max_results(2)
+                    .selection(Selection::Spread),
+                slots: vec![
+                    slot((9, 0), (9, 30))?,
+                    slot((10, 0), (11, 0))?,
+                    slot((12, 0), (12, 45))?,
+                    slot((13, 0), (13, 30))?,
+                ],
+                expected: vec![slot((10, 0), (11, 0))?, slot((13, 0), (13, 30))?],
+            },
+            TestCase {
+                name: "Work intervals with breaks",
+                options: FindOptions::new()
+                    .work_intervals(Duration::minutes(50), Duration::minutes(10)),
+                slots: vec![slot((9, 0), (11, 30))?, slot((13, 0), (13, 30))?],
+                expected: vec![
+                    slot((9, 0), (9, 50))?,
+                    slot((10, 0), (10, 50))?,
+                    slot((11, 0), (11, 30))?,
+                    slot((13, 0), (13, 30))?,
+                ],
+            },
+            TestCase {
+                name: "Gaps mode ignores the options",
+                options: FindOptions::new()
+                    .alignment(Duration::minutes(30))
+                    .max_duration(Duration::minutes(30))
+                    .order(SlotOrder::LongestFirst)
+                    .mode(OutputMode::Gaps),
+                slots: vec![slot((9, 10), (10, 30))?, slot((11, 0), (13, 0))?],
+                expected: vec![slot((9, 10), (10, 30))?, slot((11, 0), (13, 0))?],
+            },
+            TestCase {
+                name: "Granularity is applied to gaps too",
+                options: FindOptions::new()
+                    .granularity(Duration::minutes(5), Rounding::Outward)
+                    .mode(OutputMode::Gaps),
+                slots: vec![slot((9, 1), (9, 4))?, slot((9, 7), (10, 58))?],
+                expected: vec![slot((9, 10), (10, 55))?],
+            },
+            TestCase {
+                name: "Precision truncates the free time",
+                options: FindOptions::new().precision(Precision::Truncate),
+                slots: vec![Slot::new(
+                    dt(9, 0) + Duration::milliseconds(1_500),
+                    dt(10, 0) + Duration::nanoseconds(1),
+                )?],
+                expected: vec![Slot::new(dt(9, 0) + Duration::seconds(1), dt(10, 0))?],
+            },
+            TestCase {
+                name: "Max per day of zero drops all slots",
+                options: FindOptions::new().max_per_day(0),
+                slots: day_slots(1, &[9, 10])?,
+                expected: vec![],
+            },
+        ];
+
+        for case in cases {
+            let actual = case.options.apply(&days()?, case.slots);
+            assert_eq!(actual.len(), case.expected.len(), "{}", case.name);
+            for (actual, expected) in actual.iter().zip(case.expected.iter()) {
+                assert_eq!(actual.start(), expected.start(), "{}", case.name);
+                assert_eq!(actual.end(), expected.end(), "{}", case.name);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_overlap_tolerance() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(12, 0));
+        let seconds = |h: u32, m: u32, s: i64| dt(h, m) + Duration::seconds(s);
+        let tolerance = || FindOptions::new().overlap_tolerance(Duration::seconds(60));
+
+        struct TestCase {
+            name: &'static str,
+            options: FindOptions,
+            blocks: Vec<Block>,
+            expected: Vec<Slot>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Without tolerance, jitter trims the slot",
+                options: FindOptions::new(),
+                blocks: vec![Block::new(seconds(9, 59, 30), dt(11, 0))?],
+                expected: vec![
+                    Slot::new(dt(9, 0), seconds(9, 59, 30))?,
+                    slot((11, 0), (12, 0))?,
+                ],
+            },
+            TestCase {
+                name: "An overlap within the tolerance does not trim the slot",
+                options: tolerance(),
+                blocks: vec![Block::new(seconds(9, 59, 30), dt(11, 0))?],
+                expected: vec![
+                    Slot::new(dt(9, 0), seconds(10, 0, 30))?,
+                    slot((10, 59), (12, 0))?,
+                ],
+            },
+            TestCase {
+                name: "Rounded back to whole minutes by granularity",
+                options: tolerance().granularity(Duration::minutes(1), Rounding::Outward),
+                blocks: vec![Block::new(seconds(9, 59, 30), dt(11, 0))?],
+                expected: vec![slot((9, 0), (10, 0))?, slot((10, 59), (12, 0))?],
+            },
+            TestCase {
+                name: "Busy time within twice the tolerance does not split the slot",
+                options: tolerance(),
+                blocks: vec![Block::new(dt(10, 0), seconds(10, 1, 30))?],
+                expected: vec![slot((9, 0), (12, 0))?],
+            },
+            TestCase {
+                name: "Not extended outside the span",
+                options: tolerance(),
+                blocks: vec![
+                    Block::new(dt(8, 0), seconds(9, 0, 30))?,
+                    Block::new(seconds(11, 59, 30), dt(13, 0))?,
+                ],
+                expected: vec![slot((9, 0), (12, 0))?],
+            },
+            TestCase {
+                name: "Also applied to gaps",
+                options: tolerance().mode(OutputMode::Gaps),
+                blocks: vec![Block::new(dt(10, 0), seconds(10, 1, 30))?],
+                expected: vec![slot((9, 0), (12, 0))?],
+            },
+        ];
+
+        for case in cases {
+            let actual: Vec<Slot> = find_with(span()?, case.blocks, &case.options)?;
+            let times = |slots: &[Slot]| {
+                slots
+                    .iter()
+                    .map(|s| (s.start(), s.end()))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(times(&actual), times(&case.expected), "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_lead_time() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(17, 0));
+        let blocks = || Block::new(dt(12, 0), dt(13, 0)).map(|b| vec![b]);
+        // Now in another timezone than the span.
+        let lead_time = |d: Duration, policy| {
+            FindOptions::new()
+                .clock(FixedClock::new(dt(10, 30).with_timezone(&Tz::UTC)))
+                .lead_time(d, policy)
+        };
+
+        struct TestCase {
+            name: &'static str,
+            options: FindOptions,
+            expected: Vec<Slot>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Drop slots starting within the lead time",
+                options: lead_time(Duration::hours(1), LeadTimePolicy::Drop),
+                expected: vec![slot((13, 0), (17, 0))?],
+            },
+            TestCase {
+                name: "Trim slots starting within the lead time",
+                options: lead_time(Duration::hours(1), LeadTimePolicy::Trim),
+                expected: vec![slot((11, 30), (12, 0))?, slot((13, 0), (17, 0))?],
+            },
+            TestCase {
+                name: "Zero lead time trims slots to now",
+                options: lead_time(Duration::zero(), LeadTimePolicy::Trim),
+                expected: vec![slot((10, 30), (12, 0))?, slot((13, 0), (17, 0))?],
+            },
+            TestCase {
+                name: "Lead time beyond the span",
+                options: lead_time(Duration::hours(8), LeadTimePolicy::Trim),
+                expected: vec![],
+            },
+        ];
+
+        for case in cases {
+            let actual: Vec<Slot> = find_with(span()?, blocks()?, &case.options)?;
+            assert_eq!(times(&actual), times(&case.expected), "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_max_per_day() -> Result<(), PeriodError> {
+        let at = |day, hour| {
+            chrono_tz::Japan
+                .with_ymd_and_hms(2024, 1, day, hour, 0, 0)
+                .unwrap()
+        };
+        let span = || Span::new(at(1, 9), at(2, 12));
+        // Free 9:00-13:00 on the 1st and 9:00-12:00 on the 2nd, offered every hour.
+        let blocks = || Block::new(at(1, 13), at(2, 9)).map(|b| vec![b]);
+        let max_per_day = |max| {
+            FindOptions::new()
+                .stride(Duration::minutes(30), Duration::hours(1))
+                .max_per_day(max)
+        };
+
+        let cases = vec![
+            (
+                "Spread across each day",
+                max_per_day(2),
+                [day_slots(1, &[10, 12])?, day_slots(2, &[9, 11])?].concat(),
+            ),
+            (
+                "One in the middle of each day",
+                max_per_day(1),
+                [day_slots(1, &[11])?, day_slots(2, &[10])?].concat(),
+            ),
+            (
+                "Days within the limit are kept",
+                max_per_day(4),
+                [day_slots(1, &[9, 10, 11, 12])?, day_slots(2, &[9, 10, 11])?].concat(),
+            ),
+        ];
+
+        for (name, options, expected) in cases {
+            let actual: Vec<Slot> = find_with(span()?, blocks()?, &options)?;
+            assert_eq!(times(&actual), times(&expected), "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_order() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(17, 0));
+        // Given out of order, leaving 9:00-10:00, 11:00-12:00 and 15:00-17:00 free.
+        let blocks = || -> Result<Vec<Block>, PeriodError> {
+            Ok(vec![
+                Block::new(dt(12, 0), dt(15, 0))?,
+                Block::new(dt(10, 0), dt(11, 0))?,
+            ])
+        };
+        let latest_first = |s: &Slot| s.start().timestamp() as f64;
+
+        let cases = vec![
+            (
+                "By start time by default",
+                SlotOrder::Start,
+                vec![
+                    slot((9, 0), (10, 0))?,
+                    slot((11, 0), (12, 0))?,
+                    slot((15, 0), (17, 0))?,
+                ],
+            ),
+            (
+                "Longest first, ties by start time",
+                SlotOrder::LongestFirst,
+                vec![
+                    slot((15, 0), (17, 0))?,
+                    slot((9, 0), (10, 0))?,
+                    slot((11, 0), (12, 0))?,
+                ],
+            ),
+            (
+                "By score",
+                SlotOrder::Score(Arc::new(latest_first)),
+                vec![
+                    slot((15, 0), (17, 0))?,
+                    slot((11, 0), (12, 0))?,
+                    slot((9, 0), (10, 0))?,
+                ],
+            ),
+        ];
+
+        for (name, order, expected) in cases {
+            let options = FindOptions::new().order(order);
+            let actual: Vec<Slot> = find_with(span()?, blocks()?, &options)?;
+            assert_eq!(times(&actual), times(&expected), "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_stride() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(12, 0));
+        // Free 9:00-10:30 and 11:00-12:00.
+        let blocks = || Block::new(dt(10, 30), dt(11, 0)).map(|b| vec![b]);
+
+        let cases = vec![
+            (
+                "A candidate at every step",
+                Duration::hours(1),
+                Duration::minutes(30),
+                vec![
+                    slot((9, 0), (10, 0))?,
+                    slot((9, 30), (10, 30))?,
+                    slot((11, 0), (12, 0))?,
+                ],
+            ),
+            (
+                "Candidates end within the slot",
+                Duration::minutes(45),
+                Duration::minutes(20),
+                vec![
+                    slot((9, 0), (9, 45))?,
+                    slot((9, 20), (10, 5))?,
+                    slot((9, 40), (10, 25))?,
+                    slot((11, 0), (11, 45))?,
+                ],
+            ),
+            (
+                "Slots shorter than the duration are dropped",
+                Duration::hours(2),
+                Duration::minutes(30),
+                vec![],
+            ),
+        ];
+
+        for (name, duration, step, expected) in cases {
+            let options = FindOptions::new().stride(duration, step);
+            let actual: Vec<Slot> = find_with(span()?, blocks()?, &options)?;
+            assert_eq!(times(&actual), times(&expected), "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_max_duration() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(17, 0));
+        // Free 9:00-12:00 and 13:00-17:00.
+        let blocks = || Block::new(dt(12, 0), dt(13, 0)).map(|b| vec![b]);
+        let two_hours = || FindOptions::new().max_duration(Duration::hours(2));
+
+        let cases = vec![
+            (
+                "Long slots are split",
+                two_hours(),
+                vec![
+                    slot((9, 0), (11, 0))?,
+                    slot((11, 0), (12, 0))?,
+                    slot((13, 0), (15, 0))?,
+                    slot((15, 0), (17, 0))?,
+                ],
+            ),
+            (
+                "Slots not longer are kept",
+                FindOptions::new().max_duration(Duration::hours(4)),
+                vec![slot((9, 0), (12, 0))?, slot((13, 0), (17, 0))?],
+            ),
+            (
+                "A short remainder is dropped by min duration",
+                two_hours().min_duration(Duration::hours(2)),
+                vec![
+                    slot((9, 0), (11, 0))?,
+                    slot((13, 0), (15, 0))?,
+                    slot((15, 0), (17, 0))?,
+                ],
+            ),
+        ];
+
+        for (name, options, expected) in cases {
+            let actual: Vec<Slot> = find_with(span()?, blocks()?, &options)?;
+            assert_eq!(times(&actual), times(&expected), "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_mode() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(17, 0));
+        // Free 9:00-12:00 and 13:00-17:00.
+        let blocks = || Block::new(dt(12, 0), dt(13, 0)).map(|b| vec![b]);
+        let options = || {
+            FindOptions::new()
+                .min_duration(Duration::hours(4))
+                .max_results(1)
+        };
+
+        let cases = vec![
+            (
+                "Bookable by default",
+                options(),
+                vec![slot((13, 0), (17, 0))?],
+            ),
+            (
+                "Bookable",
+                options().mode(OutputMode::Bookable),
+                vec![slot((13, 0), (17, 0))?],
+            ),
+            (
+                "Gaps ignore the options on slots",
+                options().mode(OutputMode::Gaps),
+                vec![slot((9, 0), (12, 0))?, slot((13, 0), (17, 0))?],
+            ),
+            (
+                "Gaps are clamped to now",
+                options()
+                    .clock(FixedClock::new(dt(10, 0)))
+                    .clamp_to_now()
+                    .mode(OutputMode::Gaps),
+                vec![slot((10, 0), (12, 0))?, slot((13, 0), (17, 0))?],
+            ),
+        ];
+
+        for (name, options, expected) in cases {
+            let actual: Vec<Slot> = find_with(span()?, blocks()?, &options)?;
+            assert_eq!(times(&actual), times(&expected), "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_selection() -> Result<(), PeriodError> {
+        let at = |day, hour| {
+            chrono_tz::Japan
+                .with_ymd_and_hms(2024, 1, day, hour, 0, 0)
+                .unwrap()
+        };
+        let span = || Span::new(at(1, 9), at(3, 13));
+        // Free 9:00-13:00 on the 1st and 3rd and 9:00-11:00 on the 2nd, offered every hour.
+        let blocks = || -> Result<Vec<Block>, PeriodError> {
+            Ok(vec![
+                Block::new(at(1, 13), at(2, 9))?,
+                Block::new(at(2, 11), at(3, 9))?,
+            ])
+        };
+        let select = |max, selection| {
+            FindOptions::new()
+                .stride(Duration::minutes(30), Duration::hours(1))
+                .max_results(max)
+                .selection(selection)
+        };
+
+        let cases = vec![
+            (
+                "First",
+                select(4, Selection::First),
+                day_slots(1, &[9, 10, 11, 12])?,
+            ),
+            (
+                "Spread over the days",
+                select(4, Selection::Spread),
+                [
+                    day_slots(1, &[11])?,
+                    day_slots(2, &[9, 10])?,
+                    day_slots(3, &[11])?,
+                ]
+                .concat(),
+            ),
+            (
+                "Spread over fewer results than days",
+                select(2, Selection::Spread),
+                [day_slots(1, &[11])?, day_slots(3, &[11])?].concat(),
+            ),
+        ];
+
+        for (name, options, expected) in cases {
+            let actual: Vec<Slot> = find_with(span()?, blocks()?, &options)?;
+            assert_eq!(times(&actual), times(&expected), "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_work_intervals() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(12, 0));
+        // Free 9:00-11:00 and 11:15-12:00.
+        let blocks = || Block::new(dt(11, 0), dt(11, 15)).map(|b| vec![b]);
+        let pomodoro =
+            || FindOptions::new().work_intervals(Duration::minutes(50), Duration::minutes(10));
+
+        let cases = vec![
+            (
+                "Work intervals separated by breaks, the last one shorter",
+                pomodoro(),
+                vec![
+                    slot((9, 0), (9, 50))?,
+                    slot((10, 0), (10, 50))?,
+                    slot((11, 15), (12, 0))?,
+                ],
+            ),
+            (
+                "Breaks are kept when max duration splits the intervals",
+                pomodoro().max_duration(Duration::minutes(30)),
+                vec![
+                    slot((9, 0), (9, 30))?,
+                    slot((9, 30), (9, 50))?,
+                    slot((10, 0), (10, 30))?,
+                    slot((10, 30), (10, 50))?,
+                    slot((11, 15), (11, 45))?,
+                    slot((11, 45), (12, 0))?,
+                ],
+            ),
+            (
+                "Max duration as long as the work",
+                pomodoro().max_duration(Duration::minutes(50)),
+                vec![
+                    slot((9, 0), (9, 50))?,
+                    slot((10, 0), (10, 50))?,
+                    slot((11, 15), (12, 0))?,
+                ],
+            ),
+            (
+                "Work longer than any slot",
+                FindOptions::new().work_intervals(Duration::max_value(), Duration::minutes(10)),
+                vec![slot((9, 0), (11, 0))?, slot((11, 15), (12, 0))?],
+            ),
+            (
+                "A break longer than any slot",
+                FindOptions::new().work_intervals(Duration::minutes(50), Duration::max_value()),
+                vec![slot((9, 0), (9, 50))?, slot((11, 15), (12, 0))?],
+            ),
+            (
+                "A short last interval is dropped by min duration",
+                pomodoro().min_duration(Duration::minutes(50)),
+                vec![slot((9, 0), (9, 50))?, slot((10, 0), (10, 50))?],
+            ),
+        ];
+
+        for (name, options, expected) in cases {
+            let actual: Vec<Slot> = find_with(span()?, blocks()?, &options)?;
+            assert_eq!(times(&actual), times(&expected), "{}", name);
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "humantime")]
+    #[test]
+    fn test_options_duration_str() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(17, 0));
+        let blocks = || Block::new(dt(9, 10), dt(10, 0)).map(|b| vec![b]);
+        let options = FindOptions::new()
+            .alignment_str("30m")?
+            .max_duration_str("2h")?
+            .min_duration_str("1h 30m")?;
+        let actual: Vec<Slot> = find_with(span()?, blocks()?, &options)?;
+        let expected = vec![
+            slot((10, 0), (12, 0))?,
+            slot((12, 0), (14, 0))?,
+            slot((14, 0), (16, 0))?,
+        ];
+        assert_eq!(times(&actual), times(&expected));
+        assert!(FindOptions::new().min_duration_str("90").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_validate() {
+        assert!(FindOptions::new().validate().is_ok());
+        assert!(FindOptions::new()
+            .alignment(Duration::minutes(15))
+            .validate()
+            .is_ok());
+        assert!(FindOptions::new()
+            .alignment(Duration::zero())
+            .validate()
+            .is_err());
+        assert!(FindOptions::new()
+            .min_duration(Duration::minutes(-1))
+            .validate()
+            .is_err());
+        assert!(FindOptions::new()
+            .max_duration(Duration::zero())
+            .validate()
+            .is_err());
+        assert!(FindOptions::new()
+            .stride(Duration::hours(1), Duration::zero())
+            .validate()
+            .is_err());
+        assert!(FindOptions::new()
+            .overlap_tolerance(Duration::zero())
+            .validate()
+            .is_err());
+        assert!(FindOptions::new()
+            .lead_time(Duration::minutes(-1), LeadTimePolicy::Drop)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_options_horizon_truncate_unbounded() -> Result<(), PeriodError> {
+        let options = FindOptions::new().horizon(Duration::max_value(), HorizonPolicy::Truncate);
+        let prepared = options.prepare(Span::new(dt(9, 0), dt(17, 0))?)?;
+        assert_eq!(
+            prepared.map(|s| (s.start(), s.end())),
+            Some((dt(9, 0), dt(17, 0)))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_horizon_reject() -> Result<(), PeriodError> {
+        let options = FindOptions::new().horizon(Duration::hours(2), HorizonPolicy::Reject);
+        let actual = options.prepare(Span::new(dt(9, 0), dt(17, 0))?);
+        assert_eq!(
+            actual.err(),
+            Some(PeriodError::SpanTooLong {
+                length: Duration::hours(8),
+                max: Duration::hours(2)
+            })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_check_inputs() -> Result<(), PeriodError> {
+        let span = Span::new(dt(9, 0), dt(17, 0))?;
+        let fine = vec![Slot::new(dt(10, 0) + Duration::nanoseconds(1), dt(11, 0))?];
+        let options = FindOptions::new().precision(Precision::Reject);
+        assert!(options.check_inputs(&span, &fine).is_err());
+        assert!(options
+            .check_inputs(&span, &[slot((10, 0), (11, 0))?])
+            .is_ok());
+        assert!(FindOptions::new().check_inputs(&span, &fine).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_check_out_of_span() -> Result<(), PeriodError> {
+        let span = Span::new(dt(9, 0), dt(17, 0))?;
+        let inputs = vec![
+            slot((10, 0), (11, 0))?,
+            slot((7, 0), (9, 0))?,
+            slot((18, 0), (19, 0))?,
+        ];
+        let cases = vec![
+            (OutOfSpanPolicy::Allow, None),
+            (OutOfSpanPolicy::Warn, None),
+            (OutOfSpanPolicy::Reject, Some(1)),
+        ];
+        for (policy, expected) in cases {
+            let actual = FindOptions::new()
+                .out_of_span(policy)
+                .check_inputs(&span, &inputs);
+            match expected {
+                Some(index) => assert_eq!(
+                    actual.unwrap_err().to_string(),
+                    PeriodError::OutOfSpan { index }.to_string(),
+                    "{:?}",
+                    policy
+                ),
+                None => assert!(actual.is_ok(), "{:?}", policy),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_options_prepare() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(17, 0));
+        let clock = |hour, min| FixedClock::new(dt(hour, min).with_timezone(&Tz::UTC));
+
+        struct TestCase {
+            name: &'static str,
+            options: FindOptions,
+            expected: Option<(DateTime<Tz>, DateTime<Tz>)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Not clamped",
+                options: FindOptions::new().clock(clock(12, 0)),
+                expected: Some((dt(9, 0), dt(17, 0))),
+            },
+            TestCase {
+                name: "Now is before span",
+                options: FindOptions::new().clock(clock(8, 0)).clamp_to_now(),
+                expected: Some((dt(9, 0), dt(17, 0))),
+            },
+            TestCase {
+                name: "Now is within span",
+                options: FindOptions::new().clock(clock(12, 30)).clamp_to_now(),
+                expected: Some((dt(12, 30), dt(17, 0))),
+            },
+            TestCase {
+                name: "Now is after span",
+                options: FindOptions::new().clock(clock(17, 0)).clamp_to_now(),
+                expected: None,
+            },
+            TestCase {
+                name: "Within horizon",
+                options: FindOptions::new().horizon(Duration::hours(8), HorizonPolicy::Reject),
+                expected: Some((dt(9, 0), dt(17, 0))),
+            },
+            TestCase {
+                name: "Truncated to horizon",
+                options: FindOptions::new().horizon(Duration::hours(2), HorizonPolicy::Truncate),
+                expected: Some((dt(9, 0), dt(11, 0))),
+            },
+            TestCase {
+                name: "Horizon from now",
+                options: FindOptions::new()
+                    .clock(clock(12, 0))
+                    .clamp_to_now()
+                    .horizon(Duration::hours(2), HorizonPolicy::Truncate),
+                expected: Some((dt(12, 0), dt(14, 0))),
+            },
+        ];
+
+        for case in cases {
+            let actual = case.options.prepare(span()?)?;
+            let actual = actual.map(|s| (s.start(), s.end()));
+            assert_eq!(actual, case.expected, "{}", case.name);
+            if let Some((start, _)) = actual {
+                assert_eq!(start.timezone(), chrono_tz::Japan, "{}", case.name);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,290 @@
+use std::vec;
+
+use chrono::{DateTime, TimeZone};
+use chrono_tz::Tz;
+
+use crate::periods::{
+    block::{dedup_blocks, Block},
+    period::{Input, Output, Period, PeriodError},
+    slot::Slot,
+    span::Span,
+};
+
+use super::{find::find_with, options::FindOptions};
+
+/// Free time of a span, found lazily as it is iterated. Created by find_iter.
+#[derive(Debug)]
+pub struct Gaps {
+    blocks: vec::IntoIter<Block>,
+    sweep: Sweep,
+}
+
+impl Iterator for Gaps {
+    type Item = Slot;
+
+    fn next(&mut self) -> Option<Slot> {
+        while !self.sweep.is_done() {
+            if let Some(gap) = self.sweep.cut(self.blocks.next()) {
+                return Some(gap);
+            }
+        }
+        None
+    }
+}
+
+// What remains of a span as blocks ordered by start time are cut out of it one at a time,
+// for sources that push blocks instead of being iterated (e.g. async readers).
+#[derive(Debug, Clone)]
+pub(crate) struct Sweep {
+    // None when the span is used up.
+    rest: Option<Span>,
+}
+
+impl Sweep {
+    pub(crate) fn new(span: Span) -> Self {
+        Sweep { rest: Some(span) }
+    }
+
+    // Whether no block can change the result anymore.
+    pub(crate) fn is_done(&self) -> bool {
+        self.rest.is_none()
+    }
+
+    // Cut the next block out of the rest of the span, None after the last block.
+    // Returns the gap before the block, or the rest of the span after the last block.
+    pub(crate) fn cut(&mut self, block: Option<Block>) -> Option<Slot> {
+        let rest = self.rest.clone()?;
+        let Some(block) = block.filter(|b| b.start() < rest.end()) else {
+            self.rest = None;
+            return rest.to_slot().ok();
+        };
+        if block.end() <= rest.start() {
+            return None;
+        }
+        self.rest = Span::new(block.end(), rest.end()).ok();
+        Slot::new(rest.start(), block.start()).ok()
+    }
+}
+
+// Free time of the span excluding the inputs, found lazily in order of start time.
+// Only the sort of the inputs is done upfront, so taking the first few slots of a long span is cheap.
+pub fn find_iter<In: Input>(span: Span, inputs: &[In]) -> Result<Gaps, PeriodError> {
+    let blocks = inputs
+        .iter()
+        .map(|input| input.to_block())
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Gaps {
+        blocks: dedup_blocks(blocks).into_iter(),
+        sweep: Sweep::new(span),
+    })
+}
+
+/// Where the next page starts: after the start of the last slot of the previous page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    after: DateTime<Tz>,
+}
+
+impl Cursor {
+    /// Opaque string to pass to clients: Unix seconds, nanoseconds and the timezone.
+    pub fn encode(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            self.after.timestamp(),
+            self.after.timestamp_subsec_nanos(),
+            self.after.timezone().name()
+        )
+    }
+
+    /// Read a string made by encode.
+    pub fn decode(s: &str) -> Result<Self, PeriodError> {
+        let invalid = || PeriodError::InvalidFormat(format!("cursor {}", s));
+        let mut parts = s.splitn(3, '.');
+        let (Some(secs), Some(nanos), Some(tz)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+        let secs = secs.parse::<i64>().map_err(|_| invalid())?;
+        let nanos = nanos.parse::<u32>().map_err(|_| invalid())?;
+        let tz = tz.parse::<Tz>().map_err(|_| invalid())?;
+        let after = tz
+            .timestamp_opt(secs, nanos)
+            .single()
+            .ok_or_else(|| PeriodError::OutOfRange(format!("cursor {}", s)))?;
+        Ok(Cursor { after })
+    }
+}
+
+/// A page of slots, and the cursor of the next page if there are more.
+#[derive(Debug, Clone)]
+pub struct Page<Out> {
+    pub slots: Vec<Out>,
+    pub next: Option<Cursor>,
+}
+
+// A page of at most limit slots of find_with, starting after the cursor (from the start without one).
+// Only the rest of the span after the cursor is searched. The slots must be ordered by start time,
+// and options spanning several slots (max_per_day, focus_time) only see the rest of the span.
+pub fn find_page<In: Input, Out: Output>(
+    span: Span,
+    inputs: Vec<In>,
+    options: &FindOptions,
+    cursor: Option<&Cursor>,
+    limit: usize,
+) -> Result<Page<Out>, PeriodError> {
+    let after = cursor.map(|c| c.after.with_timezone(&span.start().timezone()));
+    let span = match after {
+        Some(after) if span.start() < after => match Span::new(after, span.end()) {
+            Ok(rest) => rest,
+            Err(_) => {
+                return Ok(Page {
+                    slots: Vec::new(),
+                    next: None,
+                })
+            }
+        },
+        _ => span,
+    };
+    let mut slots: Vec<Slot> = find_with(span, inputs, options)?;
+    slots.retain(|slot| after.is_none_or(|after| after < slot.start()));
+    let next = (limit > 0 && slots.len() > limit).then(|| Cursor {
+        after: slots[limit - 1].start(),
+    });
+    slots.truncate(limit);
+    Ok(Page {
+        slots: slots.into_iter().map(Out::create_from_slot).collect(),
+        next,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finder::find::find;
+    use chrono::Duration;
+
+    fn dt(hour: u32, min: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, min, 0)
+            .unwrap()
+    }
+
+    fn times(slots: &[Slot]) -> Vec<(DateTime<Tz>, DateTime<Tz>)> {
+        slots.iter().map(|s| (s.start(), s.end())).collect()
+    }
+
+    #[test]
+    fn test_find_iter() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(17, 0));
+
+        struct TestCase {
+            name: &'static str,
+            blocks: Vec<Block>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "No blocks",
+                blocks: vec![],
+            },
+            TestCase {
+                name: "Blocks inside, overlapping and touching",
+                blocks: vec![
+                    Block::new(dt(10, 0), dt(11, 0))?,
+                    Block::new(dt(10, 30), dt(12, 0))?,
+                    Block::new(dt(12, 0), dt(13, 0))?,
+                    Block::new(dt(15, 0), dt(15, 30))?,
+                ],
+            },
+            TestCase {
+                name: "Blocks across both ends",
+                blocks: vec![
+                    Block::new(dt(8, 0), dt(9, 30))?,
+                    Block::new(dt(16, 0), dt(18, 0))?,
+                ],
+            },
+            TestCase {
+                name: "Block covering the span",
+                blocks: vec![Block::new(dt(8, 0), dt(18, 0))?],
+            },
+        ];
+
+        for case in cases {
+            let lazy = find_iter(span()?, &case.blocks)?.collect::<Vec<_>>();
+            let eager: Vec<Slot> = find(span()?, case.blocks)?;
+            assert_eq!(times(&lazy), times(&eager), "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_page() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(12, 0));
+        let blocks = || Block::new(dt(10, 0), dt(10, 30)).map(|b| vec![b]);
+        let options = FindOptions::new().stride(Duration::minutes(30), Duration::minutes(15));
+        let all: Vec<Slot> = find_with(span()?, blocks()?, &options)?;
+
+        let mut pages = Vec::new();
+        let mut paged = Vec::new();
+        let mut cursor: Option<Cursor> = None;
+        loop {
+            let encoded = cursor.as_ref().map(Cursor::encode);
+            let decoded = encoded.as_deref().map(Cursor::decode).transpose()?;
+            let page: Page<Slot> = find_page(span()?, blocks()?, &options, decoded.as_ref(), 3)?;
+            pages.push(page.slots.len());
+            paged.extend(page.slots);
+            cursor = page.next;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(pages, vec![3, 3, 2]);
+        assert_eq!(times(&paged), times(&all));
+
+        let empty: Page<Slot> = find_page(span()?, blocks()?, &options, None, 0)?;
+        assert!(empty.slots.is_empty());
+        assert!(empty.next.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cursor_round_trip() -> Result<(), PeriodError> {
+        struct TestCase {
+            name: &'static str,
+            after: DateTime<Tz>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "After 1970",
+                after: dt(9, 30),
+            },
+            TestCase {
+                name: "Before 1970",
+                after: chrono_tz::America::New_York
+                    .with_ymd_and_hms(1965, 3, 1, 9, 0, 0)
+                    .unwrap(),
+            },
+            TestCase {
+                name: "After 2262",
+                after: chrono_tz::UTC
+                    .with_ymd_and_hms(2300, 1, 1, 0, 0, 0)
+                    .unwrap()
+                    + Duration::nanoseconds(1),
+            },
+        ];
+
+        for case in cases {
+            let cursor = Cursor { after: case.after };
+            assert_eq!(Cursor::decode(&cursor.encode())?, cursor, "{}", case.name);
+        }
+
+        assert!(Cursor::decode("zz.0.Asia/Tokyo").is_err());
+        assert!(Cursor::decode("10.0.Nowhere/City").is_err());
+        assert!(Cursor::decode("10.Asia/Tokyo").is_err());
+        assert!(matches!(
+            Cursor::decode(&format!("{}.0.UTC", i64::MAX)),
+            Err(PeriodError::OutOfRange(_))
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use crate::periods::{
+    period::{Input, Output, Period, PeriodError},
+    span::Span,
+};
+
+use super::{explain::Reason, find::search, options::FindOptions};
+
+/// Why a result was cut short. The client can narrow the query to see the rest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Truncation {
+    /// The span was truncated to the horizon.
+    Horizon,
+    /// More slots were found than max_results.
+    MaxResults,
+}
+
+/// Slots found, and why they are only a part of the result if they are.
+#[derive(Debug, Clone)]
+pub struct Partial<Out> {
+    pub slots: Vec<Out>,
+    /// None if the result is complete.
+    pub truncated: Option<Truncation>,
+}
+
+// Same as find_with, and tells whether a limit of the options cut the result short.
+// A truncated horizon is reported before max_results.
+pub fn find_partial<In: Input, Out: Output>(
+    span: Span,
+    inputs: Vec<In>,
+    options: &FindOptions,
+) -> Result<Partial<Out>, PeriodError> {
+    let end = span.end();
+    let mut max_results = false;
+    let found = search(span, inputs, options, &mut (), |reason, _| {
+        max_results |= reason == Reason::MaxResults;
+    })?;
+    let Some((span, slots)) = found else {
+        return Ok(Partial {
+            slots: Vec::new(),
+            truncated: None,
+        });
+    };
+    let truncated = (span.end() < end)
+        .then_some(Truncation::Horizon)
+        .or(max_results.then_some(Truncation::MaxResults));
+    Ok(Partial {
+        slots: slots.into_iter().map(Out::create_from_slot).collect(),
+        truncated,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        finder::options::HorizonPolicy,
+        periods::{block::Block, slot::Slot},
+    };
+    use chrono::{DateTime, Duration, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_find_partial() -> Result<(), PeriodError> {
+        // Free: 9-10, 11-12, 13-17
+        let blocks = || -> Result<Vec<Block>, PeriodError> {
+            Ok(vec![
+                Block::new(dt(10), dt(11))?,
+                Block::new(dt(12), dt(13))?,
+            ])
+        };
+
+        struct TestCase {
+            name: &'static str,
+            options: FindOptions,
+            expected_len: usize,
+            expected: Option<Truncation>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Complete",
+                options: FindOptions::new().max_results(3),
+                expected_len: 3,
+                expected: None,
+            },
+            TestCase {
+                name: "Max results",
+                options: FindOptions::new().max_results(2),
+                expected_len: 2,
+                expected: Some(Truncation::MaxResults),
+            },
+            TestCase {
+                name: "Horizon",
+                options: FindOptions::new().horizon(Duration::hours(3), HorizonPolicy::Truncate),
+                expected_len: 2,
+                expected: Some(Truncation::Horizon),
+            },
+            TestCase {
+                name: "Horizon is reported first",
+                options: FindOptions::new()
+                    .horizon(Duration::hours(3), HorizonPolicy::Truncate)
+                    .max_results(1),
+                expected_len: 1,
+                expected: Some(Truncation::Horizon),
+            },
+        ];
+
+        for case in cases {
+            let actual: Partial<Slot> =
+                find_partial(Span::new(dt(9), dt(17))?, blocks()?, &case.options)?;
+            assert_eq!(actual.slots.len(), case.expected_len, "{}", case.name);
+            assert_eq!(actual.truncated, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+}
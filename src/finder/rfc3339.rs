@@ -0,0 +1,153 @@
+use chrono::{DateTime, SecondsFormat};
+use chrono_tz::Tz;
+
+use crate::periods::{
+    block::Block,
+    period::{Period, PeriodError},
+    slot::Slot,
+    span::Span,
+};
+
+use super::find::find;
+
+/// A slot found by find_rfc3339, with its start and end time in RFC 3339.
+#[derive(Debug, Clone)]
+pub struct Rfc3339Slot {
+    pub slot: Slot,
+    pub start: String,
+    pub end: String,
+}
+
+impl From<Slot> for Rfc3339Slot {
+    fn from(slot: Slot) -> Self {
+        Rfc3339Slot {
+            start: slot.start().to_rfc3339_opts(SecondsFormat::AutoSi, false),
+            end: slot.end().to_rfc3339_opts(SecondsFormat::AutoSi, false),
+            slot,
+        }
+    }
+}
+
+/// Parse a timezone name such as "Asia/Tokyo".
+pub fn parse_tz(tz: &str) -> Result<Tz, PeriodError> {
+    tz.parse::<Tz>()
+        .map_err(|_| PeriodError::InvalidTimezone(tz.to_string()))
+}
+
+/// Parse an RFC 3339 string and convert it into the timezone.
+pub fn parse_rfc3339(s: &str, tz: &Tz) -> Result<DateTime<Tz>, PeriodError> {
+    DateTime::parse_from_rfc3339(s)
+        .map(|dt| dt.with_timezone(tz))
+        .map_err(|_| PeriodError::InvalidFormat(s.to_string()))
+}
+
+// Same as find, but the span and the scheduled events are RFC 3339 strings. Slots are returned in the timezone (e.g. "Asia/Tokyo").
+pub fn find_rfc3339(
+    span: (&str, &str),
+    events: &[(&str, &str)],
+    tz: &str,
+) -> Result<Vec<Rfc3339Slot>, PeriodError> {
+    let tz = parse_tz(tz)?;
+    let span = Span::new(parse_rfc3339(span.0, &tz)?, parse_rfc3339(span.1, &tz)?)?;
+    let blocks = events
+        .iter()
+        .map(|(start, end)| Block::new(parse_rfc3339(start, &tz)?, parse_rfc3339(end, &tz)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    let slots: Vec<Slot> = find(span, blocks)?;
+    Ok(slots.into_iter().map(Rfc3339Slot::from).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_find_rfc3339() {
+        struct TestCase {
+            name: &'static str,
+            span: (&'static str, &'static str),
+            events: Vec<(&'static str, &'static str)>,
+            tz: &'static str,
+            expected: Result<Vec<(&'static str, &'static str)>, PeriodError>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Events in other offsets are converted",
+                span: ("2024-01-01T09:00:00+09:00", "2024-01-01T17:00:00+09:00"),
+                events: vec![
+                    ("2024-01-01T01:00:00Z", "2024-01-01T02:00:00Z"),
+                    ("2024-01-01T12:00:00+09:00", "2024-01-01T12:30:00+09:00"),
+                ],
+                tz: "Asia/Tokyo",
+                expected: Ok(vec![
+                    ("2024-01-01T09:00:00+09:00", "2024-01-01T10:00:00+09:00"),
+                    ("2024-01-01T11:00:00+09:00", "2024-01-01T12:00:00+09:00"),
+                    ("2024-01-01T12:30:00+09:00", "2024-01-01T17:00:00+09:00"),
+                ]),
+            },
+            TestCase {
+                name: "Slots are returned in the timezone",
+                span: ("2024-01-01T00:00:00Z", "2024-01-01T01:00:00Z"),
+                events: vec![],
+                tz: "America/New_York",
+                expected: Ok(vec![(
+                    "2023-12-31T19:00:00-05:00",
+                    "2023-12-31T20:00:00-05:00",
+                )]),
+            },
+            TestCase {
+                name: "Unknown timezone",
+                span: ("2024-01-01T00:00:00Z", "2024-01-01T01:00:00Z"),
+                events: vec![],
+                tz: "Nowhere/City",
+                expected: Err(PeriodError::InvalidTimezone("Nowhere/City".to_string())),
+            },
+            TestCase {
+                name: "Invalid format",
+                span: ("2024-01-01T00:00:00Z", "2024-01-01 01:00"),
+                events: vec![],
+                tz: "UTC",
+                expected: Err(PeriodError::InvalidFormat("2024-01-01 01:00".to_string())),
+            },
+            TestCase {
+                name: "Invalid event",
+                span: ("2024-01-01T00:00:00Z", "2024-01-01T08:00:00Z"),
+                events: vec![("2024-01-01T02:00:00Z", "2024-01-01T01:00:00Z")],
+                tz: "UTC",
+                expected: Err(PeriodError::StartNotBeforeEnd {
+                    start: Tz::UTC.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap(),
+                    end: Tz::UTC.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap(),
+                }),
+            },
+        ];
+
+        for case in cases {
+            match find_rfc3339(case.span, &case.events, case.tz) {
+                Ok(slots) => {
+                    let expected = case.expected.unwrap();
+                    assert_eq!(slots.len(), expected.len(), "{}", case.name);
+                    for (actual, expected) in slots.iter().zip(expected.iter()) {
+                        assert_eq!(actual.start, expected.0, "{}", case.name);
+                        assert_eq!(actual.end, expected.1, "{}", case.name);
+                        assert_eq!(
+                            actual.slot.start().to_rfc3339(),
+                            expected.0,
+                            "{}",
+                            case.name
+                        );
+                    }
+                }
+                Err(err) => {
+                    assert_eq!(
+                        err.to_string(),
+                        case.expected.unwrap_err().to_string(),
+                        "{}",
+                        case.name
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+
+use crate::periods::{
+    period::{Input, Period, PeriodError},
+    slot::Slot,
+    span::Span,
+    zoned,
+};
+
+use super::{
+    metrics::{find_with_metrics, Metrics},
+    options::{FindOptions, OutOfSpanPolicy},
+};
+
+/// Version of the JSON shape of FindReport. It is raised on any change that breaks readers:
+/// a field removed, renamed, or changed in type or meaning. Fields and warnings may be added
+/// without raising it, so readers should ignore unknown fields.
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// The results of a search in a documented JSON shape for other services, with the serde feature:
+///
+/// ```json
+/// {
+///   "schema_version": 1,
+///   "slots": [
+///     {"start": "2024-01-01T09:00:00+09:00[Asia/Tokyo]", "end": "2024-01-01T10:00:00+09:00[Asia/Tokyo]", "minutes": 60}
+///   ],
+///   "stats": {"slots": 1, "free_minutes": 60, "blocks_examined": 2, "blocks_skipped": 0, "duplicates": 0, "past_inputs": 0, "elapsed_micros": 12},
+///   "warnings": [{"code": "inputs_outside_span", "indices": [1]}]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FindReport {
+    /// REPORT_SCHEMA_VERSION of the writer.
+    pub schema_version: u32,
+    pub slots: Vec<ReportSlot>,
+    pub stats: ReportStats,
+    pub warnings: Vec<ReportWarning>,
+}
+
+/// A slot of a FindReport. Times are RFC 3339 with the timezone name in brackets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportSlot {
+    #[serde(with = "zoned")]
+    pub start: DateTime<Tz>,
+    #[serde(with = "zoned")]
+    pub end: DateTime<Tz>,
+    pub minutes: i64,
+}
+
+/// The work done by the search of a FindReport.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReportStats {
+    pub slots: usize,
+    /// Sum of the minutes of the slots.
+    pub free_minutes: i64,
+    pub blocks_examined: usize,
+    pub blocks_skipped: usize,
+    pub duplicates: usize,
+    pub past_inputs: usize,
+    pub elapsed_micros: u64,
+}
+
+/// Something a caller may want to know about a search that did not fail, identified by "code".
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "code", rename_all = "snake_case")]
+pub enum ReportWarning {
+    /// No free time was found.
+    NoSlots,
+    /// Inputs outside the span, by index (with OutOfSpanPolicy::Warn).
+    InputsOutsideSpan { indices: Vec<usize> },
+    /// Inputs ended before now were dropped (with drop_past_inputs).
+    PastInputsDropped { count: usize },
+    /// A warning added by a newer writer.
+    #[serde(other)]
+    Unknown,
+}
+
+impl FindReport {
+    /// constructor. The warnings of the slots and the metrics are added.
+    pub fn new(slots: &[Slot], metrics: &Metrics) -> Self {
+        let slots = slots
+            .iter()
+            .map(|slot| ReportSlot {
+                start: slot.start(),
+                end: slot.end(),
+                minutes: (slot.end() - slot.start()).num_minutes(),
+            })
+            .collect::<Vec<_>>();
+        let stats = ReportStats {
+            slots: slots.len(),
+            free_minutes: slots.iter().map(|s| s.minutes).sum(),
+            blocks_examined: metrics.blocks_examined,
+            blocks_skipped: metrics.blocks_skipped,
+            duplicates: metrics.duplicates,
+            past_inputs: metrics.past_inputs,
+            elapsed_micros: u64::try_from(metrics.elapsed.as_micros()).unwrap_or(u64::MAX),
+        };
+        let mut warnings = Vec::new();
+        if slots.is_empty() {
+            warnings.push(ReportWarning::NoSlots);
+        }
+        if metrics.past_inputs > 0 {
+            warnings.push(ReportWarning::PastInputsDropped {
+                count: metrics.past_inputs,
+            });
+        }
+        FindReport {
+            schema_version: REPORT_SCHEMA_VERSION,
+            slots,
+            stats,
+            warnings,
+        }
+    }
+
+    /// Whether the report was written in a shape this version can read.
+    pub fn is_supported(&self) -> bool {
+        self.schema_version == REPORT_SCHEMA_VERSION
+    }
+}
+
+// Same as find_with_metrics, and the results are put in a FindReport to serialize for other services.
+// With OutOfSpanPolicy::Warn, the inputs outside the span are reported as a warning.
+pub fn find_report<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+    options: &FindOptions,
+) -> Result<FindReport, PeriodError> {
+    let outside = match options.out_of_span_policy() {
+        OutOfSpanPolicy::Warn => span.outside(&inputs),
+        _ => Vec::new(),
+    };
+    let (slots, metrics): (Vec<Slot>, Metrics) = find_with_metrics(span, inputs, options)?;
+    let mut report = FindReport::new(&slots, &metrics);
+    if !outside.is_empty() {
+        report
+            .warnings
+            .push(ReportWarning::InputsOutsideSpan { indices: outside });
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::TimeZone;
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_find_report() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9), dt(12));
+
+        struct TestCase {
+            name: &'static str,
+            blocks: Vec<Block>,
+            options: FindOptions,
+            expected_minutes: Vec<i64>,
+            expected_warnings: Vec<ReportWarning>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "No warnings",
+                blocks: vec![Block::new(dt(10), dt(11))?],
+                options: FindOptions::new(),
+                expected_minutes: vec![60, 60],
+                expected_warnings: vec![],
+            },
+            TestCase {
+                name: "No slots",
+                blocks: vec![Block::new(dt(8), dt(13))?],
+                options: FindOptions::new(),
+                expected_minutes: vec![],
+                expected_warnings: vec![ReportWarning::NoSlots],
+            },
+            TestCase {
+                name: "Inputs outside the span",
+                blocks: vec![Block::new(dt(10), dt(11))?, Block::new(dt(13), dt(14))?],
+                options: FindOptions::new().out_of_span(OutOfSpanPolicy::Warn),
+                expected_minutes: vec![60, 60],
+                expected_warnings: vec![ReportWarning::InputsOutsideSpan { indices: vec![1] }],
+            },
+        ];
+
+        for case in cases {
+            let report = find_report(span()?, case.blocks, &case.options)?;
+            let minutes = report.slots.iter().map(|s| s.minutes).collect::<Vec<_>>();
+            assert_eq!(minutes, case.expected_minutes, "{}", case.name);
+            assert_eq!(
+                report.stats.free_minutes,
+                minutes.iter().sum::<i64>(),
+                "{}",
+                case.name
+            );
+            assert_eq!(report.warnings, case.expected_warnings, "{}", case.name);
+            assert!(report.is_supported(), "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_report_json() -> Result<(), serde_json::Error> {
+        let slot = Slot::new(dt(9), dt(10)).unwrap();
+        let metrics = Metrics {
+            blocks_examined: 2,
+            ..Metrics::default()
+        };
+        let report = FindReport::new(&[slot], &metrics);
+        let json = serde_json::to_value(&report)?;
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "schema_version": 1,
+                "slots": [{
+                    "start": "2024-01-01T09:00:00+09:00[Asia/Tokyo]",
+                    "end": "2024-01-01T10:00:00+09:00[Asia/Tokyo]",
+                    "minutes": 60,
+                }],
+                "stats": {
+                    "slots": 1,
+                    "free_minutes": 60,
+                    "blocks_examined": 2,
+                    "blocks_skipped": 0,
+                    "duplicates": 0,
+                    "past_inputs": 0,
+                    "elapsed_micros": 0,
+                },
+                "warnings": [],
+            })
+        );
+        let read: FindReport = serde_json::from_value(json)?;
+        assert_eq!(read, report);
+
+        // A newer writer: unknown fields are ignored and unknown warnings are read as Unknown.
+        let newer: FindReport = serde_json::from_str(
+            r#"{"schema_version": 1, "slots": [], "stats": {"slots": 0, "free_minutes": 0,
+            "blocks_examined": 0, "blocks_skipped": 0, "duplicates": 0, "past_inputs": 0,
+            "elapsed_micros": 0, "cache_hits": 3}, "warnings": [{"code": "no_slots"},
+            {"code": "slow_search", "millis": 900}], "request_id": "abc"}"#,
+        )?;
+        assert_eq!(
+            newer.warnings,
+            vec![ReportWarning::NoSlots, ReportWarning::Unknown]
+        );
+        Ok(())
+    }
+}
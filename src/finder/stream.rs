@@ -0,0 +1,96 @@
+use std::sync::mpsc::Sender;
+
+use crate::periods::{
+    period::{Input, Output, PeriodError},
+    span::Span,
+};
+
+use super::page::find_iter;
+
+// Call the sink with each slot as soon as it is found, in order of start time, until it returns false.
+// Use it to send slots into any channel, e.g. with try_send or blocking_send of tokio.
+// Returns the number of slots passed to the sink.
+pub fn find_each<In: Input, Out: Output>(
+    span: Span,
+    inputs: &[In],
+    mut sink: impl FnMut(Out) -> bool,
+) -> Result<usize, PeriodError> {
+    let mut count = 0;
+    for slot in find_iter(span, inputs)? {
+        count += 1;
+        if !sink(Out::create_from_slot(slot)) {
+            break;
+        }
+    }
+    Ok(count)
+}
+
+// Send each slot to the channel as soon as it is found, so a receiver can render the first slots
+// while the rest are searched. Stops without an error when the receiver is dropped.
+// Returns the number of slots sent.
+pub fn find_to_sender<In: Input, Out: Output>(
+    span: Span,
+    inputs: &[In],
+    sender: &Sender<Out>,
+) -> Result<usize, PeriodError> {
+    let mut sent = 0;
+    find_each(span, inputs, |slot| {
+        let ok = sender.send(slot).is_ok();
+        sent += usize::from(ok);
+        ok
+    })?;
+    Ok(sent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::{block::Block, period::Period, slot::Slot};
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+    use std::{sync::mpsc, thread};
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    fn blocks() -> Result<Vec<Block>, PeriodError> {
+        (10..16)
+            .step_by(2)
+            .map(|h| Block::new(dt(h), dt(h + 1)))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_to_sender() -> Result<(), PeriodError> {
+        let (sender, receiver) = mpsc::channel::<Slot>();
+        let busy = blocks()?;
+        let producer =
+            thread::spawn(move || find_to_sender(Span::new(dt(9), dt(17))?, &busy, &sender));
+        let starts = receiver.iter().map(|s| s.start()).collect::<Vec<_>>();
+        assert_eq!(producer.join().unwrap()?, 4);
+        assert_eq!(starts, vec![dt(9), dt(11), dt(13), dt(15)]);
+
+        let (sender, receiver) = mpsc::channel::<Slot>();
+        drop(receiver);
+        assert_eq!(
+            find_to_sender(Span::new(dt(9), dt(17))?, &blocks()?, &sender)?,
+            0
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_each_stops() -> Result<(), PeriodError> {
+        let mut starts = Vec::new();
+        let count = find_each(Span::new(dt(9), dt(17))?, &blocks()?, |slot: Slot| {
+            starts.push(slot.start());
+            starts.len() < 2
+        })?;
+        assert_eq!(count, 2);
+        assert_eq!(starts, vec![dt(9), dt(11)]);
+        Ok(())
+    }
+}
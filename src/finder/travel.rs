@@ -0,0 +1,141 @@
+use chrono::Duration;
+
+use crate::periods::{
+    period::{Period, PeriodError},
+    slot::Slot,
+    span::Span,
+    tagged::Tagged,
+};
+
+use super::bounded::find_annotated;
+
+// Same as find, but events carry their location, and a slot at the location leaves time to travel
+// from the event before it and to the event after it. travel(from, to) is the time needed
+// (e.g. 45 minutes between on-site and off-site). Slots too short to travel are dropped.
+pub fn find_with_travel<L, F>(
+    span: Span,
+    events: &[Tagged<L>],
+    location: &L,
+    travel: F,
+) -> Result<Vec<Slot>, PeriodError>
+where
+    F: Fn(&L, &L) -> Duration,
+{
+    let longest = |times: Vec<Duration>| -> Result<Duration, PeriodError> {
+        match times.iter().find(|d| **d < Duration::zero()) {
+            Some(d) => Err(PeriodError::InvalidDuration(*d)),
+            None => Ok(times.into_iter().max().unwrap_or_else(Duration::zero)),
+        }
+    };
+    let mut slots = Vec::new();
+    for annotated in find_annotated(span, events)? {
+        let before = longest(
+            annotated
+                .before
+                .iter()
+                .map(|e| travel(&e.payload, location))
+                .collect(),
+        )?;
+        let after = longest(
+            annotated
+                .after
+                .iter()
+                .map(|e| travel(location, &e.payload))
+                .collect(),
+        )?;
+        let start = annotated.slot.start() + before;
+        let end = annotated.slot.end() - after;
+        if start < end {
+            slots.push(Slot::new(start, end)?);
+        }
+    }
+    Ok(slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32, min: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, min, 0)
+            .unwrap()
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum Location {
+        Office,
+        Client,
+        Remote,
+    }
+
+    fn travel(from: &Location, to: &Location) -> Duration {
+        match (from, to) {
+            (Location::Remote, _) | (_, Location::Remote) => Duration::zero(),
+            (a, b) if a == b => Duration::zero(),
+            _ => Duration::minutes(45),
+        }
+    }
+
+    #[test]
+    fn test_find_with_travel() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(18, 0));
+        let events = vec![
+            Tagged::new(dt(10, 0), dt(11, 0), Location::Client)?,
+            Tagged::new(dt(12, 0), dt(13, 0), Location::Office)?,
+            Tagged::new(dt(14, 0), dt(14, 30), Location::Remote)?,
+            Tagged::new(dt(15, 0), dt(16, 0), Location::Client)?,
+        ];
+
+        struct TestCase {
+            name: &'static str,
+            location: Location,
+            expected: Vec<((u32, u32), (u32, u32))>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "At the office, without time to reach the client at 15:00",
+                location: Location::Office,
+                expected: vec![
+                    ((9, 0), (9, 15)),
+                    ((11, 45), (12, 0)),
+                    ((13, 0), (14, 0)),
+                    ((16, 45), (18, 0)),
+                ],
+            },
+            TestCase {
+                name: "Remote",
+                location: Location::Remote,
+                expected: vec![
+                    ((9, 0), (10, 0)),
+                    ((11, 0), (12, 0)),
+                    ((13, 0), (14, 0)),
+                    ((14, 30), (15, 0)),
+                    ((16, 0), (18, 0)),
+                ],
+            },
+        ];
+
+        for case in cases {
+            let actual = find_with_travel(span()?, &events, &case.location, travel)?
+                .iter()
+                .map(|s| (s.start(), s.end()))
+                .collect::<Vec<_>>();
+            let expected = case
+                .expected
+                .iter()
+                .map(|(s, e)| (dt(s.0, s.1), dt(e.0, e.1)))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{}", case.name);
+        }
+
+        let negative = find_with_travel(span()?, &events, &Location::Office, |_, _| {
+            Duration::minutes(-1)
+        });
+        assert!(negative.is_err());
+        Ok(())
+    }
+}
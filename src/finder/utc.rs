@@ -0,0 +1,64 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+
+use crate::periods::{
+    block::Block,
+    period::{Output, PeriodError},
+    span::Span,
+};
+
+use super::find::find;
+
+// Same as find, but the span and the scheduled events are UTC as most storage keeps them.
+// They are converted into the timezone, and the slots are returned in it.
+pub fn find_utc<Out: Output>(
+    span: (DateTime<Utc>, DateTime<Utc>),
+    events: &[(DateTime<Utc>, DateTime<Utc>)],
+    tz: Tz,
+) -> Result<Vec<Out>, PeriodError> {
+    let span = Span::new(span.0.with_timezone(&tz), span.1.with_timezone(&tz))?;
+    let blocks = events
+        .iter()
+        .map(|(start, end)| Block::new(start.with_timezone(&tz), end.with_timezone(&tz)))
+        .collect::<Result<Vec<_>, _>>()?;
+    find(span, blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::{period::Period, slot::Slot};
+    use chrono::TimeZone;
+
+    fn utc(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_find_utc() -> Result<(), PeriodError> {
+        let tz = chrono_tz::Asia::Tokyo;
+        let slots: Vec<Slot> = find_utc((utc(0), utc(8)), &[(utc(3), utc(4))], tz)?;
+        let actual = slots
+            .iter()
+            .map(|s| (s.start().to_rfc3339(), s.end().to_rfc3339()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![
+                (
+                    "2024-01-01T09:00:00+09:00".to_string(),
+                    "2024-01-01T12:00:00+09:00".to_string()
+                ),
+                (
+                    "2024-01-01T13:00:00+09:00".to_string(),
+                    "2024-01-01T17:00:00+09:00".to_string()
+                ),
+            ]
+        );
+        assert!(slots.iter().all(|s| s.start().timezone() == tz));
+
+        let result: Result<Vec<Slot>, _> = find_utc((utc(8), utc(0)), &[], tz);
+        assert!(result.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,8 @@
+#[cfg(feature = "csv")]
+pub use self::csv::*;
+#[cfg(feature = "jsonl")]
+pub use self::jsonl::*;
+#[cfg(feature = "csv")]
+pub mod csv;
+#[cfg(feature = "jsonl")]
+pub mod jsonl;
@@ -0,0 +1,217 @@
+use std::io::Read;
+
+use chrono::{DateTime, NaiveDateTime};
+use chrono_tz::Tz;
+
+use crate::{
+    finder::rfc3339::{parse_rfc3339, parse_tz},
+    periods::{block::Block, period::PeriodError},
+    rules::work_week::local_time,
+};
+
+/// Where the events are in a CSV, by the names of the header columns.
+/// By default the columns are "start" and "end" in RFC 3339, in the timezone given.
+#[derive(Debug, Clone)]
+pub struct CsvFormat {
+    start: String,
+    end: String,
+    timezone: Option<String>,
+    datetime_format: Option<String>,
+    tz: Tz,
+    delimiter: u8,
+}
+
+impl CsvFormat {
+    /// constructor. Times are read into the timezone, or in it if they have no offset.
+    pub fn new(tz: Tz) -> Self {
+        CsvFormat {
+            start: "start".to_string(),
+            end: "end".to_string(),
+            timezone: None,
+            datetime_format: None,
+            tz,
+            delimiter: b',',
+        }
+    }
+
+    /// Name of the column of start times.
+    pub fn start_column(mut self, name: &str) -> Self {
+        self.start = name.to_string();
+        self
+    }
+
+    /// Name of the column of end times.
+    pub fn end_column(mut self, name: &str) -> Self {
+        self.end = name.to_string();
+        self
+    }
+
+    /// Name of a column of timezone names (e.g. "Asia/Tokyo") that the times of the row are in.
+    /// An empty cell is the timezone of the format.
+    pub fn timezone_column(mut self, name: &str) -> Self {
+        self.timezone = Some(name.to_string());
+        self
+    }
+
+    /// Format of local times without offset (e.g. "%Y/%m/%d %H:%M"), instead of RFC 3339.
+    pub fn datetime_format(mut self, format: &str) -> Self {
+        self.datetime_format = Some(format.to_string());
+        self
+    }
+
+    /// Delimiter of the columns, e.g. b';' or b'\t'.
+    pub fn delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    // The time of a cell in the timezone of the row.
+    fn parse(&self, s: &str, tz: &Tz) -> Result<DateTime<Tz>, PeriodError> {
+        match &self.datetime_format {
+            Some(format) => NaiveDateTime::parse_from_str(s.trim(), format)
+                .map_err(|_| PeriodError::InvalidFormat(s.to_string()))
+                .and_then(|naive| local_time(tz, naive)),
+            None => parse_rfc3339(s.trim(), tz),
+        }
+    }
+}
+
+/// A row of the CSV that could not be read.
+#[derive(Debug)]
+pub struct CsvRowError {
+    /// Line of the row in the CSV, from 1 for the header.
+    pub line: u64,
+    pub error: PeriodError,
+}
+
+/// The blocks read from a CSV, and the rows skipped with their errors.
+#[derive(Debug, Default)]
+pub struct CsvImport {
+    pub blocks: Vec<Block>,
+    pub errors: Vec<CsvRowError>,
+}
+
+// Read the events of a CSV with a header into blocks, e.g. a data dump of a legacy system.
+// A row that cannot be read is reported with its line, and the other rows are still read.
+// Fails only if the header cannot be read or lacks a column of the format.
+pub fn read_csv<R: Read>(reader: R, format: &CsvFormat) -> Result<CsvImport, PeriodError> {
+    let mut reader = ::csv::ReaderBuilder::new()
+        .delimiter(format.delimiter)
+        .trim(::csv::Trim::All)
+        .from_reader(reader);
+    let headers = reader
+        .headers()
+        .map_err(|err| PeriodError::InvalidFormat(err.to_string()))?
+        .clone();
+    let column = |name: &str| {
+        headers
+            .iter()
+            .position(|h| h == name)
+            .ok_or_else(|| PeriodError::InvalidFormat(format!("no column {}", name)))
+    };
+    let (start, end) = (column(&format.start)?, column(&format.end)?);
+    let timezone = format.timezone.as_deref().map(column).transpose()?;
+
+    let mut import = CsvImport::default();
+    for record in reader.records() {
+        let row = record
+            .map_err(|err| {
+                let line = err.position().map_or(0, |p| p.line());
+                (line, PeriodError::InvalidFormat(err.to_string()))
+            })
+            .and_then(|record| {
+                let line = record.position().map_or(0, |p| p.line());
+                let cell = |i: usize| record.get(i).unwrap_or_default();
+                let block = (|| {
+                    let tz = match timezone.map(cell) {
+                        Some(name) if !name.is_empty() => parse_tz(name)?,
+                        _ => format.tz,
+                    };
+                    Block::new(
+                        format.parse(cell(start), &tz)?,
+                        format.parse(cell(end), &tz)?,
+                    )
+                })();
+                block.map_err(|error| (line, error))
+            });
+        match row {
+            Ok(block) => import.blocks.push(block),
+            Err((line, error)) => import.errors.push(CsvRowError { line, error }),
+        }
+    }
+    Ok(import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::period::Period;
+    use chrono::TimeZone;
+
+    fn tokyo(h: u32, m: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, 1, 1, h, m, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_read_csv() -> Result<(), PeriodError> {
+        let tokyo_tz = chrono_tz::Asia::Tokyo;
+        let csv = "\
+start,end,title
+2024-01-01T09:00:00+09:00,2024-01-01T10:00:00+09:00,Standup
+2024-01-01T01:00:00Z,2024-01-01T02:00:00Z,\"Review, weekly\"
+2024-01-01T12:00:00+09:00,2024-01-01T11:00:00+09:00,Backwards
+yesterday,2024-01-01T11:00:00+09:00,Bad time
+";
+        let import = read_csv(csv.as_bytes(), &CsvFormat::new(tokyo_tz))?;
+        let actual = import
+            .blocks
+            .iter()
+            .map(|b| (b.start(), b.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![(tokyo(9, 0), tokyo(10, 0)), (tokyo(10, 0), tokyo(11, 0))]
+        );
+        let lines = import.errors.iter().map(|e| e.line).collect::<Vec<_>>();
+        assert_eq!(lines, vec![4, 5]);
+        assert!(matches!(
+            import.errors[1].error,
+            PeriodError::InvalidFormat(_)
+        ));
+
+        // Other columns, a timezone column, and a local datetime format.
+        let csv = "\
+From;To;Zone
+2024/01/01 09:00;2024/01/01 09:30;
+2024/01/01 01:00;2024/01/01 02:00;UTC
+2024/01/01 01:00;2024/01/01 02:00;Mars/Olympus
+";
+        let format = CsvFormat::new(tokyo_tz)
+            .start_column("From")
+            .end_column("To")
+            .timezone_column("Zone")
+            .datetime_format("%Y/%m/%d %H:%M")
+            .delimiter(b';');
+        let import = read_csv(csv.as_bytes(), &format)?;
+        let actual = import
+            .blocks
+            .iter()
+            .map(|b| (b.start(), b.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![(tokyo(9, 0), tokyo(9, 30)), (tokyo(10, 0), tokyo(11, 0))]
+        );
+        assert_eq!(import.errors.len(), 1);
+        assert_eq!(
+            import.errors[0].error.to_string(),
+            PeriodError::InvalidTimezone("Mars/Olympus".to_string()).to_string()
+        );
+
+        let missing = CsvFormat::new(tokyo_tz).start_column("begin");
+        assert!(read_csv("start,end\n".as_bytes(), &missing).is_err());
+        Ok(())
+    }
+}
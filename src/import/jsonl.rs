@@ -0,0 +1,316 @@
+use std::io::{self, BufRead, Lines};
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::Deserialize;
+
+use crate::{
+    finder::{page::Sweep, rfc3339::parse_rfc3339},
+    periods::{
+        block::Block,
+        period::{Output, Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+// An event of a line. Other fields of the line are ignored.
+#[derive(Deserialize)]
+struct JsonlEvent {
+    start: String,
+    end: String,
+}
+
+/// A line of JSON Lines that could not be read.
+#[derive(Debug)]
+pub struct JsonlLineError {
+    /// Line in the input, from 1.
+    pub line: u64,
+    pub error: PeriodError,
+}
+
+// The block of a line like {"start": "2024-01-01T09:00:00+09:00", "end": "..."}, None for a blank line.
+fn parse_line(line: &str, tz: &Tz) -> Option<Result<Block, PeriodError>> {
+    if line.trim().is_empty() {
+        return None;
+    }
+    let block = serde_json::from_str::<JsonlEvent>(line)
+        .map_err(|err| PeriodError::InvalidFormat(err.to_string()))
+        .and_then(|e| Block::new(parse_rfc3339(&e.start, tz)?, parse_rfc3339(&e.end, tz)?));
+    Some(block)
+}
+
+fn read_error(err: io::Error) -> PeriodError {
+    PeriodError::InvalidFormat(err.to_string())
+}
+
+/// Blocks of JSON Lines, read one line at a time. Created by read_jsonl.
+#[derive(Debug)]
+pub struct JsonlBlocks<R> {
+    lines: Lines<R>,
+    tz: Tz,
+    line: u64,
+}
+
+impl<R: BufRead> Iterator for JsonlBlocks<R> {
+    type Item = Result<Block, JsonlLineError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line += 1;
+            let block = match self.lines.next()? {
+                Ok(line) => parse_line(&line, &self.tz),
+                Err(err) => Some(Err(read_error(err))),
+            };
+            if let Some(block) = block {
+                let line = self.line;
+                return Some(block.map_err(|error| JsonlLineError { line, error }));
+            }
+        }
+    }
+}
+
+// Read the events of JSON Lines into blocks as they are iterated, without reading the whole input,
+// e.g. an export of a calendar too large for memory. Each line is an object with "start" and "end"
+// in RFC 3339, read into the timezone. Blank lines are skipped, and a line that cannot be read is
+// an error item with its line, after which the other lines are still read.
+pub fn read_jsonl<R: BufRead>(reader: R, tz: Tz) -> JsonlBlocks<R> {
+    JsonlBlocks {
+        lines: reader.lines(),
+        tz,
+        line: 0,
+    }
+}
+
+// The streaming search fed one line at a time, shared by the readers.
+struct Feed {
+    sweep: Sweep,
+    tz: Tz,
+    line: u64,
+    previous: Option<DateTime<Utc>>,
+}
+
+impl Feed {
+    fn new(span: Span) -> Self {
+        Feed {
+            tz: span.start().timezone(),
+            sweep: Sweep::new(span),
+            line: 0,
+            previous: None,
+        }
+    }
+
+    fn error(&self, error: PeriodError) -> JsonlLineError {
+        JsonlLineError {
+            line: self.line,
+            error,
+        }
+    }
+
+    // Cut the block of the next line out of the span, None at the end of the input.
+    // Returns the gap found, if any.
+    fn next(&mut self, line: Option<&str>) -> Result<Option<Slot>, JsonlLineError> {
+        self.line += 1;
+        let block = match line.map(|line| parse_line(line, &self.tz)) {
+            Some(None) => return Ok(None),
+            Some(Some(block)) => Some(block.map_err(|err| self.error(err))?),
+            None => None,
+        };
+        if let Some(block) = &block {
+            let start = block.start().with_timezone(&Utc);
+            if self.previous.is_some_and(|previous| start < previous) {
+                let order = format!("{} starts before the event above", block.start());
+                return Err(self.error(PeriodError::InvalidFormat(order)));
+            }
+            self.previous = Some(start);
+        }
+        Ok(self.sweep.cut(block))
+    }
+}
+
+// Call the sink with each slot of the span excluding the events of JSON Lines, in order of start time,
+// until it returns false. The events are read one line at a time and must be ordered by start time,
+// so memory does not grow with the input, and reading stops once the span is used up.
+// Times without offset are read in the timezone of the span. The first line that cannot be read,
+// or that starts before the line above, fails the search with its line.
+// Returns the number of slots passed to the sink.
+pub fn find_jsonl<R: BufRead, Out: Output>(
+    span: Span,
+    reader: R,
+    mut sink: impl FnMut(Out) -> bool,
+) -> Result<usize, JsonlLineError> {
+    let mut feed = Feed::new(span);
+    let mut lines = reader.lines();
+    let mut count = 0;
+    while !feed.sweep.is_done() {
+        let line = lines
+            .next()
+            .transpose()
+            .map_err(|err| feed.error(read_error(err)))?;
+        if let Some(slot) = feed.next(line.as_deref())? {
+            count += 1;
+            if !sink(Out::create_from_slot(slot)) {
+                break;
+            }
+        }
+    }
+    Ok(count)
+}
+
+// Same as find_jsonl, reading from an async reader of tokio, e.g. a file or a socket.
+#[cfg(feature = "tokio")]
+pub async fn find_jsonl_async<R, Out>(
+    span: Span,
+    reader: R,
+    mut sink: impl FnMut(Out) -> bool,
+) -> Result<usize, JsonlLineError>
+where
+    R: tokio::io::AsyncBufRead + Unpin,
+    Out: Output,
+{
+    use tokio::io::AsyncBufReadExt;
+
+    let mut feed = Feed::new(span);
+    let mut lines = reader.lines();
+    let mut count = 0;
+    while !feed.sweep.is_done() {
+        let line = lines
+            .next_line()
+            .await
+            .map_err(|err| feed.error(read_error(err)))?;
+        if let Some(slot) = feed.next(line.as_deref())? {
+            count += 1;
+            if !sink(Out::create_from_slot(slot)) {
+                break;
+            }
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn tokyo(h: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, 1, 1, h, 0, 0)
+            .unwrap()
+    }
+
+    fn event(start: u32, end: u32) -> String {
+        format!(
+            r#"{{"start": "{}", "end": "{}", "title": "Meeting"}}"#,
+            tokyo(start).to_rfc3339(),
+            tokyo(end).to_rfc3339()
+        )
+    }
+
+    #[test]
+    fn test_read_jsonl() {
+        let jsonl = [
+            event(9, 10),
+            String::new(),
+            r#"{"start": "2024-01-01T01:00:00Z", "end": "2024-01-01T02:00:00Z"}"#.to_string(),
+            r#"{"start": "2024-01-01T09:00:00+09:00"}"#.to_string(),
+            event(12, 11),
+        ]
+        .join("\n");
+        let rows = read_jsonl(jsonl.as_bytes(), chrono_tz::Asia::Tokyo).collect::<Vec<_>>();
+        let blocks = rows
+            .iter()
+            .filter_map(|row| row.as_ref().ok())
+            .map(|b| (b.start(), b.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(blocks, vec![(tokyo(9), tokyo(10)), (tokyo(10), tokyo(11))]);
+        let lines = rows
+            .iter()
+            .filter_map(|row| row.as_ref().err())
+            .map(|e| e.line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, vec![4, 5]);
+    }
+
+    #[test]
+    fn test_find_jsonl() -> Result<(), PeriodError> {
+        let span = || Span::new(tokyo(9), tokyo(17));
+
+        struct TestCase {
+            name: &'static str,
+            lines: Vec<String>,
+            expected: Result<Vec<(u32, u32)>, u64>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Overlapping events",
+                lines: vec![event(8, 10), String::new(), event(9, 11), event(13, 14)],
+                expected: Ok(vec![(11, 13), (14, 17)]),
+            },
+            TestCase {
+                name: "Events after the span are not read",
+                lines: vec![event(10, 11), event(18, 19), "not json".to_string()],
+                expected: Ok(vec![(9, 10), (11, 17)]),
+            },
+            TestCase {
+                name: "Unordered events",
+                lines: vec![event(13, 14), event(10, 11)],
+                expected: Err(2),
+            },
+            TestCase {
+                name: "Invalid line",
+                lines: vec![event(10, 11), String::new(), "{}".to_string()],
+                expected: Err(3),
+            },
+        ];
+
+        for case in cases {
+            let jsonl = case.lines.join("\n");
+            let mut slots = Vec::new();
+            let actual = find_jsonl(span()?, jsonl.as_bytes(), |slot: Slot| {
+                slots.push(slot);
+                true
+            })
+            .map(|_| {
+                slots
+                    .iter()
+                    .map(|s| (s.start(), s.end()))
+                    .collect::<Vec<_>>()
+            })
+            .map_err(|e| e.line);
+            let expected = case.expected.map(|hours| {
+                hours
+                    .into_iter()
+                    .map(|(start, end)| (tokyo(start), tokyo(end)))
+                    .collect::<Vec<_>>()
+            });
+            assert_eq!(actual, expected, "{}", case.name);
+        }
+
+        let jsonl = [event(10, 11), event(12, 13)].join("\n");
+        let count = find_jsonl(span()?, jsonl.as_bytes(), |_: Slot| false).map_err(|e| e.error)?;
+        assert_eq!(count, 1, "Stops when the sink returns false");
+        Ok(())
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_find_jsonl_async() -> Result<(), PeriodError> {
+        let jsonl = [event(10, 11), event(12, 13)].join("\n");
+        let mut starts = Vec::new();
+        let count = find_jsonl_async(
+            Span::new(tokyo(9), tokyo(17))?,
+            jsonl.as_bytes(),
+            |slot: Slot| {
+                starts.push(slot.start());
+                true
+            },
+        )
+        .await
+        .map_err(|e| e.error)?;
+        assert_eq!(count, 3);
+        assert_eq!(starts, vec![tokyo(9), tokyo(11), tokyo(13)]);
+        Ok(())
+    }
+}
@@ -1,7 +1,60 @@
-/// chrono-slots is a library for finding free time slots within a given period,
-/// excluding the times of already scheduled events.
+//! chrono-slots is a library for finding free time slots within a given period,
+//! excluding the times of already scheduled events.
+//! Without the default `std` feature, the periods and find compile under `no_std` with `alloc`.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[macro_use]
+mod trace;
+
+#[cfg(feature = "std")]
+pub mod analysis;
+#[cfg(feature = "std")]
+pub mod calendar;
+pub mod cancel;
+#[cfg(feature = "std")]
+pub mod clock;
+/// Compact binary encodings (MessagePack, CBOR) of periods and results, e.g. for RPC between services.
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+pub mod codec;
 pub mod finder;
+/// Readers of events from data dumps (CSV, JSON Lines) into inputs.
+#[cfg(any(feature = "csv", feature = "jsonl"))]
+pub mod import;
 pub mod periods;
+#[cfg(feature = "std")]
+pub mod rules;
+#[cfg(feature = "std")]
+pub mod scheduler;
+
+/// C interface for non-Rust callers. All times are Unix epoch seconds (UTC).
+/// The header is generated into `include/chrono_slots.h`.
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+/// Arbitrary implementations and generators of periods for property tests with proptest.
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+
+/// Python bindings. Build the extension module with `maturin build`.
+#[cfg(feature = "python")]
+pub mod python;
 
+#[cfg(feature = "std")]
+pub use crate::analysis::*;
+#[cfg(feature = "std")]
+pub use crate::calendar::*;
+pub use crate::cancel::*;
+#[cfg(feature = "std")]
+pub use crate::clock::*;
+#[cfg(any(feature = "msgpack", feature = "cbor"))]
+pub use crate::codec::*;
 pub use crate::finder::*;
+#[cfg(any(feature = "csv", feature = "jsonl"))]
+pub use crate::import::*;
 pub use crate::periods::*;
+#[cfg(feature = "std")]
+pub use crate::rules::*;
+#[cfg(feature = "std")]
+pub use crate::scheduler::*;
@@ -2,8 +2,20 @@ pub mod block;
 pub mod period;
 pub mod slot;
 pub mod span;
+pub mod tagged;
 
 pub use self::block::*;
 pub use self::period::*;
 pub use self::slot::*;
 pub use self::span::*;
+pub use self::tagged::*;
+
+#[cfg(feature = "humantime")]
+pub use self::duration::*;
+/// Parsing human-readable durations.
+#[cfg(feature = "humantime")]
+pub mod duration;
+/// Serde of DateTime<Tz> as RFC 3339 with the timezone name in brackets,
+/// e.g. "2024-01-01T09:00:00+09:00[Asia/Tokyo]". Without brackets, the time is read in UTC.
+#[cfg(feature = "serde")]
+pub(crate) mod zoned;
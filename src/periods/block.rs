@@ -1,19 +1,24 @@
-use std::fmt::Debug;
+use alloc::vec::Vec;
+use core::fmt::Debug;
 
-use chrono::DateTime;
+use chrono::{DateTime, Duration};
 use chrono_tz::Tz;
 
 use crate::impl_period;
 
 use super::{
-    period::{Period, PeriodError},
-    Span,
+    period::{from_epoch_millis, shift, Input, Period, PeriodError, Precision},
+    Slot, Span,
 };
 
 // This refers to already scheduled events. The term ‘Block’ will be standardized here.”
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "super::zoned::Times"))]
 pub struct Block {
+    #[cfg_attr(feature = "serde", serde(with = "super::zoned"))]
     start: DateTime<Tz>,
+    #[cfg_attr(feature = "serde", serde(with = "super::zoned"))]
     end: DateTime<Tz>,
 }
 
@@ -23,11 +28,69 @@ impl Block {
     // constructor
     pub fn new(start: DateTime<Tz>, end: DateTime<Tz>) -> Result<Self, PeriodError> {
         if start >= end {
-            return Err(PeriodError::InvalidTime);
+            return Err(PeriodError::StartNotBeforeEnd { start, end });
         }
         Ok(Block { start, end })
     }
 
+    // constructor, which applies the precision policy to the times.
+    pub fn new_with_precision(
+        start: DateTime<Tz>,
+        end: DateTime<Tz>,
+        precision: Precision,
+    ) -> Result<Self, PeriodError> {
+        Block::new(precision.apply(start)?, precision.apply(end)?)
+    }
+
+    // constructor, which swaps the times if they are given in reverse. They must still differ.
+    pub fn new_ordered(a: DateTime<Tz>, b: DateTime<Tz>) -> Result<Self, PeriodError> {
+        Block::new(a.min(b), a.max(b))
+    }
+
+    // constructor from Unix epoch milliseconds, in the timezone.
+    pub fn from_epoch_millis(start: i64, end: i64, tz: Tz) -> Result<Self, PeriodError> {
+        Block::new(from_epoch_millis(start, &tz)?, from_epoch_millis(end, &tz)?)
+    }
+
+    // The block extended by before at the start and after at the end, e.g. for setup and teardown time.
+    pub fn pad(&self, before: Duration, after: Duration) -> Result<Self, PeriodError> {
+        if before < Duration::zero() {
+            return Err(PeriodError::InvalidDuration(before));
+        }
+        if after < Duration::zero() {
+            return Err(PeriodError::InvalidDuration(after));
+        }
+        Block::new(shift(self.start, -before)?, shift(self.end, after)?)
+    }
+
+    // The padded block clamped to the span. Fails if nothing of it is within the span.
+    pub fn pad_within(
+        &self,
+        before: Duration,
+        after: Duration,
+        span: &Span,
+    ) -> Result<Self, PeriodError> {
+        let padded = self.pad(before, after)?;
+        Block::new(padded.start.max(span.start()), padded.end.min(span.end()))
+    }
+
+    // Split the block at an instant strictly inside it. None if the instant is not inside.
+    pub fn split_at(&self, at: DateTime<Tz>) -> Option<(Self, Self)> {
+        if at <= self.start || self.end <= at {
+            return None;
+        }
+        Some((
+            Block {
+                start: self.start,
+                end: at,
+            },
+            Block {
+                start: at,
+                end: self.end,
+            },
+        ))
+    }
+
     // Whether the Block contains the given Period.
     pub fn contains(&self, other: &Span) -> bool {
         self.start <= other.start() && other.end() <= self.end
@@ -49,10 +112,36 @@ impl Block {
     }
 }
 
+// Sort the blocks by start and end time, and remove identical ones (same start and end),
+// such as the same event pulled from several synced calendars.
+pub fn dedup_blocks(mut blocks: Vec<Block>) -> Vec<Block> {
+    blocks.sort_by_key(|b| (b.start, b.end));
+    blocks.dedup_by(|a, b| a.start == b.start && a.end == b.end);
+    blocks
+}
+
+// A Block can be passed to find as is.
+impl Input for Block {
+    fn to_block(&self) -> Result<Block, PeriodError> {
+        Ok(self.clone())
+    }
+}
+
+// A Slot can be booked as a Block.
+impl From<Slot> for Block {
+    fn from(slot: Slot) -> Self {
+        Block {
+            start: slot.start(),
+            end: slot.end(),
+        }
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::unit_arg)]
 mod tests {
     use super::*;
-    use chrono::{Duration, Utc};
+    use chrono::Utc;
     use chrono_tz::Tz;
 
     fn block(now: DateTime<Tz>, start: i64, end: i64) -> Result<Block, PeriodError> {
@@ -196,4 +285,80 @@ mod tests {
         let invalid_block = Block::new(dt(now, 8), dt(now, 0));
         assert!(invalid_block.is_err(), "Invalid block creation should fail");
     }
+
+    #[test]
+    fn test_new_ordered() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let later = now + Duration::hours(1);
+        let cases = vec![("In order", now, later), ("Reversed", later, now)];
+        for (name, a, b) in cases {
+            let block = Block::new_ordered(a, b)?;
+            assert_eq!((block.start(), block.end()), (now, later), "{}", name);
+            let span = Span::new_ordered(a, b)?;
+            assert_eq!((span.start(), span.end()), (now, later), "{}", name);
+        }
+        assert!(Block::new_ordered(now, now).is_err());
+        assert!(Span::new_ordered(now, now).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_pad() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let padded = block(now, 2, 4)?.pad(Duration::minutes(15), Duration::minutes(30))?;
+        assert_eq!(
+            (padded.start(), padded.end()),
+            (now + Duration::minutes(105), now + Duration::minutes(270))
+        );
+        let clamped = block(now, 2, 4)?.pad_within(
+            Duration::hours(3),
+            Duration::zero(),
+            &span(now, 0, 8)?,
+        )?;
+        assert_eq!(clamped.start(), now);
+        assert!(matches!(
+            block(now, 2, 4)?.pad(Duration::max_value(), Duration::zero()),
+            Err(PeriodError::OutOfRange(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_split_at() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let (first, second) = block(now, 0, 8)?
+            .split_at(now + Duration::hours(2))
+            .unwrap();
+        assert_eq!(
+            (first.start(), first.end()),
+            (now, now + Duration::hours(2))
+        );
+        assert_eq!(
+            (second.start(), second.end()),
+            (now + Duration::hours(2), now + Duration::hours(8))
+        );
+        assert!(block(now, 0, 8)?.split_at(now).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_dedup_blocks() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let blocks = vec![
+            block(now, 3, 4)?,
+            block(now, 1, 2)?,
+            block(now, 1, 3)?,
+            block(now, 3, 4)?,
+            Block::new(
+                (now + Duration::hours(1)).with_timezone(&Tz::UTC),
+                (now + Duration::hours(2)).with_timezone(&Tz::UTC),
+            )?,
+        ];
+        let hours = dedup_blocks(blocks)
+            .iter()
+            .map(|b| ((b.start() - now).num_hours(), (b.end() - now).num_hours()))
+            .collect::<Vec<_>>();
+        assert_eq!(hours, vec![(1, 2), (1, 3), (3, 4)]);
+        Ok(())
+    }
 }
@@ -0,0 +1,34 @@
+use chrono::Duration;
+
+use super::period::PeriodError;
+
+// Parse a human-readable duration like "90m", "1h30m", "1h 30m", or "2d"
+// (e.g. for min_duration or buffers read from env vars or config files).
+pub fn parse_duration(s: &str) -> Result<Duration, PeriodError> {
+    let invalid = || PeriodError::InvalidFormat(s.to_string());
+    let std = humantime::parse_duration(s.trim()).map_err(|_| invalid())?;
+    Duration::from_std(std).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        let cases = vec![
+            ("90m", Some(Duration::minutes(90))),
+            ("1h30m", Some(Duration::minutes(90))),
+            ("1h 30m", Some(Duration::minutes(90))),
+            ("2d", Some(Duration::days(2))),
+            (" 45min ", Some(Duration::minutes(45))),
+            ("15s", Some(Duration::seconds(15))),
+            ("90", None),
+            ("an hour", None),
+            ("", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_duration(input).ok(), expected, "{}", input);
+        }
+    }
+}
@@ -1,34 +1,100 @@
-use std::fmt::{self, Debug};
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Debug;
 
-use std::error::Error;
-
-use chrono::DateTime;
+use chrono::{DateTime, Duration, DurationRound, TimeZone, Timelike};
 use chrono_tz::Tz;
+use thiserror::Error;
 
 use super::block::Block;
 use super::slot::Slot;
+use super::span::Span;
 
-#[derive(Debug)]
+/// Errors of this crate. More variants may be added, so match with a wildcard arm.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
 pub enum PeriodError {
-    InvalidTime,
+    /// The start time of a period is not before its end time.
+    #[error("Start time must be before end time. (start: {start}, end: {end})")]
+    StartNotBeforeEnd {
+        start: DateTime<Tz>,
+        end: DateTime<Tz>,
+    },
+    /// A duration that must be positive is not.
+    #[error("Duration must be positive. (duration: {0})")]
+    InvalidDuration(Duration),
+    /// A timezone name is unknown.
+    #[error("Unknown timezone: {0}")]
+    InvalidTimezone(String),
+    /// A time string cannot be parsed.
+    #[error("Invalid time format: {0}")]
+    InvalidFormat(String),
+    /// A time cannot be represented. The value tells which one.
+    #[error("Time is out of range: {0}")]
+    OutOfRange(String),
+    /// A span is longer than allowed.
+    #[error("Span is too long. (length: {length}, max: {max})")]
+    SpanTooLong { length: Duration, max: Duration },
+    /// A time has sub-second precision, which the precision policy rejects.
+    #[error("Time has sub-second precision: {0}")]
+    SubSecond(DateTime<Tz>),
+    /// The computation was cancelled by its token.
+    #[error("Cancelled")]
+    Cancelled,
+    /// The computation passed its deadline.
+    #[error("Timed out")]
+    TimedOut,
+    /// An input is entirely outside the span, which the out-of-span policy rejects.
+    #[error("Input {index} is outside the span")]
+    OutOfSpan { index: usize },
+    /// A slot to reserve overlaps a block or another slot. The index tells which slot.
+    #[error("Slot {index} is already taken")]
+    Conflict { index: usize },
+    /// An input cannot be converted into a Block.
+    #[error("Conversion failed: {0}")]
+    Conversion(String),
 }
 
-impl fmt::Display for PeriodError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match *self {
-            PeriodError::InvalidTime => write!(f, "Start time must be before end time."),
-        }
-    }
+/// How times with sub-second precision (e.g. nanosecond timestamps) are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Precision {
+    /// Keep the time as is. (default)
+    #[default]
+    Keep,
+    /// Truncate the time to whole seconds.
+    Truncate,
+    /// Fail with SubSecond.
+    Reject,
 }
 
-impl Error for PeriodError {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
-        None
+impl Precision {
+    /// Apply the policy to the time.
+    pub fn apply(self, dt: DateTime<Tz>) -> Result<DateTime<Tz>, PeriodError> {
+        match self {
+            Precision::Keep => Ok(dt),
+            _ if dt.nanosecond() == 0 => Ok(dt),
+            Precision::Truncate => dt
+                .duration_trunc(Duration::seconds(1))
+                .map_err(|err| PeriodError::OutOfRange(format!("{}", err))),
+            Precision::Reject => Err(PeriodError::SubSecond(dt)),
+        }
     }
 }
 
 const DATETIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
 
+// Convert Unix epoch milliseconds into the timezone.
+pub(crate) fn from_epoch_millis(millis: i64, tz: &Tz) -> Result<DateTime<Tz>, PeriodError> {
+    tz.timestamp_millis_opt(millis)
+        .single()
+        .ok_or_else(|| PeriodError::OutOfRange(format!("{} ms since the epoch", millis)))
+}
+
+// The time moved by the duration, or OutOfRange if it cannot be represented.
+pub(crate) fn shift(dt: DateTime<Tz>, duration: Duration) -> Result<DateTime<Tz>, PeriodError> {
+    dt.checked_add_signed(duration)
+        .ok_or_else(|| PeriodError::OutOfRange(format!("{} moved by {}", dt, duration)))
+}
+
 /// This is an interface representing a period. Block, Span, and Slot all implement the Period interface.
 pub trait Period {
     /// Start time of the period.
@@ -49,6 +115,59 @@ pub trait Period {
             minutes
         )
     }
+
+    /// Start time and end time as Unix epoch milliseconds.
+    fn epoch_millis(&self) -> (i64, i64) {
+        (
+            self.start().timestamp_millis(),
+            self.end().timestamp_millis(),
+        )
+    }
+
+    /// Free time between the periods, in either order. Zero if they touch, None if they overlap.
+    fn gap_to<P: Period>(&self, other: &P) -> Option<Duration>
+    where
+        Self: Sized,
+    {
+        if self.end() <= other.start() {
+            Some(other.start() - self.end())
+        } else if other.end() <= self.start() {
+            Some(self.start() - other.end())
+        } else {
+            None
+        }
+    }
+
+    /// Signed distance to the other period: positive if it is after this one, negative if before,
+    /// and zero if they touch or overlap.
+    fn distance_to<P: Period>(&self, other: &P) -> Duration
+    where
+        Self: Sized,
+    {
+        if self.end() <= other.start() {
+            other.start() - self.end()
+        } else if other.end() <= self.start() {
+            other.end() - self.start()
+        } else {
+            Duration::zero()
+        }
+    }
+
+    /// The part of the period within the span, in the timezone of the span. None if they do not overlap.
+    fn clip_to(&self, span: &Span) -> Option<Slot>
+    where
+        Self: Sized,
+    {
+        intersect(span, self)
+    }
+}
+
+/// The time common to both periods, in the timezone of the first. None if they do not overlap.
+pub fn intersect<A: Period, B: Period>(a: &A, b: &B) -> Option<Slot> {
+    let tz = a.start().timezone();
+    let start = a.start().max(b.start()).with_timezone(&tz);
+    let end = a.end().min(b.end()).with_timezone(&tz);
+    Slot::new(start, end).ok()
 }
 
 /// input of find
@@ -67,6 +186,31 @@ pub trait Output: Period {
 pub trait PeriodVec {
     /// Represents the start time and end time as strings.
     fn to_string(&self) -> String;
+
+    /// Sum of the lengths of the periods. Overlapping time is counted for each period.
+    fn total_duration(&self) -> Duration;
+
+    /// The earliest start time, or None if empty.
+    fn min_start(&self) -> Option<DateTime<Tz>>;
+
+    /// The latest end time, or None if empty.
+    fn max_end(&self) -> Option<DateTime<Tz>>;
+
+    /// Sort the periods by start time (then by end time).
+    fn sort_by_start(&mut self);
+
+    /// The time covered by the periods as sorted, disjoint periods, joining the ones that overlap
+    /// or touch. The output may be Block or Slot, e.g. `let busy: Vec<Block> = blocks.merge_overlapping();`
+    fn merge_overlapping<P: From<Slot>>(&self) -> Vec<P>;
+
+    /// Index of the period containing the instant (start inclusive, end exclusive), by binary search.
+    /// The periods must be sorted and disjoint, e.g. the output of merge_overlapping or find.
+    fn find_covering(&self, dt: DateTime<Tz>) -> Option<usize>;
+
+    /// Whether any period contains the instant. The periods must be sorted and disjoint.
+    fn contains_instant(&self, dt: DateTime<Tz>) -> bool {
+        self.find_covering(dt).is_some()
+    }
 }
 
 impl<T> PeriodVec for Vec<T>
@@ -80,6 +224,49 @@ where
             .collect::<Vec<_>>()
             .join("\n ")
     }
+
+    fn total_duration(&self) -> Duration {
+        self.iter()
+            .fold(Duration::zero(), |acc, p| acc + (p.end() - p.start()))
+    }
+
+    fn min_start(&self) -> Option<DateTime<Tz>> {
+        self.iter().map(|p| p.start()).min()
+    }
+
+    fn max_end(&self) -> Option<DateTime<Tz>> {
+        self.iter().map(|p| p.end()).max()
+    }
+
+    fn sort_by_start(&mut self) {
+        self.sort_by_key(|p| (p.start(), p.end()));
+    }
+
+    fn merge_overlapping<P: From<Slot>>(&self) -> Vec<P> {
+        let mut bounds = self
+            .iter()
+            .map(|p| (p.start(), p.end()))
+            .filter(|(start, end)| start < end)
+            .collect::<Vec<_>>();
+        bounds.sort();
+        let mut merged: Vec<(DateTime<Tz>, DateTime<Tz>)> = Vec::new();
+        for (start, end) in bounds {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        merged
+            .into_iter()
+            .filter_map(|(start, end)| Slot::new(start, end).ok())
+            .map(P::from)
+            .collect()
+    }
+
+    fn find_covering(&self, dt: DateTime<Tz>) -> Option<usize> {
+        let i = self.partition_point(|p| p.start() <= dt).checked_sub(1)?;
+        (dt < self[i].end()).then_some(i)
+    }
 }
 
 #[macro_export]
@@ -100,6 +287,7 @@ macro_rules! impl_period {
 }
 
 #[cfg(test)]
+#[allow(clippy::unit_arg, clippy::useless_vec)]
 mod tests {
     use super::*;
     use chrono::{Duration, Utc};
@@ -143,6 +331,103 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_period_gap_to() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let base = block(now, 2, 4)?;
+        let cases = vec![
+            ("After", block(now, 5, 6)?, Some(1), 1),
+            ("Before", block(now, 0, 1)?, Some(1), -1),
+            ("Touching", block(now, 4, 5)?, Some(0), 0),
+            ("Overlapping", block(now, 3, 5)?, None, 0),
+            ("Containing", block(now, 0, 8)?, None, 0),
+        ];
+        for (name, other, gap, distance) in cases {
+            assert_eq!(base.gap_to(&other), gap.map(Duration::hours), "{}", name);
+            assert_eq!(
+                base.distance_to(&other),
+                Duration::hours(distance),
+                "{}",
+                name
+            );
+            assert_eq!(other.gap_to(&base), base.gap_to(&other), "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_intersect() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let base = block(now, 2, 6)?;
+        let cases = vec![
+            ("Overlapping", block(now, 4, 8)?, Some((4, 6))),
+            ("Containing", block(now, 0, 8)?, Some((2, 6))),
+            ("Contained", block(now, 3, 4)?, Some((3, 4))),
+            ("Touching", block(now, 6, 8)?, None),
+            ("Apart", block(now, 7, 8)?, None),
+        ];
+        for (name, other, expected) in cases {
+            let hours = |s: Slot| ((s.start() - now).num_hours(), (s.end() - now).num_hours());
+            assert_eq!(intersect(&base, &other).map(hours), expected, "{}", name);
+            assert_eq!(intersect(&other, &base).map(hours), expected, "{}", name);
+        }
+
+        let span = Span::new(dt(now, 0), dt(now, 4))?;
+        let utc = Block::new(
+            dt(now, 3).with_timezone(&Tz::UTC),
+            dt(now, 5).with_timezone(&Tz::UTC),
+        )?;
+        let clipped = utc.clip_to(&span).unwrap();
+        assert_eq!((clipped.start(), clipped.end()), (dt(now, 3), dt(now, 4)));
+        assert_eq!(clipped.start().timezone(), chrono_tz::Japan);
+        Ok(())
+    }
+
+    #[test]
+    fn test_period_error_context() {
+        let now = chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, 9, 0, 0)
+            .unwrap();
+        let cases = vec![
+            (
+                Block::new(now, now).unwrap_err(),
+                "Start time must be before end time. (start: 2024-01-01 09:00:00 JST, end: 2024-01-01 09:00:00 JST)",
+            ),
+            (
+                PeriodError::InvalidDuration(Duration::minutes(-1)),
+                "Duration must be positive. (duration: -PT60S)",
+            ),
+            (
+                from_epoch_millis(i64::MAX, &Tz::UTC).unwrap_err(),
+                "Time is out of range: 9223372036854775807 ms since the epoch",
+            ),
+        ];
+        for (err, expected) in cases {
+            assert_eq!(err.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_precision() {
+        let whole = chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, 9, 0, 0)
+            .unwrap();
+        let fine = whole + Duration::nanoseconds(1_500);
+        let cases = vec![
+            (Precision::Keep, fine, Some(fine)),
+            (Precision::Truncate, fine, Some(whole)),
+            (Precision::Reject, fine, None),
+            (Precision::Reject, whole, Some(whole)),
+        ];
+        for (precision, dt, expected) in cases {
+            assert_eq!(precision.apply(dt).ok(), expected, "{:?}", precision);
+        }
+        assert_eq!(
+            Precision::Reject.apply(fine),
+            Err(PeriodError::SubSecond(fine))
+        );
+    }
+
     #[test]
     fn test_invalid_block_creation() {
         let now = Utc::now().with_timezone(&chrono_tz::Japan);
@@ -186,4 +471,113 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn test_period_vec_aggregates() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let mut periods = vec![block(now, 3, 5)?, block(now, 0, 2)?, block(now, 0, 1)?];
+        assert_eq!(periods.total_duration(), Duration::hours(5));
+        assert_eq!(periods.min_start(), Some(now));
+        assert_eq!(periods.max_end(), Some(dt(now, 5)));
+
+        periods.sort_by_start();
+        let hours = periods
+            .iter()
+            .map(|p| ((p.start() - now).num_hours(), (p.end() - now).num_hours()))
+            .collect::<Vec<_>>();
+        assert_eq!(hours, vec![(0, 1), (0, 2), (3, 5)]);
+
+        let empty: Vec<Block> = Vec::new();
+        assert_eq!(empty.total_duration(), Duration::zero());
+        assert_eq!(empty.min_start(), None);
+        assert_eq!(empty.max_end(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_merge_overlapping() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        fn hours<P: Period>(now: DateTime<Tz>, periods: &[P]) -> Vec<(i64, i64)> {
+            periods
+                .iter()
+                .map(|p| ((p.start() - now).num_hours(), (p.end() - now).num_hours()))
+                .collect()
+        }
+
+        struct TestCase {
+            name: &'static str,
+            blocks: Vec<Block>,
+            expected: Vec<(i64, i64)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Overlapping, touching and apart",
+                blocks: vec![
+                    block(now, 5, 6)?,
+                    block(now, 0, 2)?,
+                    block(now, 1, 3)?,
+                    block(now, 3, 4)?,
+                ],
+                expected: vec![(0, 4), (5, 6)],
+            },
+            TestCase {
+                name: "Contained",
+                blocks: vec![block(now, 0, 8)?, block(now, 2, 3)?],
+                expected: vec![(0, 8)],
+            },
+            TestCase {
+                name: "Empty",
+                blocks: vec![],
+                expected: vec![],
+            },
+        ];
+
+        for case in cases {
+            let blocks: Vec<Block> = case.blocks.merge_overlapping();
+            let slots: Vec<Slot> = case.blocks.merge_overlapping();
+            assert_eq!(hours(now, &blocks), case.expected, "{}", case.name);
+            assert_eq!(hours(now, &slots), case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_covering() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let periods = vec![block(now, 0, 2)?, block(now, 3, 4)?, block(now, 6, 9)?];
+        let cases = vec![
+            ("Before all", -1, None),
+            ("At a start", 0, Some(0)),
+            ("Inside", 7, Some(2)),
+            ("At an end", 2, None),
+            ("Between", 5, None),
+            ("After all", 9, None),
+        ];
+        for (name, hour, expected) in cases {
+            assert_eq!(periods.find_covering(dt(now, hour)), expected, "{}", name);
+            assert_eq!(
+                periods.contains_instant(dt(now, hour)),
+                expected.is_some(),
+                "{}",
+                name
+            );
+        }
+        assert_eq!(Vec::<Block>::new().find_covering(now), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_period_epoch_millis() -> Result<(), PeriodError> {
+        let tz = chrono_tz::Japan;
+        let start = from_epoch_millis(1_704_067_200_123, &tz)?;
+        let end = from_epoch_millis(1_704_070_800_000, &tz)?;
+        assert_eq!(start.to_rfc3339(), "2024-01-01T09:00:00.123+09:00");
+        assert_eq!(
+            Block::new(start, end)?.epoch_millis(),
+            (1_704_067_200_123, 1_704_070_800_000)
+        );
+        assert!(from_epoch_millis(i64::MAX, &tz).is_err());
+        Ok(())
+    }
 }
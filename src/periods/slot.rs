@@ -1,20 +1,24 @@
-use std::fmt::Debug;
+use core::fmt::Debug;
 
-use chrono::DateTime;
+use chrono::{DateTime, Duration};
 use chrono_tz::Tz;
 
 use crate::impl_period;
 
 use super::{
     block::Block,
-    period::{Period, PeriodError},
+    period::{shift, Output, Period, PeriodError, Precision},
     span::Span,
 };
 
 /// This refers to available free time. The term ‘Slot’ will be standardized here.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "super::zoned::Times"))]
 pub struct Slot {
+    #[cfg_attr(feature = "serde", serde(with = "super::zoned"))]
     start: DateTime<Tz>,
+    #[cfg_attr(feature = "serde", serde(with = "super::zoned"))]
     end: DateTime<Tz>,
 }
 
@@ -24,27 +28,110 @@ impl Slot {
     /// constructor
     pub fn new(start: DateTime<Tz>, end: DateTime<Tz>) -> Result<Self, PeriodError> {
         if start >= end {
-            return Err(PeriodError::InvalidTime);
+            return Err(PeriodError::StartNotBeforeEnd { start, end });
         }
         Ok(Slot { start, end })
     }
 
+    /// constructor, which applies the precision policy to the times.
+    pub fn new_with_precision(
+        start: DateTime<Tz>,
+        end: DateTime<Tz>,
+        precision: Precision,
+    ) -> Result<Self, PeriodError> {
+        Slot::new(precision.apply(start)?, precision.apply(end)?)
+    }
+
     /// constructor
     pub fn create_from(target: &Span, block: &Block) -> Result<Self, PeriodError> {
         if target.start() > block.start() {
-            return Err(PeriodError::InvalidTime);
+            return Err(PeriodError::StartNotBeforeEnd {
+                start: target.start(),
+                end: block.start(),
+            });
         }
         Ok(Slot {
             start: target.start(),
             end: block.start(),
         })
     }
+
+    /// The slot with the start moved later by the duration. Fails if nothing remains.
+    pub fn trim_start(&self, duration: Duration) -> Result<Self, PeriodError> {
+        if duration < Duration::zero() {
+            return Err(PeriodError::InvalidDuration(duration));
+        }
+        Slot::new(shift(self.start, duration)?, self.end)
+    }
+
+    /// The slot with the end moved earlier by the duration. Fails if nothing remains.
+    pub fn trim_end(&self, duration: Duration) -> Result<Self, PeriodError> {
+        if duration < Duration::zero() {
+            return Err(PeriodError::InvalidDuration(duration));
+        }
+        Slot::new(self.start, shift(self.end, -duration)?)
+    }
+
+    /// The first part of the slot of at most the duration. A shorter slot is kept as is.
+    pub fn trim_to(&self, duration: Duration) -> Result<Self, PeriodError> {
+        if duration <= Duration::zero() {
+            return Err(PeriodError::InvalidDuration(duration));
+        }
+        Slot::new(self.start, self.end.min(shift(self.start, duration)?))
+    }
+
+    /// The slot extended by before at the start and after at the end, e.g. for setup and teardown time.
+    pub fn pad(&self, before: Duration, after: Duration) -> Result<Self, PeriodError> {
+        if before < Duration::zero() {
+            return Err(PeriodError::InvalidDuration(before));
+        }
+        if after < Duration::zero() {
+            return Err(PeriodError::InvalidDuration(after));
+        }
+        Slot::new(shift(self.start, -before)?, shift(self.end, after)?)
+    }
+
+    /// The padded slot clamped to the span. Fails if nothing of it is within the span.
+    pub fn pad_within(
+        &self,
+        before: Duration,
+        after: Duration,
+        span: &Span,
+    ) -> Result<Self, PeriodError> {
+        let padded = self.pad(before, after)?;
+        Slot::new(padded.start.max(span.start()), padded.end.min(span.end()))
+    }
+
+    /// Split the slot at an instant strictly inside it. None if the instant is not inside.
+    pub fn split_at(&self, at: DateTime<Tz>) -> Option<(Self, Self)> {
+        if at <= self.start || self.end <= at {
+            return None;
+        }
+        Some((
+            Slot {
+                start: self.start,
+                end: at,
+            },
+            Slot {
+                start: at,
+                end: self.end,
+            },
+        ))
+    }
+}
+
+/// A Slot can be received from find as is.
+impl Output for Slot {
+    fn create_from_slot(slot: Slot) -> Self {
+        slot
+    }
 }
 
 #[cfg(test)]
+#[allow(clippy::unit_arg)]
 mod tests {
     use super::*;
-    use chrono::{Duration, Utc};
+    use chrono::Utc;
 
     fn dt(now: DateTime<Tz>, hours: i64) -> DateTime<Tz> {
         now + Duration::hours(hours)
@@ -84,7 +171,10 @@ mod tests {
                 name: "Invalid Slot creation (Span starts after Block)",
                 span: span(now, 4, 8)?,
                 block: block(now, 1, 5)?,
-                expected: Err(PeriodError::InvalidTime),
+                expected: Err(PeriodError::StartNotBeforeEnd {
+                    start: now + Duration::hours(4),
+                    end: now + Duration::hours(1),
+                }),
             },
         ];
 
@@ -112,4 +202,101 @@ mod tests {
             }
         })
     }
+
+    #[test]
+    fn test_slot_trim() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let target = slot(now, 0, 8)?;
+        let hours = |s: Result<Slot, PeriodError>| {
+            s.ok()
+                .map(|s| ((s.start() - now).num_hours(), (s.end() - now).num_hours()))
+        };
+        let h = Duration::hours;
+        let cases = vec![
+            ("trim_start", hours(target.trim_start(h(3))), Some((3, 8))),
+            (
+                "trim_start zero",
+                hours(target.trim_start(h(0))),
+                Some((0, 8)),
+            ),
+            ("trim_start all", hours(target.trim_start(h(8))), None),
+            ("trim_start negative", hours(target.trim_start(h(-1))), None),
+            ("trim_end", hours(target.trim_end(h(3))), Some((0, 5))),
+            ("trim_end too much", hours(target.trim_end(h(9))), None),
+            ("trim_to", hours(target.trim_to(h(2))), Some((0, 2))),
+            ("trim_to longer", hours(target.trim_to(h(10))), Some((0, 8))),
+            ("trim_to zero", hours(target.trim_to(h(0))), None),
+        ];
+        for (name, actual, expected) in cases {
+            assert_eq!(actual, expected, "{}", name);
+        }
+
+        let max = Duration::max_value();
+        let out_of_range =
+            |s: Result<Slot, PeriodError>| matches!(s, Err(PeriodError::OutOfRange(_)));
+        assert!(out_of_range(target.trim_start(max)), "trim_start");
+        assert!(out_of_range(target.trim_end(max)), "trim_end");
+        assert!(out_of_range(target.trim_to(max)), "trim_to");
+        Ok(())
+    }
+
+    #[test]
+    fn test_slot_pad() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let target = slot(now, 2, 4)?;
+        let hours = |s: Result<Slot, PeriodError>| {
+            s.ok()
+                .map(|s| ((s.start() - now).num_hours(), (s.end() - now).num_hours()))
+        };
+        let h = Duration::hours;
+        let within = span(now, 1, 8)?;
+        let cases = vec![
+            ("pad", hours(target.pad(h(1), h(2))), Some((1, 6))),
+            ("pad nothing", hours(target.pad(h(0), h(0))), Some((2, 4))),
+            ("pad negative", hours(target.pad(h(-1), h(0))), None),
+            (
+                "pad out of range",
+                hours(target.pad(Duration::max_value(), h(0))),
+                None,
+            ),
+            (
+                "pad_within clamped",
+                hours(target.pad_within(h(2), h(5), &within)),
+                Some((1, 8)),
+            ),
+            (
+                "pad_within outside",
+                hours(target.pad_within(h(0), h(0), &span(now, 5, 8)?)),
+                None,
+            ),
+        ];
+        for (name, actual, expected) in cases {
+            assert_eq!(actual, expected, "{}", name);
+        }
+        assert!(matches!(
+            target.pad(h(0), Duration::max_value()),
+            Err(PeriodError::OutOfRange(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_slot_split_at() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let target = slot(now, 0, 8)?;
+        let cases = vec![
+            ("Inside", 3, Some(((0, 3), (3, 8)))),
+            ("At the start", 0, None),
+            ("At the end", 8, None),
+            ("Outside", 9, None),
+        ];
+        for (name, at, expected) in cases {
+            let hours = |s: &Slot| ((s.start() - now).num_hours(), (s.end() - now).num_hours());
+            let actual = target
+                .split_at(dt(now, at))
+                .map(|(a, b)| (hours(&a), hours(&b)));
+            assert_eq!(actual, expected, "{}", name);
+        }
+        Ok(())
+    }
 }
@@ -1,20 +1,37 @@
-use std::fmt::Debug;
+use alloc::{format, vec::Vec};
+use core::fmt::Debug;
 
-use chrono::DateTime;
+use chrono::{DateTime, Duration, NaiveDate, TimeZone, Weekday};
 use chrono_tz::Tz;
 
 use crate::impl_period;
 
 use super::{
     block::Block,
-    period::{Period, PeriodError},
+    period::{from_epoch_millis, Period, PeriodError, Precision},
     slot::Slot,
 };
 
+// The first instant of the local day. If midnight is skipped by DST, the day starts at the first valid time.
+pub(crate) fn start_of_day(date: NaiveDate, tz: &Tz) -> Result<DateTime<Tz>, PeriodError> {
+    let out_of_range = || PeriodError::OutOfRange(format!("start of {}", date));
+    let midnight = date.and_hms_opt(0, 0, 0).ok_or_else(out_of_range)?;
+    (0..=24)
+        .find_map(|hours| {
+            tz.from_local_datetime(&(midnight + Duration::hours(hours)))
+                .earliest()
+        })
+        .ok_or_else(out_of_range)
+}
+
 /// This is the period for searching for free time. The term ‘Span’ will be standardized here. Note that the Span is mutable.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "super::zoned::Times"))]
 pub struct Span {
+    #[cfg_attr(feature = "serde", serde(with = "super::zoned"))]
     start: DateTime<Tz>,
+    #[cfg_attr(feature = "serde", serde(with = "super::zoned"))]
     end: DateTime<Tz>,
 }
 
@@ -24,11 +41,69 @@ impl Span {
     /// constructor
     pub fn new(start: DateTime<Tz>, end: DateTime<Tz>) -> Result<Self, PeriodError> {
         if start >= end {
-            return Err(PeriodError::InvalidTime);
+            return Err(PeriodError::StartNotBeforeEnd { start, end });
         }
         Ok(Span { start, end })
     }
 
+    /// constructor, which applies the precision policy to the times.
+    pub fn new_with_precision(
+        start: DateTime<Tz>,
+        end: DateTime<Tz>,
+        precision: Precision,
+    ) -> Result<Self, PeriodError> {
+        Span::new(precision.apply(start)?, precision.apply(end)?)
+    }
+
+    /// constructor, which swaps the times if they are given in reverse. They must still differ.
+    pub fn new_ordered(a: DateTime<Tz>, b: DateTime<Tz>) -> Result<Self, PeriodError> {
+        Span::new(a.min(b), a.max(b))
+    }
+
+    /// constructor, which truncates the span to max_len from the start
+    /// (e.g. to refuse searching the next 50 years by accident).
+    pub fn new_clamped(
+        start: DateTime<Tz>,
+        end: DateTime<Tz>,
+        max_len: Duration,
+    ) -> Result<Self, PeriodError> {
+        if max_len <= Duration::zero() {
+            return Err(PeriodError::InvalidDuration(max_len));
+        }
+        let span = Span::new(start, end)?;
+        Ok(Span {
+            end: start
+                .checked_add_signed(max_len)
+                .map_or(span.end, |end| span.end.min(end)),
+            ..span
+        })
+    }
+
+    /// constructor, which fails with SpanTooLong if the span is longer than max_len.
+    pub fn new_limited(
+        start: DateTime<Tz>,
+        end: DateTime<Tz>,
+        max_len: Duration,
+    ) -> Result<Self, PeriodError> {
+        if max_len <= Duration::zero() {
+            return Err(PeriodError::InvalidDuration(max_len));
+        }
+        let span = Span::new(start, end)?;
+        let length = end - start;
+        if length > max_len {
+            return Err(PeriodError::SpanTooLong {
+                length,
+                max: max_len,
+            });
+        }
+        Ok(span)
+    }
+
+    /// constructor from Unix epoch milliseconds, in the timezone.
+    pub fn from_epoch_millis(start: i64, end: i64, tz: Tz) -> Result<Self, PeriodError> {
+        Span::new(from_epoch_millis(start, &tz)?, from_epoch_millis(end, &tz)?)
+    }
+
     /// Whether there is remaining time in the period.
     pub fn remain(&self) -> bool {
         self.start < self.end
@@ -44,6 +119,64 @@ impl Span {
         self.start = self.end
     }
 
+    /// constructor of the ISO 8601 week (from Monday 00:00 to the next Monday) in the timezone,
+    /// e.g. 2025 and 37 for 2025-W37.
+    pub fn iso_week(year: i32, week: u32, tz: Tz) -> Result<Self, PeriodError> {
+        let monday = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+            .ok_or_else(|| PeriodError::OutOfRange(format!("{}-W{:02}", year, week)))?;
+        Span::new(
+            start_of_day(monday, &tz)?,
+            start_of_day(monday + Duration::days(7), &tz)?,
+        )
+    }
+
+    /// Split the span at an instant strictly inside it. None if the instant is not inside.
+    pub fn split_at(&self, at: DateTime<Tz>) -> Option<(Self, Self)> {
+        if at <= self.start || self.end <= at {
+            return None;
+        }
+        Some((
+            Span {
+                start: self.start,
+                end: at,
+            },
+            Span {
+                start: at,
+                end: self.end,
+            },
+        ))
+    }
+
+    /// The local days of the timezone that the span touches, each with the part of the span on
+    /// that day (in the timezone). Days shortened or lengthened by DST keep their real length.
+    pub fn days_in(&self, tz: Tz) -> Result<Vec<(NaiveDate, Span)>, PeriodError> {
+        let start = self.start.with_timezone(&tz);
+        let end = self.end.with_timezone(&tz);
+        let mut days = Vec::new();
+        let mut date = start.date_naive();
+        let mut day_start = start;
+        while day_start < end {
+            let next = date
+                .succ_opt()
+                .ok_or_else(|| PeriodError::OutOfRange(format!("day after {}", date)))?;
+            let day_end = start_of_day(next, &tz)?.min(end);
+            days.push((date, Span::new(day_start, day_end)?));
+            date = next;
+            day_start = day_end;
+        }
+        Ok(days)
+    }
+
+    /// Indices of the periods entirely outside the span (touching it at an end is outside).
+    pub fn outside<P: Period>(&self, periods: &[P]) -> Vec<usize> {
+        periods
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| p.end() <= self.start || self.end <= p.start())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
     /// Convert the Span into a Slot.
     pub fn to_slot(&self) -> Result<Slot, PeriodError> {
         Slot::new(self.start(), self.end())
@@ -51,9 +184,10 @@ impl Span {
 }
 
 #[cfg(test)]
+#[allow(clippy::unit_arg)]
 mod tests {
     use super::*;
-    use chrono::{Duration, Utc};
+    use chrono::{Datelike, Utc};
 
     fn dt(now: DateTime<Tz>, hours: i64) -> DateTime<Tz> {
         now + Duration::hours(hours)
@@ -95,7 +229,10 @@ mod tests {
             TestCase {
                 name: "invalid",
                 span: span(now, 0, 0)?,
-                result: Err(PeriodError::InvalidTime),
+                result: Err(PeriodError::StartNotBeforeEnd {
+                    start: dt(now, 8),
+                    end: dt(now, 8),
+                }),
             },
         ];
 
@@ -195,4 +332,163 @@ mod tests {
             );
         })
     }
+
+    #[test]
+    fn test_span_split_at() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let (first, second) = span(now, 0, 8)?.split_at(dt(now, 5)).unwrap();
+        assert_eq!(first.to_string(), span(now, 0, 5)?.to_string());
+        assert_eq!(second.to_string(), span(now, 5, 8)?.to_string());
+        assert!(span(now, 0, 8)?.split_at(dt(now, 8)).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_days_in() -> Result<(), PeriodError> {
+        let tz = chrono_tz::America::New_York;
+        let ny = |d: u32, h: u32| tz.with_ymd_and_hms(2024, 3, d, h, 0, 0).unwrap();
+
+        struct TestCase {
+            name: &'static str,
+            span: Span,
+            tz: Tz,
+            expected: Vec<(u32, i64)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Partial first and last days, DST day is 23 hours",
+                span: Span::new(ny(9, 12), ny(11, 12))?,
+                tz,
+                expected: vec![(9, 12), (10, 23), (11, 12)],
+            },
+            TestCase {
+                name: "Exactly one day",
+                span: Span::new(ny(8, 0), ny(9, 0))?,
+                tz,
+                expected: vec![(8, 24)],
+            },
+            TestCase {
+                name: "Days of another timezone",
+                span: Span::new(ny(8, 0), ny(9, 0))?,
+                tz: chrono_tz::Asia::Tokyo,
+                expected: vec![(8, 10), (9, 14)],
+            },
+        ];
+
+        for case in cases {
+            let days = case.span.days_in(case.tz)?;
+            let actual = days
+                .iter()
+                .map(|(date, day)| (date.day(), (day.end() - day.start()).num_hours()))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+            assert!(days
+                .iter()
+                .all(|(_, day)| day.start().timezone() == case.tz));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_outside() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let span = Span::new(dt(now, 2), dt(now, 6))?;
+        let blocks = vec![
+            Block::new(dt(now, 0), dt(now, 1))?,
+            Block::new(dt(now, 1), dt(now, 3))?,
+            Block::new(dt(now, 0), dt(now, 2))?,
+            Block::new(dt(now, 3), dt(now, 4))?,
+            Block::new(dt(now, 6), dt(now, 7))?,
+            Block::new(dt(now, 0), dt(now, 8))?,
+        ];
+        assert_eq!(span.outside(&blocks), vec![0, 2, 4]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_iso_week() -> Result<(), PeriodError> {
+        let tz = chrono_tz::Asia::Tokyo;
+        let cases = vec![
+            (
+                "Week in the middle of the year",
+                2025,
+                37,
+                Some((9, 8, 9, 15)),
+            ),
+            ("Week 1 starting in December", 2025, 1, Some((12, 30, 1, 6))),
+            ("Week 53 of a long year", 2026, 53, Some((12, 28, 1, 4))),
+            ("No week 53", 2025, 53, None),
+            ("No week 0", 2025, 0, None),
+        ];
+        for (name, year, week, expected) in cases {
+            let actual = Span::iso_week(year, week, tz).ok().map(|s| {
+                assert_eq!(s.start().time(), chrono::NaiveTime::MIN, "{}", name);
+                (
+                    s.start().month(),
+                    s.start().day(),
+                    s.end().month(),
+                    s.end().day(),
+                )
+            });
+            assert_eq!(actual, expected, "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_span_length_limits() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let max = Duration::hours(24);
+
+        struct TestCase {
+            name: &'static str,
+            end: i64,
+            clamped: Option<i64>,
+            limited: Option<i64>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Shorter than max",
+                end: 8,
+                clamped: Some(8),
+                limited: Some(8),
+            },
+            TestCase {
+                name: "Exactly max",
+                end: 24,
+                clamped: Some(24),
+                limited: Some(24),
+            },
+            TestCase {
+                name: "Longer than max",
+                end: 24 * 365 * 50,
+                clamped: Some(24),
+                limited: None,
+            },
+        ];
+
+        for case in cases {
+            let hours = |s: Span| (s.end() - s.start()).num_hours();
+            let clamped = Span::new_clamped(now, dt(now, case.end), max).map(hours);
+            assert_eq!(clamped.ok(), case.clamped, "{}", case.name);
+            let limited = Span::new_limited(now, dt(now, case.end), max);
+            assert_eq!(limited.map(hours).ok(), case.limited, "{}", case.name);
+        }
+
+        let err = Span::new_limited(now, dt(now, 48), max).unwrap_err();
+        assert_eq!(
+            err,
+            PeriodError::SpanTooLong {
+                length: Duration::hours(48),
+                max,
+            }
+        );
+        let unbounded = Span::new_clamped(now, dt(now, 8), Duration::max_value())?;
+        assert_eq!(unbounded.end(), dt(now, 8));
+        assert!(Span::new_clamped(now, dt(now, 8), Duration::zero()).is_err());
+        assert!(Span::new_clamped(dt(now, 8), now, max).is_err());
+        Ok(())
+    }
 }
@@ -0,0 +1,64 @@
+use core::fmt::Debug;
+
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use super::{
+    block::Block,
+    period::{Input, Period, PeriodError},
+};
+
+/// An event carrying a payload (event id, calendar id, owner, ...), to be passed to find as an Input.
+#[derive(Debug, Clone)]
+pub struct Tagged<T> {
+    start: DateTime<Tz>,
+    end: DateTime<Tz>,
+    pub payload: T,
+}
+
+impl<T> Tagged<T> {
+    /// constructor
+    pub fn new(start: DateTime<Tz>, end: DateTime<Tz>, payload: T) -> Result<Self, PeriodError> {
+        if start >= end {
+            return Err(PeriodError::StartNotBeforeEnd { start, end });
+        }
+        Ok(Tagged {
+            start,
+            end,
+            payload,
+        })
+    }
+}
+
+impl<T> Period for Tagged<T> {
+    fn start(&self) -> DateTime<Tz> {
+        self.start
+    }
+
+    fn end(&self) -> DateTime<Tz> {
+        self.end
+    }
+}
+
+impl<T> Input for Tagged<T> {
+    fn to_block(&self) -> Result<Block, PeriodError> {
+        Block::new(self.start, self.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    #[test]
+    fn test_tagged_new() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let tagged = Tagged::new(now, now + Duration::hours(1), "standup")?;
+        let block = tagged.to_block()?;
+        assert_eq!((block.start(), block.end()), (tagged.start(), tagged.end()));
+        assert_eq!(tagged.payload, "standup");
+        assert!(Tagged::new(now, now, "empty").is_err());
+        Ok(())
+    }
+}
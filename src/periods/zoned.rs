@@ -0,0 +1,53 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+use super::{block::Block, period::PeriodError, slot::Slot, span::Span};
+
+pub fn serialize<S: Serializer>(dt: &DateTime<Tz>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{}[{}]", dt.to_rfc3339(), dt.timezone().name()))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Tz>, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    let (time, tz) = match s.strip_suffix(']').and_then(|s| s.split_once('[')) {
+        Some((time, name)) => (time, name.parse::<Tz>().map_err(D::Error::custom)?),
+        None => (s.as_str(), Tz::UTC),
+    };
+    DateTime::parse_from_rfc3339(time)
+        .map(|dt| dt.with_timezone(&tz))
+        .map_err(D::Error::custom)
+}
+
+// The times of a period as read, checked by the constructor of the period.
+#[derive(Deserialize)]
+pub struct Times {
+    #[serde(with = "self")]
+    start: DateTime<Tz>,
+    #[serde(with = "self")]
+    end: DateTime<Tz>,
+}
+
+impl TryFrom<Times> for Block {
+    type Error = PeriodError;
+
+    fn try_from(times: Times) -> Result<Self, PeriodError> {
+        Block::new(times.start, times.end)
+    }
+}
+
+impl TryFrom<Times> for Slot {
+    type Error = PeriodError;
+
+    fn try_from(times: Times) -> Result<Self, PeriodError> {
+        Slot::new(times.start, times.end)
+    }
+}
+
+impl TryFrom<Times> for Span {
+    type Error = PeriodError;
+
+    fn try_from(times: Times) -> Result<Self, PeriodError> {
+        Span::new(times.start, times.end)
+    }
+}
@@ -0,0 +1,257 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{timezone_utc, PyDateTime},
+};
+
+use crate::{
+    finder::find as find_slots,
+    periods::{
+        block::Block,
+        period::{Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+impl From<PeriodError> for PyErr {
+    fn from(err: PeriodError) -> Self {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+// Accepts a timezone name ("Asia/Tokyo") or a zoneinfo.ZoneInfo, whose str() is its key.
+fn to_tz(tz: Option<&Bound<'_, PyAny>>) -> PyResult<Tz> {
+    let Some(tz) = tz else {
+        return Ok(Tz::UTC);
+    };
+    let name = tz.str()?.to_string();
+    name.parse::<Tz>().map_err(PyValueError::new_err)
+}
+
+// Without an explicit timezone, the zoneinfo of the datetime is used (UTC for fixed offsets).
+fn infer_tz(dt: &Bound<'_, PyDateTime>, tz: Option<&Bound<'_, PyAny>>) -> PyResult<Tz> {
+    if tz.is_some() {
+        return to_tz(tz);
+    }
+    let tzinfo = dt.getattr("tzinfo")?;
+    Ok(to_tz(Some(&tzinfo)).unwrap_or(Tz::UTC))
+}
+
+// Converts an aware datetime (zoneinfo or fixed offset) into the timezone.
+fn to_datetime(dt: &Bound<'_, PyDateTime>, tz: &Tz) -> PyResult<DateTime<Tz>> {
+    if dt.getattr("tzinfo")?.is_none() {
+        return Err(PyValueError::new_err("naive datetime is not supported"));
+    }
+    let utc: DateTime<Utc> = dt
+        .call_method1("astimezone", (timezone_utc(dt.py()),))?
+        .extract()?;
+    Ok(utc.with_timezone(tz))
+}
+
+// Converts into an aware datetime whose tzinfo is a zoneinfo.ZoneInfo.
+fn to_py_datetime<'py>(py: Python<'py>, dt: DateTime<Tz>) -> PyResult<Bound<'py, PyAny>> {
+    let zone = py
+        .import("zoneinfo")?
+        .getattr("ZoneInfo")?
+        .call1((dt.timezone().name(),))?;
+    dt.fixed_offset()
+        .into_pyobject(py)?
+        .call_method1("astimezone", (zone,))
+}
+
+/// Period for searching for free time.
+#[pyclass(name = "Span", module = "chrono_slots")]
+#[derive(Debug, Clone)]
+pub struct PySpan {
+    inner: Span,
+}
+
+#[pymethods]
+impl PySpan {
+    #[new]
+    #[pyo3(signature = (start, end, tz=None))]
+    fn new(
+        start: &Bound<'_, PyDateTime>,
+        end: &Bound<'_, PyDateTime>,
+        tz: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let tz = infer_tz(start, tz)?;
+        let inner = Span::new(to_datetime(start, &tz)?, to_datetime(end, &tz)?)?;
+        Ok(PySpan { inner })
+    }
+
+    #[getter]
+    fn start<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py_datetime(py, self.inner.start())
+    }
+
+    #[getter]
+    fn end<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py_datetime(py, self.inner.end())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Span({})", self.inner.to_string())
+    }
+}
+
+/// Already scheduled event.
+#[pyclass(name = "Block", module = "chrono_slots")]
+#[derive(Debug, Clone)]
+pub struct PyBlock {
+    inner: Block,
+}
+
+#[pymethods]
+impl PyBlock {
+    #[new]
+    #[pyo3(signature = (start, end, tz=None))]
+    fn new(
+        start: &Bound<'_, PyDateTime>,
+        end: &Bound<'_, PyDateTime>,
+        tz: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let tz = infer_tz(start, tz)?;
+        let inner = Block::new(to_datetime(start, &tz)?, to_datetime(end, &tz)?)?;
+        Ok(PyBlock { inner })
+    }
+
+    #[getter]
+    fn start<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py_datetime(py, self.inner.start())
+    }
+
+    #[getter]
+    fn end<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py_datetime(py, self.inner.end())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Block({})", self.inner.to_string())
+    }
+}
+
+/// Available free time.
+#[pyclass(name = "Slot", module = "chrono_slots")]
+#[derive(Debug, Clone)]
+pub struct PySlot {
+    inner: Slot,
+}
+
+#[pymethods]
+impl PySlot {
+    #[getter]
+    fn start<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py_datetime(py, self.inner.start())
+    }
+
+    #[getter]
+    fn end<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        to_py_datetime(py, self.inner.end())
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Slot({})", self.inner.to_string())
+    }
+}
+
+/// Calculate available time slots of the span excluding the blocks.
+/// Slots are returned in the timezone of the span.
+#[pyfunction]
+fn find(span: PySpan, blocks: Vec<PyBlock>) -> PyResult<Vec<PySlot>> {
+    let tz = span.inner.start().timezone();
+    let blocks = blocks
+        .into_iter()
+        .map(|b| {
+            Block::new(
+                b.inner.start().with_timezone(&tz),
+                b.inner.end().with_timezone(&tz),
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let slots: Vec<Slot> = find_slots(span.inner, blocks)?;
+    Ok(slots.into_iter().map(|inner| PySlot { inner }).collect())
+}
+
+/// The `chrono_slots` Python module.
+#[pymodule]
+fn chrono_slots(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PySpan>()?;
+    m.add_class::<PyBlock>()?;
+    m.add_class::<PySlot>()?;
+    m.add_function(wrap_pyfunction!(find, m)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::PyDict;
+
+    #[test]
+    fn test_python_find() -> PyResult<()> {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = PyModule::new(py, "chrono_slots")?;
+            chrono_slots(&module)?;
+            let locals = PyDict::new(py);
+            locals.set_item("cs", module)?;
+            py.run(
+                c"
+from datetime import datetime
+from zoneinfo import ZoneInfo
+tokyo = ZoneInfo('Asia/Tokyo')
+span = cs.Span(datetime(2024, 1, 1, 9, tzinfo=tokyo), datetime(2024, 1, 1, 17, tzinfo=tokyo), tokyo)
+blocks = [
+    cs.Block(datetime(2024, 1, 1, 3, tzinfo=ZoneInfo('UTC')), datetime(2024, 1, 1, 4, tzinfo=ZoneInfo('UTC'))),
+    cs.Block(datetime(2024, 1, 1, 10, tzinfo=tokyo), datetime(2024, 1, 1, 11, tzinfo=tokyo)),
+]
+slots = [(s.start.hour, s.end.hour, str(s.start.tzinfo)) for s in cs.find(span, blocks)]
+",
+                None,
+                Some(&locals),
+            )?;
+            let slots: Vec<(u32, u32, String)> = locals.get_item("slots")?.unwrap().extract()?;
+            let tokyo = "Asia/Tokyo".to_string();
+            assert_eq!(
+                slots,
+                vec![
+                    (9, 10, tokyo.clone()),
+                    (11, 12, tokyo.clone()),
+                    (13, 17, tokyo.clone())
+                ]
+            );
+
+            let err = py.run(
+                c"cs.Span(datetime(2024, 1, 2, tzinfo=tokyo), datetime(2024, 1, 1, tzinfo=tokyo))",
+                None,
+                Some(&locals),
+            );
+            assert!(err.is_err(), "Invalid span should fail");
+
+            let err = py.run(
+                c"cs.Span(datetime(2024, 1, 1), datetime(2024, 1, 2))",
+                None,
+                Some(&locals),
+            );
+            assert!(err.is_err(), "Naive datetime should fail");
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_python_to_tz() -> PyResult<()> {
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            assert_eq!(to_tz(None)?, Tz::UTC);
+            let name = "Asia/Tokyo".into_pyobject(py)?.into_any();
+            assert_eq!(to_tz(Some(&name))?, chrono_tz::Asia::Tokyo);
+            let invalid = "Nowhere/City".into_pyobject(py)?.into_any();
+            assert!(to_tz(Some(&invalid)).is_err());
+            Ok(())
+        })
+    }
+}
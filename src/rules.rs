@@ -0,0 +1,16 @@
+pub use self::alternating::*;
+pub use self::blackout::*;
+pub use self::overrides::*;
+pub use self::recurrence::*;
+pub use self::work_week::*;
+pub mod alternating;
+pub mod blackout;
+pub mod overrides;
+pub mod recurrence;
+pub mod work_week;
+
+#[cfg(feature = "cron")]
+pub use self::cron::*;
+/// Recurring blocks from cron expressions.
+#[cfg(feature = "cron")]
+pub mod cron;
@@ -0,0 +1,187 @@
+use chrono::{DateTime, Datelike, Duration, NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+
+use crate::{
+    periods::{
+        block::Block,
+        period::{Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+    rules::work_week::{local_time, WorkWeek},
+};
+
+/// Working hours that rotate through several weeks, e.g. an A/B week cycle of shift workers.
+/// The first week applies to the week (from Monday) containing the anchor date.
+#[derive(Debug, Clone)]
+pub struct AlternatingWeeks {
+    anchor: NaiveDate,
+    weeks: Vec<WorkWeek>,
+}
+
+// Monday of the week containing the date.
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+impl AlternatingWeeks {
+    /// constructor. The weeks take turns in order from the week of the anchor date,
+    /// and must be in the same timezone.
+    pub fn new(anchor: NaiveDate, weeks: Vec<WorkWeek>) -> Result<Self, PeriodError> {
+        let Some(first) = weeks.first() else {
+            return Err(PeriodError::InvalidFormat(
+                "no weeks to alternate".to_string(),
+            ));
+        };
+        let tz = first.timezone();
+        if let Some(week) = weeks.iter().find(|w| w.timezone() != tz) {
+            return Err(PeriodError::InvalidFormat(format!(
+                "week in {} alternating with {}",
+                week.timezone().name(),
+                tz.name()
+            )));
+        }
+        Ok(AlternatingWeeks {
+            anchor: week_start(anchor),
+            weeks,
+        })
+    }
+
+    /// Timezone of the working hours.
+    pub fn timezone(&self) -> Tz {
+        self.weeks[0].timezone()
+    }
+
+    /// The week of the cycle that applies to the date.
+    pub fn week_of(&self, date: NaiveDate) -> &WorkWeek {
+        let weeks = (week_start(date) - self.anchor).num_days() / 7;
+        &self.weeks[weeks.rem_euclid(self.weeks.len() as i64) as usize]
+    }
+
+    /// Working hours within the span, sorted and merged where they touch across weeks.
+    pub fn windows(&self, span: &Span) -> Result<Vec<Slot>, PeriodError> {
+        let tz = self.timezone();
+        let first = week_start(span.start().with_timezone(&tz).date_naive());
+        let last = span.end().with_timezone(&tz).date_naive();
+        let mut windows: Vec<Slot> = Vec::new();
+        let mut monday = first;
+        while monday <= last {
+            let next = monday + Duration::days(7);
+            let start = local_time(&tz, monday.and_time(NaiveTime::MIN))?.max(span.start());
+            let end = local_time(&tz, next.and_time(NaiveTime::MIN))?.min(span.end());
+            if start < end {
+                for window in self.week_of(monday).windows(&Span::new(start, end)?)? {
+                    match windows.last_mut() {
+                        Some(prev) if prev.end() == window.start() => {
+                            *prev = Slot::new(prev.start(), window.end())?
+                        }
+                        _ => windows.push(window),
+                    }
+                }
+            }
+            monday = next;
+        }
+        let tz = span.start().timezone();
+        windows
+            .into_iter()
+            .map(|s| Slot::new(s.start().with_timezone(&tz), s.end().with_timezone(&tz)))
+            .collect()
+    }
+
+    /// Time of the span outside working hours, as blocks to pass to find with the other inputs.
+    pub fn off_hours(&self, span: &Span) -> Result<Vec<Block>, PeriodError> {
+        let mut blocks = Vec::new();
+        let mut cursor: DateTime<Tz> = span.start();
+        for window in self.windows(span)? {
+            if cursor < window.start() {
+                blocks.push(Block::new(cursor, window.start())?);
+            }
+            cursor = window.end();
+        }
+        if cursor < span.end() {
+            blocks.push(Block::new(cursor, span.end())?);
+        }
+        Ok(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn tokyo(m: u32, d: u32, h: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, m, d, h, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_alternating_weeks_windows() -> Result<(), PeriodError> {
+        let tz = chrono_tz::Asia::Tokyo;
+        // Week A from Monday 2024-01-08: mornings. Week B: nights into Monday.
+        let weeks = AlternatingWeeks::new(
+            NaiveDate::from_ymd_opt(2024, 1, 10).unwrap(),
+            vec![
+                WorkWeek::parse("Mon 09:00-12:00", tz)?,
+                WorkWeek::parse("Mon 06:00-09:00; Sun 22:00-24:00", tz)?,
+            ],
+        )?;
+
+        struct TestCase {
+            name: &'static str,
+            span: Span,
+            expected: Vec<(DateTime<Tz>, DateTime<Tz>)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Week A",
+                span: Span::new(tokyo(1, 8, 0), tokyo(1, 9, 0))?,
+                expected: vec![(tokyo(1, 8, 9), tokyo(1, 8, 12))],
+            },
+            TestCase {
+                name: "Week B, and week A again",
+                span: Span::new(tokyo(1, 15, 0), tokyo(1, 23, 0))?,
+                expected: vec![
+                    (tokyo(1, 15, 6), tokyo(1, 15, 9)),
+                    (tokyo(1, 21, 22), tokyo(1, 22, 0)),
+                    (tokyo(1, 22, 9), tokyo(1, 22, 12)),
+                ],
+            },
+            TestCase {
+                name: "Before the anchor",
+                span: Span::new(tokyo(1, 7, 0), tokyo(1, 8, 10))?,
+                expected: vec![
+                    (tokyo(1, 7, 22), tokyo(1, 8, 0)),
+                    (tokyo(1, 8, 9), tokyo(1, 8, 10)),
+                ],
+            },
+        ];
+
+        for case in cases {
+            let actual = weeks
+                .windows(&case.span)?
+                .iter()
+                .map(|s| (s.start(), s.end()))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+
+        let blocks = weeks.off_hours(&Span::new(tokyo(1, 8, 0), tokyo(1, 9, 0))?)?;
+        assert_eq!(blocks.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_alternating_weeks_new_error() -> Result<(), PeriodError> {
+        let anchor = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        assert!(AlternatingWeeks::new(anchor, vec![]).is_err());
+        let weeks = vec![
+            WorkWeek::parse("Mon 09:00-17:00", chrono_tz::Asia::Tokyo)?,
+            WorkWeek::parse("Mon 09:00-17:00", chrono_tz::UTC)?,
+        ];
+        assert!(AlternatingWeeks::new(anchor, weeks).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use chrono::{NaiveDate, NaiveTime};
+use chrono_tz::Tz;
+
+use crate::{
+    periods::{
+        block::Block,
+        period::{Period, PeriodError},
+        span::Span,
+    },
+    rules::work_week::local_time,
+};
+
+/// Dates that are entirely unavailable in a timezone, e.g. company shutdowns and vacations.
+/// Kept apart from the events, and applied as full-day blocks with working hours and other rules.
+#[derive(Debug, Clone)]
+pub struct Blackout {
+    tz: Tz,
+    // Inclusive ranges of dates.
+    ranges: Vec<(NaiveDate, NaiveDate)>,
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate, PeriodError> {
+    NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d")
+        .map_err(|_| PeriodError::InvalidFormat(s.to_string()))
+}
+
+impl Blackout {
+    /// constructor without dates
+    pub fn new(tz: Tz) -> Self {
+        Blackout {
+            tz,
+            ranges: Vec::new(),
+        }
+    }
+
+    /// Parse dates like "2024-08-13, 2024-12-28..2025-01-03" in the timezone.
+    /// Dates are separated by "," or new lines, and a range "first..last" includes both ends.
+    pub fn parse(dates: &str, tz: Tz) -> Result<Self, PeriodError> {
+        let mut blackout = Blackout::new(tz);
+        for part in dates.split([',', '\n']).map(str::trim) {
+            if part.is_empty() {
+                continue;
+            }
+            blackout = match part.split_once("..") {
+                Some((first, last)) => blackout.range(parse_date(first)?, parse_date(last)?)?,
+                None => blackout.date(parse_date(part)?),
+            };
+        }
+        Ok(blackout)
+    }
+
+    /// Add a date.
+    pub fn date(mut self, date: NaiveDate) -> Self {
+        self.ranges.push((date, date));
+        self
+    }
+
+    /// Add the dates from first to last, both included.
+    pub fn range(mut self, first: NaiveDate, last: NaiveDate) -> Result<Self, PeriodError> {
+        if last < first {
+            return Err(PeriodError::InvalidFormat(format!("{}..{}", first, last)));
+        }
+        self.ranges.push((first, last));
+        Ok(self)
+    }
+
+    /// Timezone of the dates.
+    pub fn timezone(&self) -> Tz {
+        self.tz
+    }
+
+    /// Whether the date is blacked out.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.ranges
+            .iter()
+            .any(|(first, last)| *first <= date && date <= *last)
+    }
+
+    /// The blacked out days within the span, from local midnight to midnight, as blocks to pass
+    /// to find with the other inputs. Consecutive days are merged into one block.
+    pub fn blocks(&self, span: &Span) -> Result<Vec<Block>, PeriodError> {
+        let mut ranges = self.ranges.clone();
+        ranges.sort();
+        let mut merged: Vec<(NaiveDate, NaiveDate)> = Vec::new();
+        for (first, last) in ranges {
+            match merged.last_mut() {
+                Some(prev) if first <= prev.1.succ_opt().unwrap_or(prev.1) => {
+                    prev.1 = prev.1.max(last)
+                }
+                _ => merged.push((first, last)),
+            }
+        }
+        let tz = span.start().timezone();
+        let mut blocks = Vec::new();
+        for (first, last) in merged {
+            let next = last
+                .succ_opt()
+                .ok_or_else(|| PeriodError::OutOfRange(format!("day after {}", last)))?;
+            let start = local_time(&self.tz, first.and_time(NaiveTime::MIN))?.with_timezone(&tz);
+            let end = local_time(&self.tz, next.and_time(NaiveTime::MIN))?.with_timezone(&tz);
+            let (start, end) = (start.max(span.start()), end.min(span.end()));
+            if start < end {
+                blocks.push(Block::new(start, end)?);
+            }
+        }
+        Ok(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{finder::find::find, periods::slot::Slot, rules::work_week::WorkWeek};
+    use chrono::{DateTime, TimeZone};
+
+    fn tokyo(m: u32, d: u32, h: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, m, d, h, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_blackout_blocks() -> Result<(), PeriodError> {
+        let tz = chrono_tz::Asia::Tokyo;
+        let blackout = Blackout::parse("2024-08-13, 2024-08-14..2024-08-15\n2024-08-20", tz)?;
+        let span = Span::new(tokyo(8, 14, 12), tokyo(8, 31, 0))?;
+        let actual = blackout
+            .blocks(&span)?
+            .iter()
+            .map(|b| (b.start(), b.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![
+                (tokyo(8, 14, 12), tokyo(8, 16, 0)),
+                (tokyo(8, 20, 0), tokyo(8, 21, 0))
+            ],
+            "Merged, and clipped to the span"
+        );
+        assert!(blackout.contains(NaiveDate::from_ymd_opt(2024, 8, 15).unwrap()));
+        assert!(!blackout.contains(NaiveDate::from_ymd_opt(2024, 8, 16).unwrap()));
+
+        // Days of another timezone: 2024-08-20 in UTC is 09:00 to 09:00 in Tokyo.
+        let utc = Blackout::new(Tz::UTC).date(NaiveDate::from_ymd_opt(2024, 8, 20).unwrap());
+        let blocks = utc.blocks(&span)?;
+        assert_eq!(blocks[0].start(), tokyo(8, 20, 9));
+        assert_eq!(blocks[0].end(), tokyo(8, 21, 9));
+
+        for dates in ["2024-08-15..2024-08-14", "2024/08/13", "tomorrow"] {
+            assert!(Blackout::parse(dates, tz).is_err(), "{}", dates);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_blackout_with_work_week() -> Result<(), PeriodError> {
+        let tz = chrono_tz::Asia::Tokyo;
+        let week = WorkWeek::parse("Mon-Fri 09:00-17:00", tz)?;
+        // 2024-08-12 is a Monday, and the 13th to 15th are a shutdown.
+        let blackout = Blackout::parse("2024-08-13..2024-08-15", tz)?;
+        let span = Span::new(tokyo(8, 12, 0), tokyo(8, 17, 0))?;
+        let mut blocks = week.off_hours(&span)?;
+        blocks.extend(blackout.blocks(&span)?);
+        let slots: Vec<Slot> = find(span, blocks)?;
+        let actual = slots
+            .iter()
+            .map(|s| (s.start(), s.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![
+                (tokyo(8, 12, 9), tokyo(8, 12, 17)),
+                (tokyo(8, 16, 9), tokyo(8, 16, 17))
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use std::str::FromStr;
+
+use chrono::Duration;
+use chrono_tz::Tz;
+use cron::Schedule;
+
+use crate::{
+    cancel::Cancellation,
+    periods::{
+        block::Block,
+        period::{Period, PeriodError},
+        span::Span,
+    },
+};
+
+/// Recurring busy periods given by a cron expression and a duration, e.g. maintenance windows.
+#[derive(Debug, Clone)]
+pub struct CronBlocks {
+    schedule: Schedule,
+    duration: Duration,
+    tz: Tz,
+    cancellation: Cancellation,
+}
+
+impl CronBlocks {
+    /// constructor. The expression is evaluated in the timezone. Both the standard 5 fields
+    /// ("0 3 * * Sun") and the 6 or 7 fields with seconds (and years) of the cron crate are accepted.
+    pub fn new(expression: &str, duration: Duration, tz: Tz) -> Result<Self, PeriodError> {
+        if duration <= Duration::zero() {
+            return Err(PeriodError::InvalidDuration(duration));
+        }
+        let expression = expression.trim();
+        let normalized = match expression.split_whitespace().count() {
+            5 => format!("0 {}", expression),
+            _ => expression.to_string(),
+        };
+        let schedule = Schedule::from_str(&normalized)
+            .map_err(|err| PeriodError::InvalidFormat(format!("{}: {}", expression, err)))?;
+        Ok(CronBlocks {
+            schedule,
+            duration,
+            tz,
+            cancellation: Cancellation::default(),
+        })
+    }
+
+    /// Abandon the expansion of blocks with Cancelled or TimedOut, e.g. for a span of years
+    /// with an occurrence every minute.
+    pub fn cancellation(mut self, cancellation: Cancellation) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// The occurrences overlapping the span, as blocks to pass to find with the other inputs.
+    /// An occurrence starting before the span is included while it lasts into the span.
+    pub fn blocks(&self, span: &Span) -> Result<Vec<Block>, PeriodError> {
+        let from = (span.start() - self.duration).with_timezone(&self.tz);
+        let tz = span.start().timezone();
+        self.schedule
+            .after(&from)
+            .take_while(|start| *start < span.end())
+            .map(|start| {
+                self.cancellation.check()?;
+                let start = start.with_timezone(&tz);
+                Block::new(start, start + self.duration)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cancel::CancelToken, finder::find::find, periods::slot::Slot};
+    use chrono::{DateTime, TimeZone};
+
+    fn tokyo(d: u32, h: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, 1, d, h, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cron_blocks() -> Result<(), PeriodError> {
+        let tz = chrono_tz::Asia::Tokyo;
+
+        struct TestCase {
+            name: &'static str,
+            expression: &'static str,
+            duration: Duration,
+            span: Span,
+            expected: Vec<(DateTime<Tz>, DateTime<Tz>)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Daily at 3:00 for 2 hours",
+                expression: "0 3 * * *",
+                duration: Duration::hours(2),
+                span: Span::new(tokyo(1, 4), tokyo(3, 4))?,
+                expected: vec![
+                    (tokyo(1, 3), tokyo(1, 5)),
+                    (tokyo(2, 3), tokyo(2, 5)),
+                    (tokyo(3, 3), tokyo(3, 5)),
+                ],
+            },
+            TestCase {
+                name: "With seconds, on Sundays, including one lasting into the span",
+                expression: "0 0 22 * * Sun",
+                duration: Duration::hours(4),
+                span: Span::new(tokyo(1, 0), tokyo(15, 0))?,
+                expected: vec![
+                    (
+                        tz.with_ymd_and_hms(2023, 12, 31, 22, 0, 0).unwrap(),
+                        tokyo(1, 2),
+                    ),
+                    (tokyo(7, 22), tokyo(8, 2)),
+                    (tokyo(14, 22), tokyo(15, 2)),
+                ],
+            },
+        ];
+
+        for case in cases {
+            let blocks = CronBlocks::new(case.expression, case.duration, tz)?.blocks(&case.span)?;
+            let actual = blocks
+                .iter()
+                .map(|b| (b.start(), b.end()))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+
+        let span = Span::new(tokyo(1, 0), tokyo(1, 12))?;
+        let maintenance = CronBlocks::new("30 1 * * *", Duration::hours(1), tz)?;
+        let slots: Vec<Slot> = find(span.clone(), maintenance.blocks(&span)?)?;
+        assert_eq!(slots.len(), 2);
+
+        assert!(CronBlocks::new("not cron", Duration::hours(1), tz).is_err());
+        assert!(CronBlocks::new("0 3 * * *", Duration::zero(), tz).is_err());
+
+        let token = CancelToken::new();
+        token.cancel();
+        let every_minute = CronBlocks::new("* * * * *", Duration::minutes(1), tz)?
+            .cancellation(Cancellation::new().token(token));
+        assert_eq!(
+            every_minute.blocks(&span).err(),
+            Some(PeriodError::Cancelled)
+        );
+        Ok(())
+    }
+}
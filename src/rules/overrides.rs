@@ -0,0 +1,123 @@
+use crate::periods::{
+    block::Block,
+    period::{Period, PeriodError},
+    slot::Slot,
+};
+
+/// One-off windows of availability, e.g. "exceptionally available this Saturday 10-12".
+///
+/// Precedence: overrides reopen time closed by rules (working hours, blackout dates, recurrences),
+/// but not events, which stay busy. Reopen the blocks of the rules, then add the events:
+/// ```text
+/// let mut blocks = overrides.reopen(week.off_hours(&span)?)?;
+/// blocks.extend(events);
+/// ```
+/// A BookingPolicy still applies to the slots found in reopened time.
+#[derive(Debug, Clone, Default)]
+pub struct Overrides {
+    windows: Vec<Slot>,
+}
+
+impl Overrides {
+    /// constructor without windows
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a window of availability.
+    pub fn open(mut self, window: Slot) -> Self {
+        self.windows.push(window);
+        self
+    }
+
+    /// The windows, sorted and merged where they overlap or touch.
+    pub fn windows(&self) -> Result<Vec<Slot>, PeriodError> {
+        let mut windows = self.windows.clone();
+        windows.sort_by_key(|w| w.start());
+        let mut merged: Vec<Slot> = Vec::new();
+        for window in windows {
+            match merged.last_mut() {
+                Some(last) if window.start() <= last.end() => {
+                    if last.end() < window.end() {
+                        *last = Slot::new(last.start(), window.end())?;
+                    }
+                }
+                _ => merged.push(window),
+            }
+        }
+        Ok(merged)
+    }
+
+    /// The closed blocks of rules with the windows cut out of them.
+    pub fn reopen(&self, closed: Vec<Block>) -> Result<Vec<Block>, PeriodError> {
+        let windows = self.windows()?;
+        let mut blocks = Vec::new();
+        for block in closed {
+            let mut cursor = block.start();
+            for window in windows
+                .iter()
+                .filter(|w| w.start() < block.end() && block.start() < w.end())
+            {
+                if cursor < window.start() {
+                    blocks.push(Block::new(cursor, window.start())?);
+                }
+                cursor = cursor.max(window.end());
+            }
+            if cursor < block.end() {
+                blocks.push(Block::new(cursor, block.end())?);
+            }
+        }
+        Ok(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        finder::find::find,
+        periods::span::Span,
+        rules::{blackout::Blackout, work_week::WorkWeek},
+    };
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+
+    fn tokyo(m: u32, d: u32, h: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, m, d, h, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_overrides_reopen() -> Result<(), PeriodError> {
+        let tz = chrono_tz::Asia::Tokyo;
+        let week = WorkWeek::parse("Mon-Fri 09:00-17:00", tz)?;
+        let blackout = Blackout::parse("2024-08-16", tz)?;
+        // 2024-08-16 is a blacked out Friday, and the 17th a Saturday.
+        let span = Span::new(tokyo(8, 16, 0), tokyo(8, 18, 0))?;
+        let overrides = Overrides::new()
+            .open(Slot::new(tokyo(8, 17, 10), tokyo(8, 17, 12))?)
+            .open(Slot::new(tokyo(8, 17, 11), tokyo(8, 17, 13))?)
+            .open(Slot::new(tokyo(8, 16, 14), tokyo(8, 16, 16))?);
+        assert_eq!(overrides.windows()?.len(), 2, "Overlapping windows merged");
+
+        let mut closed = week.off_hours(&span)?;
+        closed.extend(blackout.blocks(&span)?);
+        let mut blocks = overrides.reopen(closed)?;
+        // An event in the reopened time stays busy.
+        blocks.push(Block::new(tokyo(8, 17, 12), tokyo(8, 17, 13))?);
+        let slots: Vec<Slot> = find(span, blocks)?;
+        let actual = slots
+            .iter()
+            .map(|s| (s.start(), s.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![
+                (tokyo(8, 16, 14), tokyo(8, 16, 16)),
+                (tokyo(8, 17, 10), tokyo(8, 17, 12))
+            ]
+        );
+        Ok(())
+    }
+}
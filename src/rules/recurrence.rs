@@ -0,0 +1,311 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveTime, Weekday};
+use chrono_tz::Tz;
+
+use crate::{
+    periods::{
+        block::Block,
+        period::{Period, PeriodError},
+        span::Span,
+    },
+    rules::work_week::local_time,
+};
+
+/// What a monthly recurrence does in months without the day, e.g. the 31st in April.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShortMonth {
+    /// Occur on the last day of the month instead.
+    #[default]
+    Clamp,
+    /// Do not occur in the month.
+    Skip,
+}
+
+#[derive(Debug, Clone)]
+enum Pattern {
+    EveryWeeks { anchor: NaiveDate, weeks: u32 },
+    MonthDay { day: u32, short: ShortMonth },
+    NthWeekday { nth: i32, weekday: Weekday },
+}
+
+/// Recurring busy periods at a local time, e.g. every other Monday 10:00, the 25th of each month,
+/// or the second Tuesday of each month.
+/// A simpler alternative to cron expressions for common patterns.
+#[derive(Debug, Clone)]
+pub struct Recurrence {
+    pattern: Pattern,
+    time: NaiveTime,
+    duration: Duration,
+    tz: Tz,
+}
+
+// Number of days in the month of the date.
+fn days_in_month(date: NaiveDate) -> u32 {
+    let (year, month) = match date.month() {
+        12 => (date.year() + 1, 1),
+        month => (date.year(), month + 1),
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|d| d.pred_opt())
+        .map_or(31, |d| d.day())
+}
+
+impl Recurrence {
+    /// constructor of an occurrence every N weeks, on the weekday of the anchor date and the weeks
+    /// counted from it. Every 2 weeks is biweekly.
+    pub fn every_weeks(
+        weeks: u32,
+        anchor: NaiveDate,
+        time: NaiveTime,
+        duration: Duration,
+        tz: Tz,
+    ) -> Result<Self, PeriodError> {
+        if weeks == 0 {
+            return Err(PeriodError::InvalidFormat("every 0 weeks".to_string()));
+        }
+        Recurrence::new(Pattern::EveryWeeks { anchor, weeks }, time, duration, tz)
+    }
+
+    /// constructor of an occurrence on the day (1-31) of every month.
+    pub fn monthly(
+        day: u32,
+        short: ShortMonth,
+        time: NaiveTime,
+        duration: Duration,
+        tz: Tz,
+    ) -> Result<Self, PeriodError> {
+        if !(1..=31).contains(&day) {
+            return Err(PeriodError::InvalidFormat(format!("day {} of month", day)));
+        }
+        Recurrence::new(Pattern::MonthDay { day, short }, time, duration, tz)
+    }
+
+    /// constructor of an occurrence on the nth weekday of every month, e.g. 2 and Tue for the second
+    /// Tuesday. Negative counts from the end of the month, so -1 is the last. Months without the nth
+    /// weekday (the fifth in most months) are skipped.
+    pub fn nth_weekday(
+        nth: i32,
+        weekday: Weekday,
+        time: NaiveTime,
+        duration: Duration,
+        tz: Tz,
+    ) -> Result<Self, PeriodError> {
+        if nth == 0 || !(-5..=5).contains(&nth) {
+            return Err(PeriodError::InvalidFormat(format!(
+                "{} {} of month",
+                nth, weekday
+            )));
+        }
+        Recurrence::new(Pattern::NthWeekday { nth, weekday }, time, duration, tz)
+    }
+
+    fn new(
+        pattern: Pattern,
+        time: NaiveTime,
+        duration: Duration,
+        tz: Tz,
+    ) -> Result<Self, PeriodError> {
+        if duration <= Duration::zero() {
+            return Err(PeriodError::InvalidDuration(duration));
+        }
+        Ok(Recurrence {
+            pattern,
+            time,
+            duration,
+            tz,
+        })
+    }
+
+    // Whether an occurrence starts on the local date.
+    fn occurs_on(&self, date: NaiveDate) -> bool {
+        match self.pattern {
+            Pattern::EveryWeeks { anchor, weeks } => {
+                (date - anchor).num_days().rem_euclid(7 * weeks as i64) == 0
+            }
+            Pattern::MonthDay { day, short } => {
+                let last = days_in_month(date);
+                match short {
+                    ShortMonth::Clamp => date.day() == day.min(last),
+                    ShortMonth::Skip => date.day() == day,
+                }
+            }
+            Pattern::NthWeekday { nth, weekday } => {
+                let nth = match nth {
+                    n if n > 0 => (date.day() as i32 - 1) / 7 + 1 == n,
+                    n => (days_in_month(date) as i32 - date.day() as i32) / 7 + 1 == -n,
+                };
+                date.weekday() == weekday && nth
+            }
+        }
+    }
+
+    /// The occurrences overlapping the span, as blocks to pass to find with the other inputs.
+    /// The time is local, so occurrences keep their wall-clock time across DST changes.
+    pub fn blocks(&self, span: &Span) -> Result<Vec<Block>, PeriodError> {
+        let first = (span.start() - self.duration)
+            .with_timezone(&self.tz)
+            .date_naive();
+        let last = span.end().with_timezone(&self.tz).date_naive();
+        let tz = span.start().timezone();
+        let mut blocks = Vec::new();
+        for date in first.iter_days().take_while(|d| *d <= last) {
+            if !self.occurs_on(date) {
+                continue;
+            }
+            let start = local_time(&self.tz, date.and_time(self.time))?.with_timezone(&tz);
+            let end = start + self.duration;
+            if start < span.end() && span.start() < end {
+                blocks.push(Block::new(start, end)?);
+            }
+        }
+        Ok(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, TimeZone};
+
+    fn tokyo(m: u32, d: u32, h: u32) -> DateTime<Tz> {
+        chrono_tz::Asia::Tokyo
+            .with_ymd_and_hms(2024, m, d, h, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_recurrence_blocks() -> Result<(), PeriodError> {
+        let tz = chrono_tz::Asia::Tokyo;
+        let ten = NaiveTime::from_hms_opt(10, 0, 0).unwrap();
+        let hour = Duration::hours(1);
+
+        struct TestCase {
+            name: &'static str,
+            recurrence: Recurrence,
+            span: Span,
+            expected: Vec<DateTime<Tz>>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Biweekly from the anchor, also before it",
+                // 2024-01-15 is a Monday.
+                recurrence: Recurrence::every_weeks(
+                    2,
+                    NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+                    ten,
+                    hour,
+                    tz,
+                )?,
+                span: Span::new(tokyo(1, 1, 0), tokyo(2, 1, 0))?,
+                expected: vec![tokyo(1, 1, 10), tokyo(1, 15, 10), tokyo(1, 29, 10)],
+            },
+            TestCase {
+                name: "31st clamped to short months",
+                recurrence: Recurrence::monthly(31, ShortMonth::Clamp, ten, hour, tz)?,
+                span: Span::new(tokyo(1, 1, 0), tokyo(5, 1, 0))?,
+                expected: vec![
+                    tokyo(1, 31, 10),
+                    tokyo(2, 29, 10),
+                    tokyo(3, 31, 10),
+                    tokyo(4, 30, 10),
+                ],
+            },
+            TestCase {
+                name: "31st skipped in short months",
+                recurrence: Recurrence::monthly(31, ShortMonth::Skip, ten, hour, tz)?,
+                span: Span::new(tokyo(1, 1, 0), tokyo(5, 1, 0))?,
+                expected: vec![tokyo(1, 31, 10), tokyo(3, 31, 10)],
+            },
+            TestCase {
+                name: "Occurrence overlapping the start of the span",
+                recurrence: Recurrence::monthly(1, ShortMonth::Clamp, ten, hour, tz)?,
+                span: Span::new(tokyo(2, 1, 10), tokyo(2, 2, 0))?,
+                expected: vec![tokyo(2, 1, 10)],
+            },
+            TestCase {
+                name: "Second Tuesday at the local time across the DST change",
+                recurrence: Recurrence::nth_weekday(
+                    2,
+                    Weekday::Tue,
+                    NaiveTime::from_hms_opt(14, 0, 0).unwrap(),
+                    hour,
+                    chrono_tz::America::New_York,
+                )?,
+                span: Span::new(tokyo(2, 1, 0), tokyo(4, 1, 0))?,
+                // 14:00 EST is 04:00 in Tokyo, 14:00 EDT is 03:00.
+                expected: vec![tokyo(2, 14, 4), tokyo(3, 13, 3)],
+            },
+            TestCase {
+                name: "Last Friday",
+                recurrence: Recurrence::nth_weekday(-1, Weekday::Fri, ten, hour, tz)?,
+                span: Span::new(tokyo(1, 1, 0), tokyo(4, 1, 0))?,
+                expected: vec![tokyo(1, 26, 10), tokyo(2, 23, 10), tokyo(3, 29, 10)],
+            },
+            TestCase {
+                name: "Fifth Thursday only in months with one",
+                recurrence: Recurrence::nth_weekday(5, Weekday::Thu, ten, hour, tz)?,
+                span: Span::new(tokyo(1, 1, 0), tokyo(4, 1, 0))?,
+                expected: vec![tokyo(2, 29, 10)],
+            },
+        ];
+
+        for case in cases {
+            let actual = case
+                .recurrence
+                .blocks(&case.span)?
+                .iter()
+                .map(|b| b.start())
+                .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_recurrence_nth_weekday() -> Result<(), PeriodError> {
+        let ten = NaiveTime::from_hms_opt(10, 0, 0).unwrap();
+        // Wednesdays of January 2024 are 3, 10, 17, 24, 31; of February 7, 14, 21, 28.
+        let cases = vec![
+            (1, vec![(1, 3), (2, 7)]),
+            (2, vec![(1, 10), (2, 14)]),
+            (4, vec![(1, 24), (2, 28)]),
+            (5, vec![(1, 31)]),
+            (-1, vec![(1, 31), (2, 28)]),
+            (-2, vec![(1, 24), (2, 21)]),
+            (-4, vec![(1, 10), (2, 7)]),
+            (-5, vec![(1, 3)]),
+        ];
+        for (nth, expected) in cases {
+            let recurrence = Recurrence::nth_weekday(
+                nth,
+                Weekday::Wed,
+                ten,
+                Duration::hours(1),
+                chrono_tz::Asia::Tokyo,
+            )?;
+            let actual = NaiveDate::from_ymd_opt(2024, 1, 1)
+                .unwrap()
+                .iter_days()
+                .take_while(|d| d.month() <= 2)
+                .filter(|d| recurrence.occurs_on(*d))
+                .map(|d| (d.month(), d.day()))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{}", nth);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_recurrence_error() {
+        let tz = chrono_tz::Asia::Tokyo;
+        let ten = NaiveTime::from_hms_opt(10, 0, 0).unwrap();
+        let anchor = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let hour = Duration::hours(1);
+        assert!(Recurrence::every_weeks(0, anchor, ten, hour, tz).is_err());
+        assert!(Recurrence::monthly(0, ShortMonth::Clamp, ten, hour, tz).is_err());
+        assert!(Recurrence::monthly(32, ShortMonth::Clamp, ten, hour, tz).is_err());
+        assert!(Recurrence::monthly(1, ShortMonth::Clamp, ten, Duration::zero(), tz).is_err());
+        assert!(Recurrence::nth_weekday(0, Weekday::Tue, ten, hour, tz).is_err());
+        assert!(Recurrence::nth_weekday(6, Weekday::Tue, ten, hour, tz).is_err());
+    }
+}
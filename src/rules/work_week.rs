@@ -0,0 +1,293 @@
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Weekday,
+};
+use chrono_tz::Tz;
+
+use crate::periods::{
+    block::Block,
+    period::{Period, PeriodError},
+    slot::Slot,
+    span::Span,
+};
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Weekly working hours in a timezone, e.g. Mon-Fri 09:00-17:00.
+#[derive(Debug, Clone)]
+pub struct WorkWeek {
+    tz: Tz,
+    // Working hours of each weekday, from Monday. An end of None is the end of the day.
+    hours: [Vec<(NaiveTime, Option<NaiveTime>)>; 7],
+}
+
+// The local time in the timezone. A time skipped by DST is moved forward by an hour.
+pub(crate) fn local_time(tz: &Tz, naive: NaiveDateTime) -> Result<DateTime<Tz>, PeriodError> {
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .or_else(|| {
+            tz.from_local_datetime(&(naive + Duration::hours(1)))
+                .earliest()
+        })
+        .ok_or_else(|| PeriodError::OutOfRange(format!("{} in {}", naive, tz.name())))
+}
+
+fn parse_weekday(s: &str) -> Result<Weekday, PeriodError> {
+    s.parse::<Weekday>()
+        .map_err(|_| PeriodError::InvalidFormat(s.to_string()))
+}
+
+fn parse_days(s: &str) -> Result<Vec<Weekday>, PeriodError> {
+    let mut days = Vec::new();
+    for part in s.split(',') {
+        let part = part.trim();
+        if part.eq_ignore_ascii_case("daily") {
+            days.extend(WEEKDAYS);
+            continue;
+        }
+        match part.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (parse_weekday(from)?, parse_weekday(to)?);
+                let mut day = from;
+                days.push(day);
+                while day != to {
+                    day = day.succ();
+                    days.push(day);
+                }
+            }
+            None => days.push(parse_weekday(part)?),
+        }
+    }
+    Ok(days)
+}
+
+fn parse_hours(s: &str) -> Result<(NaiveTime, Option<NaiveTime>), PeriodError> {
+    let invalid = || PeriodError::InvalidFormat(s.to_string());
+    let (start, end) = s.split_once('-').ok_or_else(invalid)?;
+    let start = NaiveTime::parse_from_str(start.trim(), "%H:%M").map_err(|_| invalid())?;
+    let end = match end.trim() {
+        "24:00" => None,
+        end => Some(NaiveTime::parse_from_str(end, "%H:%M").map_err(|_| invalid())?),
+    };
+    if end.is_some_and(|end| end <= start) {
+        return Err(invalid());
+    }
+    Ok((start, end))
+}
+
+impl WorkWeek {
+    /// constructor of a week without working hours
+    pub fn new(tz: Tz) -> Self {
+        WorkWeek {
+            tz,
+            hours: Default::default(),
+        }
+    }
+
+    /// Parse rules like "Mon-Fri 09:00-17:00; Sat 10:00-13:00" in the timezone.
+    /// Rules are separated by ";" or new lines. Days are a weekday, a range (Mon-Fri), a list (Mon,Wed),
+    /// or "Daily". Several hours may follow the days (Mon-Fri 09:00-12:00 13:00-17:00), and 24:00 ends the day.
+    pub fn parse(rules: &str, tz: Tz) -> Result<Self, PeriodError> {
+        let mut week = WorkWeek::new(tz);
+        for rule in rules.split([';', '\n']).map(str::trim) {
+            if rule.is_empty() {
+                continue;
+            }
+            let mut parts = rule.split_whitespace();
+            let days = parse_days(parts.next().unwrap_or_default())?;
+            let hours = parts
+                .flat_map(|p| p.split(','))
+                .filter(|p| !p.is_empty())
+                .map(parse_hours)
+                .collect::<Result<Vec<_>, _>>()?;
+            if hours.is_empty() {
+                return Err(PeriodError::InvalidFormat(rule.to_string()));
+            }
+            for day in days {
+                week.hours[day.num_days_from_monday() as usize].extend(hours.iter().copied());
+            }
+        }
+        Ok(week)
+    }
+
+    /// Add working hours to the weekday. An end of None is the end of the day.
+    pub fn add(
+        mut self,
+        day: Weekday,
+        start: NaiveTime,
+        end: Option<NaiveTime>,
+    ) -> Result<Self, PeriodError> {
+        if end.is_some_and(|end| end <= start) {
+            return Err(PeriodError::InvalidFormat(format!(
+                "{} {}-{:?}",
+                day, start, end
+            )));
+        }
+        self.hours[day.num_days_from_monday() as usize].push((start, end));
+        Ok(self)
+    }
+
+    /// Timezone of the working hours.
+    pub fn timezone(&self) -> Tz {
+        self.tz
+    }
+
+    /// Working hours within the span, sorted and merged where they overlap or touch.
+    pub fn windows(&self, span: &Span) -> Result<Vec<Slot>, PeriodError> {
+        let first = span.start().with_timezone(&self.tz).date_naive();
+        let last = span.end().with_timezone(&self.tz).date_naive();
+        let mut windows: Vec<(DateTime<Tz>, DateTime<Tz>)> = Vec::new();
+        for date in first.iter_days().take_while(|d| *d <= last) {
+            let next: NaiveDate = date
+                .succ_opt()
+                .ok_or_else(|| PeriodError::OutOfRange(format!("day after {}", date)))?;
+            for (start, end) in &self.hours[date.weekday().num_days_from_monday() as usize] {
+                let start = local_time(&self.tz, date.and_time(*start))?;
+                let end = match end {
+                    Some(end) => local_time(&self.tz, date.and_time(*end))?,
+                    None => local_time(&self.tz, next.and_time(NaiveTime::MIN))?,
+                };
+                let start = start.max(span.start().with_timezone(&self.tz));
+                let end = end.min(span.end().with_timezone(&self.tz));
+                if start < end {
+                    windows.push((start, end));
+                }
+            }
+        }
+        windows.sort();
+        let mut merged: Vec<(DateTime<Tz>, DateTime<Tz>)> = Vec::new();
+        for (start, end) in windows {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        let tz = span.start().timezone();
+        merged
+            .into_iter()
+            .map(|(start, end)| Slot::new(start.with_timezone(&tz), end.with_timezone(&tz)))
+            .collect()
+    }
+
+    /// Time of the span outside working hours, as blocks to pass to find with the other inputs.
+    pub fn off_hours(&self, span: &Span) -> Result<Vec<Block>, PeriodError> {
+        let mut blocks = Vec::new();
+        let mut cursor = span.start();
+        for window in self.windows(span)? {
+            if cursor < window.start() {
+                blocks.push(Block::new(cursor, window.start())?);
+            }
+            cursor = window.end();
+        }
+        if cursor < span.end() {
+            blocks.push(Block::new(cursor, span.end())?);
+        }
+        Ok(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::finder::find::find;
+
+    fn ny(m: u32, d: u32, h: u32, min: u32) -> DateTime<Tz> {
+        chrono_tz::America::New_York
+            .with_ymd_and_hms(2024, m, d, h, min, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_work_week_parse() {
+        let tz = chrono_tz::America::New_York;
+        let cases = vec![
+            ("Mon-Fri 09:00-17:00; Sat 10:00-13:00", true),
+            (
+                "Mon,Wed 09:00-12:00 13:00-17:00\nFri 09:00-12:00,13:00-15:00",
+                true,
+            ),
+            ("Fri-Mon 22:00-24:00", true),
+            ("Daily 00:00-24:00", true),
+            ("", true),
+            ("Mon 17:00-09:00", false),
+            ("Mon", false),
+            ("Someday 09:00-17:00", false),
+            ("Mon 9am-5pm", false),
+        ];
+        for (rules, ok) in cases {
+            assert_eq!(WorkWeek::parse(rules, tz).is_ok(), ok, "{}", rules);
+        }
+    }
+
+    #[test]
+    fn test_work_week_windows() -> Result<(), PeriodError> {
+        let tz = chrono_tz::America::New_York;
+        let week = WorkWeek::parse("Mon-Fri 09:00-17:00; Sat 10:00-13:00; Fri 16:00-18:00", tz)?;
+
+        struct TestCase {
+            name: &'static str,
+            span: Span,
+            expected: Vec<(DateTime<Tz>, DateTime<Tz>)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Friday to Sunday, with overlapping rules merged",
+                // 2024-03-08 is a Friday.
+                span: Span::new(ny(3, 8, 12, 0), ny(3, 11, 0, 0))?,
+                expected: vec![
+                    (ny(3, 8, 12, 0), ny(3, 8, 18, 0)),
+                    (ny(3, 9, 10, 0), ny(3, 9, 13, 0)),
+                ],
+            },
+            TestCase {
+                name: "Local hours across the DST change",
+                span: Span::new(ny(3, 8, 0, 0), ny(3, 12, 0, 0))?,
+                expected: vec![
+                    (ny(3, 8, 9, 0), ny(3, 8, 18, 0)),
+                    (ny(3, 9, 10, 0), ny(3, 9, 13, 0)),
+                    (ny(3, 11, 9, 0), ny(3, 11, 17, 0)),
+                ],
+            },
+        ];
+
+        for case in cases {
+            let actual = week
+                .windows(&case.span)?
+                .iter()
+                .map(|s| (s.start(), s.end()))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_work_week_off_hours() -> Result<(), PeriodError> {
+        let week = WorkWeek::parse("Mon-Fri 09:00-17:00", chrono_tz::America::New_York)?;
+        // Monday 2024-03-11
+        let span = Span::new(ny(3, 11, 0, 0), ny(3, 12, 0, 0))?;
+        let mut blocks = week.off_hours(&span)?;
+        blocks.push(Block::new(ny(3, 11, 12, 0), ny(3, 11, 13, 0))?);
+        let slots: Vec<Slot> = find(span, blocks)?;
+        let actual = slots
+            .iter()
+            .map(|s| (s.start(), s.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![
+                (ny(3, 11, 9, 0), ny(3, 11, 12, 0)),
+                (ny(3, 11, 13, 0), ny(3, 11, 17, 0)),
+            ]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,18 @@
+pub use self::fit::*;
+pub use self::meeting::*;
+pub use self::place::*;
+pub use self::policy::*;
+pub use self::pool::*;
+pub use self::sample::*;
+pub use self::score::*;
+pub use self::split::*;
+pub use self::suggest::*;
+pub mod fit;
+pub mod meeting;
+pub mod place;
+pub mod policy;
+pub mod pool;
+pub mod sample;
+pub mod score;
+pub mod split;
+pub mod suggest;
@@ -0,0 +1,163 @@
+use chrono::Duration;
+
+use crate::{
+    finder::{find::find_with, options::FindOptions},
+    periods::{
+        period::{Input, Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+/// A slot offered for a requested duration, with how much of it is missing
+/// (e.g. "45 of the 60 minutes you asked for").
+#[derive(Debug, Clone)]
+pub struct FuzzyFit {
+    pub slot: Slot,
+    /// The requested duration minus the length of the slot. Zero for a perfect fit.
+    pub shortfall: Duration,
+}
+
+impl FuzzyFit {
+    /// Whether the slot covers the whole requested duration.
+    pub fn is_perfect(&self) -> bool {
+        self.shortfall.is_zero()
+    }
+}
+
+// Slots for an event of the duration, including free time covering at least min_percent of it.
+// A free time long enough gives a perfect fit of the duration at its start, and a shorter one is
+// offered whole with its shortfall. Perfect fits come first, then the smallest shortfall, then the earliest.
+// Free time is found with find_with and the options.
+pub fn find_fuzzy_fit<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+    options: &FindOptions,
+    duration: Duration,
+    min_percent: u32,
+) -> Result<Vec<FuzzyFit>, PeriodError> {
+    if duration <= Duration::zero() {
+        return Err(PeriodError::InvalidDuration(duration));
+    }
+    if !(1..=100).contains(&min_percent) {
+        return Err(PeriodError::InvalidFormat(format!("{}%", min_percent)));
+    }
+    let min_length = duration * min_percent as i32 / 100;
+    let slots: Vec<Slot> = find_with(span, inputs, options)?;
+    let mut fits = slots
+        .into_iter()
+        .filter_map(|slot| {
+            let length = slot.end() - slot.start();
+            if length >= duration {
+                let slot = slot.trim_to(duration).ok()?;
+                Some(FuzzyFit {
+                    slot,
+                    shortfall: Duration::zero(),
+                })
+            } else if length >= min_length {
+                Some(FuzzyFit {
+                    slot,
+                    shortfall: duration - length,
+                })
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    fits.sort_by_key(|fit| (fit.shortfall, fit.slot.start()));
+    Ok(fits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32, min: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, min, 0)
+            .unwrap()
+    }
+
+    fn slot(start: (u32, u32), end: (u32, u32)) -> Result<Slot, PeriodError> {
+        Slot::new(dt(start.0, start.1), dt(end.0, end.1))
+    }
+
+    #[test]
+    fn test_find_fuzzy_fit() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(13, 0));
+        // Free: 9:00-9:45, 10:00-10:30, 11:00-11:50, 12:00-13:00
+        let blocks = vec![
+            Block::new(dt(9, 45), dt(10, 0))?,
+            Block::new(dt(10, 30), dt(11, 0))?,
+            Block::new(dt(11, 50), dt(12, 0))?,
+        ];
+        let hour = Duration::hours(1);
+
+        struct TestCase {
+            name: &'static str,
+            min_percent: u32,
+            expected: Vec<(Slot, i64)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Perfect fit first, then the smallest shortfall",
+                min_percent: 75,
+                expected: vec![
+                    (slot((12, 0), (13, 0))?, 0),
+                    (slot((11, 0), (11, 50))?, 10),
+                    (slot((9, 0), (9, 45))?, 15),
+                ],
+            },
+            TestCase {
+                name: "Half of the duration",
+                min_percent: 50,
+                expected: vec![
+                    (slot((12, 0), (13, 0))?, 0),
+                    (slot((11, 0), (11, 50))?, 10),
+                    (slot((9, 0), (9, 45))?, 15),
+                    (slot((10, 0), (10, 30))?, 30),
+                ],
+            },
+            TestCase {
+                name: "Only perfect fits",
+                min_percent: 100,
+                expected: vec![(slot((12, 0), (13, 0))?, 0)],
+            },
+        ];
+
+        for case in cases {
+            let fits = find_fuzzy_fit(
+                span()?,
+                blocks.clone(),
+                &FindOptions::new(),
+                hour,
+                case.min_percent,
+            )?;
+            let actual = fits
+                .iter()
+                .map(|f| (f.slot.start(), f.slot.end(), f.shortfall.num_minutes()))
+                .collect::<Vec<_>>();
+            let expected = case
+                .expected
+                .iter()
+                .map(|(s, m)| (s.start(), s.end(), *m))
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{}", case.name);
+        }
+
+        for percent in [0, 101] {
+            assert!(
+                find_fuzzy_fit(span()?, blocks.clone(), &FindOptions::new(), hour, percent)
+                    .is_err()
+            );
+        }
+        assert!(
+            find_fuzzy_fit(span()?, blocks, &FindOptions::new(), Duration::zero(), 50).is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    cancel::Cancellation,
+    periods::{
+        block::Block,
+        period::{Input, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+use super::pool::segments;
+
+/// A person invited to a meeting and the times they are busy.
+#[derive(Debug, Clone)]
+pub struct Attendee<In> {
+    pub name: String,
+    pub busy: Vec<In>,
+    /// Slots must suit required attendees. Optional attendees are reported when they can make it.
+    pub required: bool,
+}
+
+impl<In: Input> Attendee<In> {
+    /// constructor of a required attendee
+    pub fn required(name: impl Into<String>, busy: Vec<In>) -> Self {
+        Attendee {
+            name: name.into(),
+            busy,
+            required: true,
+        }
+    }
+
+    /// constructor of an optional attendee
+    pub fn optional(name: impl Into<String>, busy: Vec<In>) -> Self {
+        Attendee {
+            name: name.into(),
+            busy,
+            required: false,
+        }
+    }
+}
+
+/// A slot where all required attendees are free.
+#[derive(Debug, Clone)]
+pub struct MeetingSlot {
+    pub slot: Slot,
+    /// Indices of the optional attendees free for the whole slot, in the order of the attendees.
+    pub optional: Vec<usize>,
+}
+
+// Calculate slots where all required attendees are free (e.g. an interview panel).
+// A slot ends where the set of optional attendees who can make it changes.
+pub fn find_for_attendees<In: Input>(
+    span: Span,
+    attendees: &[Attendee<In>],
+) -> Result<Vec<MeetingSlot>, PeriodError> {
+    find_for_attendees_with_cancel(span, attendees, &Cancellation::default())
+}
+
+// Same as find_for_attendees, but abandoned with Cancelled or TimedOut as the cancellation says,
+// e.g. when the client of a request for a large panel disconnects.
+pub fn find_for_attendees_with_cancel<In: Input>(
+    span: Span,
+    attendees: &[Attendee<In>],
+    cancel: &Cancellation,
+) -> Result<Vec<MeetingSlot>, PeriodError> {
+    let mut required = Vec::new();
+    let mut optional = Vec::new();
+    let mut optional_index = Vec::new();
+    for (i, attendee) in attendees.iter().enumerate() {
+        let busy = attendee
+            .busy
+            .iter()
+            .map(|b| b.to_block())
+            .collect::<Result<Vec<Block>, _>>()?;
+        if attendee.required {
+            required.extend(busy);
+        } else {
+            optional.push(busy);
+            optional_index.push(i);
+        }
+    }
+    Ok(segments(span, required, optional, cancel)?
+        .into_iter()
+        .map(|(slot, free)| MeetingSlot {
+            slot,
+            optional: free.into_iter().map(|i| optional_index[i]).collect(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cancel::CancelToken, periods::period::Period};
+    use chrono::{DateTime, Duration, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: i64) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            + Duration::hours(hour)
+    }
+
+    fn block(start: i64, end: i64) -> Result<Block, PeriodError> {
+        Block::new(dt(start), dt(end))
+    }
+
+    #[test]
+    fn test_find_for_attendees() -> Result<(), PeriodError> {
+        let span = Span::new(dt(9), dt(17))?;
+        let attendees = vec![
+            Attendee::optional("Observer", vec![block(9, 12)?]),
+            Attendee::required("Candidate", vec![block(12, 13)?]),
+            Attendee::required("Manager", vec![block(15, 17)?]),
+            Attendee::optional("Engineer", vec![block(10, 11)?, block(13, 14)?]),
+        ];
+
+        let actual = find_for_attendees(span.clone(), &attendees)?
+            .iter()
+            .map(|s| {
+                (
+                    (s.slot.start() - dt(0)).num_hours(),
+                    (s.slot.end() - dt(0)).num_hours(),
+                    s.optional.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![
+                (9, 10, vec![3]),
+                (10, 11, vec![]),
+                (11, 12, vec![3]),
+                (13, 14, vec![0]),
+                (14, 15, vec![0, 3]),
+            ]
+        );
+
+        let token = CancelToken::new();
+        let cancel = Cancellation::new().token(token.clone());
+        let slots = find_for_attendees_with_cancel(span.clone(), &attendees, &cancel)?;
+        assert_eq!(slots.len(), 5);
+        token.cancel();
+        assert_eq!(
+            find_for_attendees_with_cancel(span, &attendees, &cancel).err(),
+            Some(PeriodError::Cancelled)
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+use std::cmp::Reverse;
+
+use chrono::{DateTime, Duration};
+use chrono_tz::Tz;
+
+use crate::{
+    finder::find::find_slots,
+    periods::{
+        period::{Input, Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+/// A task to be placed into free time. Implement it for your struct.
+pub trait Task {
+    /// Time needed for the task.
+    fn duration(&self) -> Duration;
+
+    /// The task must end by this time. (No deadline by default)
+    fn deadline(&self) -> Option<DateTime<Tz>> {
+        None
+    }
+
+    /// Tasks with higher priority get earlier slots. (0 by default)
+    fn priority(&self) -> i32 {
+        0
+    }
+}
+
+/// Which free slot a task is placed into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlacementStrategy {
+    /// The earliest slot long enough for the task.
+    #[default]
+    EarliestFit,
+    /// The slot leaving the least free time (the earliest one among ties).
+    BestFit,
+}
+
+/// A task and the time assigned to it.
+#[derive(Debug, Clone)]
+pub struct Placed<T> {
+    pub task: T,
+    pub slot: Slot,
+}
+
+/// Result of placing tasks.
+#[derive(Debug, Clone)]
+pub struct Placement<T> {
+    /// Placed tasks, in the order they were placed.
+    pub placed: Vec<Placed<T>>,
+    /// Tasks for which no slot was long enough.
+    pub unplaced: Vec<T>,
+    /// Tasks for which a slot was long enough, but not before their deadline.
+    pub missed_deadline: Vec<T>,
+}
+
+// Place tasks into the slots. Each task is put at the start of the chosen slot.
+// Tasks are placed in order of priority (highest first), then of deadline (earliest first), otherwise in the given order.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(slots = slots.len(), tasks = tasks.len()))
+)]
+pub fn place<T: Task>(
+    slots: &[Slot],
+    tasks: Vec<T>,
+    strategy: PlacementStrategy,
+) -> Result<Placement<T>, PeriodError> {
+    let mut free = slots.to_vec();
+    free.sort_by_key(|s| s.start());
+
+    let mut tasks = tasks;
+    tasks.sort_by_key(|t| (Reverse(t.priority()), t.deadline().is_none(), t.deadline()));
+
+    let mut placed = Vec::new();
+    let mut unplaced = Vec::new();
+    let mut missed_deadline = Vec::new();
+    for task in tasks {
+        let duration = task.duration();
+        if duration <= Duration::zero() {
+            return Err(PeriodError::InvalidDuration(duration));
+        }
+        let fits = |s: &Slot| s.end() - s.start() >= duration;
+        let in_time = |s: &Slot| task.deadline().is_none_or(|d| s.start() + duration <= d);
+        let candidates = free
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| fits(s) && in_time(s));
+        let chosen = match strategy {
+            PlacementStrategy::EarliestFit => candidates.map(|(i, _)| i).next(),
+            PlacementStrategy::BestFit => candidates
+                .min_by_key(|(_, s)| s.end() - s.start())
+                .map(|(i, _)| i),
+        };
+        let Some(i) = chosen else {
+            if free.iter().any(fits) {
+                missed_deadline.push(task);
+            } else {
+                unplaced.push(task);
+            }
+            continue;
+        };
+
+        let slot = Slot::new(free[i].start(), free[i].start() + duration)?;
+        if slot.end() < free[i].end() {
+            free[i] = Slot::new(slot.end(), free[i].end())?;
+        } else {
+            free.remove(i);
+        }
+        placed.push(Placed { task, slot });
+    }
+    debug!(
+        placed = placed.len(),
+        unplaced = unplaced.len(),
+        missed_deadline = missed_deadline.len(),
+        "tasks placed"
+    );
+    Ok(Placement {
+        placed,
+        unplaced,
+        missed_deadline,
+    })
+}
+
+// Find free slots of the span and place tasks into them.
+pub fn schedule<In: Input, T: Task>(
+    span: Span,
+    inputs: Vec<In>,
+    tasks: Vec<T>,
+    strategy: PlacementStrategy,
+) -> Result<Placement<T>, PeriodError> {
+    let slots = find_slots(span, inputs)?;
+    place(&slots, tasks, strategy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct MockTask {
+        name: &'static str,
+        minutes: i64,
+        deadline: Option<DateTime<Tz>>,
+        priority: i32,
+    }
+
+    impl Task for MockTask {
+        fn duration(&self) -> Duration {
+            Duration::minutes(self.minutes)
+        }
+
+        fn deadline(&self) -> Option<DateTime<Tz>> {
+            self.deadline
+        }
+
+        fn priority(&self) -> i32 {
+            self.priority
+        }
+    }
+
+    fn task(name: &'static str, minutes: i64) -> MockTask {
+        MockTask {
+            name,
+            minutes,
+            deadline: None,
+            priority: 0,
+        }
+    }
+
+    fn dt(now: DateTime<Tz>, minutes: i64) -> DateTime<Tz> {
+        now + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn test_place() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        // Free 0-60, 90-120, 180-300 (minutes)
+        let slots = vec![
+            Slot::new(dt(now, 180), dt(now, 300))?,
+            Slot::new(dt(now, 0), dt(now, 60))?,
+            Slot::new(dt(now, 90), dt(now, 120))?,
+        ];
+
+        struct TestCase {
+            name: &'static str,
+            strategy: PlacementStrategy,
+            tasks: Vec<MockTask>,
+            expected_placed: Vec<(&'static str, i64, i64)>,
+            expected_unplaced: Vec<&'static str>,
+            expected_missed: Vec<&'static str>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Earliest fit",
+                strategy: PlacementStrategy::EarliestFit,
+                tasks: vec![task("a", 30), task("b", 30), task("c", 30), task("d", 90)],
+                expected_placed: vec![("a", 0, 30), ("b", 30, 60), ("c", 90, 120), ("d", 180, 270)],
+                expected_unplaced: vec![],
+                expected_missed: vec![],
+            },
+            TestCase {
+                name: "Best fit",
+                strategy: PlacementStrategy::BestFit,
+                tasks: vec![task("a", 30), task("b", 60), task("c", 100)],
+                expected_placed: vec![("a", 90, 120), ("b", 0, 60), ("c", 180, 280)],
+                expected_unplaced: vec![],
+                expected_missed: vec![],
+            },
+            TestCase {
+                name: "Tasks too long are unplaced",
+                strategy: PlacementStrategy::EarliestFit,
+                tasks: vec![task("a", 150), task("b", 100), task("c", 30)],
+                expected_placed: vec![("b", 180, 280), ("c", 0, 30)],
+                expected_unplaced: vec!["a"],
+                expected_missed: vec![],
+            },
+            TestCase {
+                name: "Higher priority gets earlier slots",
+                strategy: PlacementStrategy::EarliestFit,
+                tasks: vec![
+                    task("low", 60),
+                    MockTask {
+                        priority: 2,
+                        ..task("high", 60)
+                    },
+                    MockTask {
+                        priority: 1,
+                        ..task("middle", 30)
+                    },
+                ],
+                expected_placed: vec![("high", 0, 60), ("middle", 90, 120), ("low", 180, 240)],
+                expected_unplaced: vec![],
+                expected_missed: vec![],
+            },
+            TestCase {
+                name: "Deadlines",
+                strategy: PlacementStrategy::EarliestFit,
+                tasks: vec![
+                    MockTask {
+                        deadline: Some(dt(now, 200)),
+                        ..task("late", 60)
+                    },
+                    MockTask {
+                        deadline: Some(dt(now, 120)),
+                        ..task("urgent", 30)
+                    },
+                    MockTask {
+                        deadline: Some(dt(now, 300)),
+                        ..task("ok", 60)
+                    },
+                    task("huge", 200),
+                ],
+                expected_placed: vec![("urgent", 0, 30), ("ok", 180, 240)],
+                expected_unplaced: vec!["huge"],
+                expected_missed: vec!["late"],
+            },
+        ];
+
+        for case in cases {
+            let result = place(&slots, case.tasks, case.strategy)?;
+            let placed = result
+                .placed
+                .iter()
+                .map(|p| {
+                    (
+                        p.task.name,
+                        (p.slot.start() - now).num_minutes(),
+                        (p.slot.end() - now).num_minutes(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(placed, case.expected_placed, "{}", case.name);
+            let unplaced = result.unplaced.iter().map(|t| t.name).collect::<Vec<_>>();
+            assert_eq!(unplaced, case.expected_unplaced, "{}", case.name);
+            let missed = result
+                .missed_deadline
+                .iter()
+                .map(|t| t.name)
+                .collect::<Vec<_>>();
+            assert_eq!(missed, case.expected_missed, "{}", case.name);
+        }
+
+        let invalid = place(&slots, vec![task("a", 0)], PlacementStrategy::EarliestFit);
+        assert!(invalid.is_err(), "Task without duration should fail");
+        Ok(())
+    }
+
+    #[test]
+    fn test_schedule() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let span = Span::new(dt(now, 0), dt(now, 120))?;
+        let blocks = vec![Block::new(dt(now, 30), dt(now, 60))?];
+        let result = schedule(
+            span,
+            blocks,
+            vec![task("a", 45), task("b", 30)],
+            PlacementStrategy::EarliestFit,
+        )?;
+        assert_eq!(result.placed.len(), 2);
+        assert_eq!(result.placed[0].slot.start(), dt(now, 60));
+        assert_eq!(result.placed[1].slot.start(), dt(now, 0));
+        assert!(result.unplaced.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, NaiveDate};
+use chrono_tz::Tz;
+
+use crate::{
+    finder::{
+        filter::lead,
+        find::find_with,
+        options::{FindOptions, LeadTimePolicy},
+    },
+    periods::{
+        period::{Input, Output, Period, PeriodError},
+        slot::Slot,
+        span::{start_of_day, Span},
+    },
+};
+
+/// Why a booking is refused by a BookingPolicy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolicyViolation {
+    /// The booking starts within the minimum notice.
+    TooSoon { earliest: DateTime<Tz> },
+    /// The booking ends beyond the maximum advance booking window.
+    TooFarAhead { latest: DateTime<Tz> },
+    /// The local day of the booking already has the maximum number of bookings.
+    DayFull { date: NaiveDate, max: usize },
+    /// The length of the booking is not one of the allowed durations.
+    DurationNotAllowed { duration: Duration },
+}
+
+/// Rules on what can be booked, enforced in one place both when offering slots (find)
+/// and when accepting a booking (check). Each rule is disabled unless it is set.
+#[derive(Debug, Clone, Default)]
+pub struct BookingPolicy {
+    min_notice: Option<Duration>,
+    max_advance: Option<Duration>,
+    max_per_day: Option<usize>,
+    durations: Vec<Duration>,
+}
+
+// Trim the slot to start on the first local day with fewer than max bookings.
+// None if every day the slot covers is full.
+fn skip_full_days(
+    mut slot: Slot,
+    tz: &Tz,
+    per_day: &HashMap<NaiveDate, usize>,
+    max: usize,
+) -> Option<Slot> {
+    loop {
+        let date = slot.start().with_timezone(tz).date_naive();
+        if per_day.get(&date).copied().unwrap_or(0) < max {
+            return Some(slot);
+        }
+        let next = start_of_day(date.succ_opt()?, tz).ok()?;
+        slot = slot.trim_start(next - slot.start()).ok()?;
+    }
+}
+
+impl BookingPolicy {
+    /// constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bookings must start at least the duration after now.
+    pub fn min_notice(mut self, duration: Duration) -> Self {
+        self.min_notice = Some(duration);
+        self
+    }
+
+    /// Bookings must end within the duration from now (e.g. 60 days ahead).
+    pub fn max_advance(mut self, duration: Duration) -> Self {
+        self.max_advance = Some(duration);
+        self
+    }
+
+    /// At most max bookings may start on a local day.
+    pub fn max_per_day(mut self, max: usize) -> Self {
+        self.max_per_day = Some(max);
+        self
+    }
+
+    /// Bookings must have one of the durations (e.g. 30 and 60 minutes).
+    pub fn allowed_durations(mut self, durations: Vec<Duration>) -> Self {
+        self.durations = durations;
+        self
+    }
+
+    /// Check that the policy is consistent.
+    pub fn validate(&self) -> Result<(), PeriodError> {
+        let windows = [self.min_notice, self.max_advance].into_iter().flatten();
+        if let Some(d) = windows.into_iter().find(|d| *d < Duration::zero()) {
+            return Err(PeriodError::InvalidDuration(d));
+        }
+        match self.durations.iter().find(|d| **d <= Duration::zero()) {
+            Some(d) => Err(PeriodError::InvalidDuration(*d)),
+            None => Ok(()),
+        }
+    }
+
+    /// Slots that can be booked under the policy, as find_with with the options. The inputs are
+    /// the existing bookings, which count toward max_per_day on the local days of the span.
+    /// Slots are trimmed to the notice and the advance window, and to start on a day that is not
+    /// full, and dropped if shorter than the shortest allowed duration. The clock of the options tells now.
+    pub fn find<In: Input, Out: Output>(
+        &self,
+        span: Span,
+        inputs: Vec<In>,
+        options: &FindOptions,
+    ) -> Result<Vec<Out>, PeriodError> {
+        self.validate()?;
+        let now = options.now();
+        let tz = span.start().timezone();
+        let span = match self.max_advance.map(|d| now + d) {
+            Some(latest) if latest <= span.start() => return Ok(Vec::new()),
+            Some(latest) => Span::new(span.start(), span.end().min(latest))?,
+            None => span,
+        };
+        let mut per_day: HashMap<NaiveDate, usize> = HashMap::new();
+        for input in &inputs {
+            let date = input.start().with_timezone(&tz).date_naive();
+            *per_day.entry(date).or_default() += 1;
+        }
+        let shortest = self.durations.iter().min().copied();
+
+        let slots: Vec<Slot> = find_with(span, inputs, options)?;
+        Ok(slots
+            .into_iter()
+            .filter_map(|slot| match self.min_notice {
+                Some(d) => lead(slot, now + d, LeadTimePolicy::Trim),
+                None => Some(slot),
+            })
+            .filter_map(|slot| match self.max_per_day {
+                Some(max) => skip_full_days(slot, &tz, &per_day, max),
+                None => Some(slot),
+            })
+            .filter(|slot| shortest.is_none_or(|d| slot.end() - slot.start() >= d))
+            .map(Out::create_from_slot)
+            .collect())
+    }
+
+    /// Why the booking would be refused at now, given the existing bookings. Empty if it is allowed.
+    /// Days are the local days of the timezone of the booking.
+    pub fn check<P: Period, B: Period>(
+        &self,
+        booking: &P,
+        bookings: &[B],
+        now: DateTime<Tz>,
+    ) -> Vec<PolicyViolation> {
+        let mut violations = Vec::new();
+        if let Some(earliest) = self.min_notice.map(|d| now + d) {
+            if booking.start() < earliest {
+                violations.push(PolicyViolation::TooSoon { earliest });
+            }
+        }
+        if let Some(latest) = self.max_advance.map(|d| now + d) {
+            if booking.end() > latest {
+                violations.push(PolicyViolation::TooFarAhead { latest });
+            }
+        }
+        if let Some(max) = self.max_per_day {
+            let tz = booking.start().timezone();
+            let date = booking.start().date_naive();
+            let count = bookings
+                .iter()
+                .filter(|b| b.start().with_timezone(&tz).date_naive() == date)
+                .count();
+            if count >= max {
+                violations.push(PolicyViolation::DayFull { date, max });
+            }
+        }
+        let duration = booking.end() - booking.start();
+        if !self.durations.is_empty() && !self.durations.contains(&duration) {
+            violations.push(PolicyViolation::DurationNotAllowed { duration });
+        }
+        violations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clock::FixedClock, periods::block::Block};
+    use chrono::TimeZone;
+
+    fn dt(d: u32, h: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, d, h, 0, 0)
+            .unwrap()
+    }
+
+    fn policy() -> BookingPolicy {
+        BookingPolicy::new()
+            .min_notice(Duration::hours(2))
+            .max_advance(Duration::days(2))
+            .max_per_day(2)
+            .allowed_durations(vec![Duration::minutes(30), Duration::hours(1)])
+    }
+
+    #[test]
+    fn test_booking_policy_find() -> Result<(), PeriodError> {
+        let options = FindOptions::new().clock(FixedClock::new(dt(1, 9)));
+        // Day 1 is full with two bookings, so the slot from 16:00 is trimmed to start on day 2.
+        let bookings = vec![
+            Block::new(dt(1, 12), dt(1, 13))?,
+            Block::new(dt(1, 15), dt(1, 16))?,
+            Block::new(dt(2, 10), dt(2, 13))?,
+        ];
+        let span = Span::new(dt(1, 0), dt(4, 0))?;
+        let slots: Vec<Slot> = policy().find(span, bookings, &options)?;
+        let actual = slots
+            .iter()
+            .map(|s| (s.start(), s.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![(dt(2, 0), dt(2, 10)), (dt(2, 13), dt(3, 9))],
+            "Day 1 is full, and the window ends on day 3 at 9:00"
+        );
+
+        let one_a_day = BookingPolicy::new().max_per_day(1);
+        let slots: Vec<Slot> = one_a_day.find(
+            Span::new(dt(1, 0), dt(3, 0))?,
+            vec![Block::new(dt(1, 9), dt(1, 10))?],
+            &options,
+        )?;
+        let actual = slots
+            .iter()
+            .map(|s| (s.start(), s.end()))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, vec![(dt(2, 0), dt(3, 0))], "Day 2 is free");
+
+        let slots: Vec<Slot> = policy().find(
+            Span::new(dt(5, 0), dt(6, 0))?,
+            Vec::<Block>::new(),
+            &options,
+        )?;
+        assert!(slots.is_empty(), "Beyond the window");
+        Ok(())
+    }
+
+    #[test]
+    fn test_booking_policy_check() -> Result<(), PeriodError> {
+        let now = dt(1, 9);
+        let bookings = vec![
+            Block::new(dt(2, 10), dt(2, 11))?,
+            Block::new(dt(2, 12), dt(2, 13))?,
+        ];
+        let cases = vec![
+            ("Allowed", Block::new(dt(1, 12), dt(1, 13))?, vec![]),
+            (
+                "Too soon",
+                Block::new(dt(1, 10), dt(1, 11))?,
+                vec![PolicyViolation::TooSoon {
+                    earliest: dt(1, 11),
+                }],
+            ),
+            (
+                "Too far ahead, and not an allowed duration",
+                Block::new(dt(3, 8), dt(3, 10))?,
+                vec![
+                    PolicyViolation::TooFarAhead { latest: dt(3, 9) },
+                    PolicyViolation::DurationNotAllowed {
+                        duration: Duration::hours(2),
+                    },
+                ],
+            ),
+            (
+                "Day full",
+                Block::new(dt(2, 15), dt(2, 16))?,
+                vec![PolicyViolation::DayFull {
+                    date: dt(2, 0).date_naive(),
+                    max: 2,
+                }],
+            ),
+        ];
+        for (name, booking, expected) in cases {
+            assert_eq!(
+                policy().check(&booking, &bookings, now),
+                expected,
+                "{}",
+                name
+            );
+        }
+        assert!(BookingPolicy::new()
+            .allowed_durations(vec![Duration::zero()])
+            .validate()
+            .is_err());
+        Ok(())
+    }
+}
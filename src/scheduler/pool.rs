@@ -0,0 +1,455 @@
+use chrono::DateTime;
+use chrono_tz::Tz;
+
+use crate::{
+    cancel::Cancellation,
+    finder::find::{sweep, CANCEL_INTERVAL},
+    periods::{
+        block::Block,
+        period::{Input, Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+/// A resource (room, staff member) and the times it is busy.
+#[derive(Debug, Clone)]
+pub struct Resource<R> {
+    pub name: String,
+    pub busy: Vec<R>,
+    /// Skills or equipment of the resource (e.g. "hygienist", "x-ray").
+    pub tags: Vec<String>,
+}
+
+impl<R: Input> Resource<R> {
+    /// constructor
+    pub fn new(name: impl Into<String>, busy: Vec<R>) -> Self {
+        Resource {
+            name: name.into(),
+            busy,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Add the tag to the resource.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    /// Whether the resource has the tag.
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+// The busy blocks of each resource.
+fn calendars<R: Input>(pool: &[Resource<R>]) -> Result<Vec<Vec<Block>>, PeriodError> {
+    pool.iter()
+        .map(|r| r.busy.iter().map(|b| b.to_block()).collect())
+        .collect()
+}
+
+/// A resource needed by a request: any resource having all of the tags.
+#[derive(Debug, Clone, Default)]
+pub struct Requirement {
+    pub tags: Vec<String>,
+}
+
+impl Requirement {
+    /// constructor
+    pub fn new<S: Into<String>>(tags: impl IntoIterator<Item = S>) -> Self {
+        Requirement {
+            tags: tags.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Whether the resource meets the requirement.
+    pub fn matches<R: Input>(&self, resource: &Resource<R>) -> bool {
+        self.tags.iter().all(|tag| resource.has_tag(tag))
+    }
+}
+
+/// Which free resource of the pool is assigned to each slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PoolStrategy {
+    /// The first free resource in the pool.
+    #[default]
+    First,
+    /// Rotate through the pool: the first free resource after the one assigned to the previous slot,
+    /// so bookings are spread evenly.
+    RoundRobin,
+}
+
+/// A slot where the attendees are free, with a resource assigned.
+#[derive(Debug, Clone)]
+pub struct PoolSlot {
+    pub slot: Slot,
+    /// Index of the resource assigned in the pool.
+    pub resource: usize,
+    /// Indices of all resources free for the whole slot, in the order of the pool.
+    pub available: Vec<usize>,
+}
+
+/// A slot where the attendees are free, with a distinct resource assigned to each requirement.
+#[derive(Debug, Clone)]
+pub struct MatchedSlot {
+    pub slot: Slot,
+    /// Index of the resource in the pool assigned to each requirement, in the order of the requirements.
+    pub resources: Vec<usize>,
+}
+
+// Whether one of the sorted slots contains the period from start to end.
+fn covers(slots: &[Slot], start: DateTime<Tz>, end: DateTime<Tz>) -> bool {
+    let i = slots.partition_point(|s| s.start() <= start);
+    i > 0 && end <= slots[i - 1].end()
+}
+
+// Split the times the attendees are free where the set of free calendars changes.
+// Each period has the indices of the calendars free for the whole period.
+// The cancellation is checked for each calendar and while the periods are split.
+pub(crate) fn segments<In: Input>(
+    span: Span,
+    attendees: Vec<In>,
+    calendars: Vec<Vec<Block>>,
+    cancel: &Cancellation,
+) -> Result<Vec<(Slot, Vec<usize>)>, PeriodError> {
+    let free = sweep(span.clone(), attendees, &mut (), cancel)?;
+    let resources = calendars
+        .into_iter()
+        .map(|busy| sweep(span.clone(), busy, &mut (), cancel))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut times = free
+        .iter()
+        .chain(resources.iter().flatten())
+        .flat_map(|s| [s.start(), s.end()])
+        .collect::<Vec<_>>();
+    times.sort();
+    times.dedup();
+
+    let mut segments: Vec<(Slot, Vec<usize>)> = Vec::new();
+    for (i, pair) in times.windows(2).enumerate() {
+        if i % CANCEL_INTERVAL == 0 {
+            cancel.check()?;
+        }
+        let (start, end) = (pair[0], pair[1]);
+        if !covers(&free, start, end) {
+            continue;
+        }
+        let available = (0..resources.len())
+            .filter(|i| covers(&resources[*i], start, end))
+            .collect::<Vec<_>>();
+        match segments.last_mut() {
+            Some((slot, last)) if slot.end() == start && *last == available => {
+                *slot = Slot::new(slot.start(), end)?;
+            }
+            _ => segments.push((Slot::new(start, end)?, available)),
+        }
+    }
+    Ok(segments)
+}
+
+// Calculate the times the attendees are free (attendees are the blocks of all of their calendars),
+// paired with a resource of the pool free at the same time (e.g. a meeting room).
+// A slot ends where the set of free resources changes. The first free resource of the pool is assigned.
+pub fn find_with_pool<In: Input, R: Input>(
+    span: Span,
+    attendees: Vec<In>,
+    pool: &[Resource<R>],
+) -> Result<Vec<PoolSlot>, PeriodError> {
+    find_with_pool_by(span, attendees, pool, PoolStrategy::First)
+}
+
+// Same as find_with_pool, but abandoned with Cancelled or TimedOut as the cancellation says,
+// e.g. when the pool has many resources with long calendars.
+pub fn find_with_pool_with_cancel<In: Input, R: Input>(
+    span: Span,
+    attendees: Vec<In>,
+    pool: &[Resource<R>],
+    cancel: &Cancellation,
+) -> Result<Vec<PoolSlot>, PeriodError> {
+    pool_slots(span, attendees, pool, PoolStrategy::First, cancel)
+}
+
+// Same as find_with_pool, but the resource assigned to each slot is chosen by the strategy.
+pub fn find_with_pool_by<In: Input, R: Input>(
+    span: Span,
+    attendees: Vec<In>,
+    pool: &[Resource<R>],
+    strategy: PoolStrategy,
+) -> Result<Vec<PoolSlot>, PeriodError> {
+    pool_slots(span, attendees, pool, strategy, &Cancellation::default())
+}
+
+fn pool_slots<In: Input, R: Input>(
+    span: Span,
+    attendees: Vec<In>,
+    pool: &[Resource<R>],
+    strategy: PoolStrategy,
+    cancel: &Cancellation,
+) -> Result<Vec<PoolSlot>, PeriodError> {
+    let mut next = 0;
+    Ok(segments(span, attendees, calendars(pool)?, cancel)?
+        .into_iter()
+        .filter(|(_, available)| !available.is_empty())
+        .map(|(slot, available)| {
+            let resource = match strategy {
+                PoolStrategy::First => available[0],
+                PoolStrategy::RoundRobin => {
+                    let chosen = available
+                        .iter()
+                        .copied()
+                        .find(|i| *i >= next)
+                        .unwrap_or(available[0]);
+                    next = chosen + 1;
+                    chosen
+                }
+            };
+            PoolSlot {
+                slot,
+                resource,
+                available,
+            }
+        })
+        .collect())
+}
+
+// Assign a distinct available resource to each requirement, by augmenting paths.
+// assigned[i] is the requirement the resource i is assigned to.
+fn assign(
+    requirement: usize,
+    candidates: &[Vec<usize>],
+    assigned: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    for &resource in &candidates[requirement] {
+        if visited[resource] {
+            continue;
+        }
+        visited[resource] = true;
+        let free = match assigned[resource] {
+            Some(other) => assign(other, candidates, assigned, visited),
+            None => true,
+        };
+        if free {
+            assigned[resource] = Some(requirement);
+            return true;
+        }
+    }
+    false
+}
+
+// Same as find_with_pool, but the request needs a resource for each requirement
+// (e.g. a hygienist and an x-ray room). Slots are returned only where each requirement
+// can get its own free resource having the tags. Earlier resources of the pool are preferred.
+pub fn find_with_requirements<In: Input, R: Input>(
+    span: Span,
+    attendees: Vec<In>,
+    pool: &[Resource<R>],
+    requirements: &[Requirement],
+) -> Result<Vec<MatchedSlot>, PeriodError> {
+    let mut slots: Vec<MatchedSlot> = Vec::new();
+    for (slot, available) in segments(span, attendees, calendars(pool)?, &Cancellation::default())?
+    {
+        let candidates = requirements
+            .iter()
+            .map(|req| {
+                available
+                    .iter()
+                    .copied()
+                    .filter(|i| req.matches(&pool[*i]))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut assigned = vec![None; pool.len()];
+        let matched = (0..requirements.len()).all(|req| {
+            assign(
+                req,
+                &candidates,
+                &mut assigned,
+                &mut vec![false; pool.len()],
+            )
+        });
+        if !matched {
+            continue;
+        }
+        let mut resources = vec![0; requirements.len()];
+        for (resource, req) in assigned.iter().enumerate() {
+            if let Some(req) = req {
+                resources[*req] = resource;
+            }
+        }
+        match slots.last_mut() {
+            Some(last) if last.slot.end() == slot.start() && last.resources == resources => {
+                last.slot = Slot::new(last.slot.start(), slot.end())?;
+            }
+            _ => slots.push(MatchedSlot { slot, resources }),
+        }
+    }
+    Ok(slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cancel::CancelToken;
+    use chrono::{Duration, TimeZone};
+
+    fn dt(hour: i64) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+            .unwrap()
+            + Duration::hours(hour)
+    }
+
+    fn block(start: i64, end: i64) -> Result<Block, PeriodError> {
+        Block::new(dt(start), dt(end))
+    }
+
+    #[test]
+    fn test_find_with_pool() -> Result<(), PeriodError> {
+        let span = Span::new(dt(9), dt(17))?;
+        // Attendees are free 9-10, 11-13, 15-17.
+        let attendees = vec![block(10, 11)?, block(13, 14)?, block(14, 15)?];
+        let pool = vec![
+            Resource::new("Room A", vec![block(9, 12)?, block(16, 17)?]),
+            Resource::new("Room B", vec![block(9, 10)?, block(12, 16)?]),
+            Resource::new("Room C", vec![block(0, 24)?]),
+        ];
+
+        let actual = find_with_pool(span, attendees, &pool)?
+            .iter()
+            .map(|s| {
+                (
+                    (s.slot.start() - dt(0)).num_hours(),
+                    (s.slot.end() - dt(0)).num_hours(),
+                    s.resource,
+                    s.available.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            vec![
+                (11, 12, 1, vec![1]),
+                (12, 13, 0, vec![0]),
+                (15, 16, 0, vec![0]),
+                (16, 17, 1, vec![1]),
+            ]
+        );
+
+        let free_room = vec![Resource::new("Free", Vec::<Block>::new())];
+        let slots = find_with_pool(Span::new(dt(9), dt(17))?, vec![block(10, 11)?], &free_room)?;
+        assert_eq!(
+            slots.len(),
+            2,
+            "Slots are not split while the same rooms are free"
+        );
+
+        let token = CancelToken::new();
+        token.cancel();
+        let cancel = Cancellation::new().token(token);
+        assert_eq!(
+            find_with_pool_with_cancel(
+                Span::new(dt(9), dt(17))?,
+                vec![block(10, 11)?],
+                &pool,
+                &cancel
+            )
+            .err(),
+            Some(PeriodError::Cancelled)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_with_pool_by() -> Result<(), PeriodError> {
+        let span = Span::new(dt(9), dt(15))?;
+        // Attendees are free 9-10, 11-12, 13-15.
+        let attendees = vec![block(10, 11)?, block(12, 13)?];
+        let staff = vec![
+            Resource::new("Aoki", Vec::<Block>::new()),
+            Resource::new("Baba", vec![block(13, 15)?]),
+            Resource::new("Chiba", Vec::<Block>::new()),
+        ];
+
+        let cases = vec![
+            ("First", PoolStrategy::First, vec![0, 0, 0]),
+            ("Round robin", PoolStrategy::RoundRobin, vec![0, 1, 2]),
+        ];
+        for (name, strategy, expected) in cases {
+            let actual = find_with_pool_by(span.clone(), attendees.clone(), &staff, strategy)?
+                .iter()
+                .map(|s| s.resource)
+                .collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{}", name);
+        }
+
+        // Baba is busy for the third slot, so the rotation skips to Chiba, then wraps around.
+        let attendees = vec![block(10, 11)?, block(12, 13)?, block(14, 15)?];
+        let span = Span::new(dt(9), dt(17))?;
+        let actual = find_with_pool_by(span, attendees, &staff, PoolStrategy::RoundRobin)?
+            .iter()
+            .map(|s| s.resource)
+            .collect::<Vec<_>>();
+        assert_eq!(actual, vec![0, 1, 2, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_with_requirements() -> Result<(), PeriodError> {
+        let span = Span::new(dt(9), dt(17))?;
+        let patient = vec![block(12, 13)?];
+        let pool = vec![
+            Resource::new("Dr. Sato", vec![block(9, 10)?])
+                .tag("hygienist")
+                .tag("dentist"),
+            Resource::new("Ms. Ito", vec![block(9, 11)?, block(15, 17)?]).tag("hygienist"),
+            Resource::new("Room 1", vec![block(14, 17)?]).tag("x-ray"),
+        ];
+
+        struct TestCase {
+            name: &'static str,
+            requirements: Vec<Requirement>,
+            expected: Vec<(i64, i64, Vec<usize>)>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Hygienist and x-ray room",
+                requirements: vec![Requirement::new(["hygienist"]), Requirement::new(["x-ray"])],
+                expected: vec![(10, 12, vec![0, 2]), (13, 14, vec![0, 2])],
+            },
+            TestCase {
+                name: "Dentist and another hygienist",
+                requirements: vec![
+                    Requirement::new(["hygienist"]),
+                    Requirement::new(["hygienist", "dentist"]),
+                ],
+                expected: vec![(11, 12, vec![1, 0]), (13, 15, vec![1, 0])],
+            },
+            TestCase {
+                name: "No such resource",
+                requirements: vec![Requirement::new(["surgeon"])],
+                expected: vec![],
+            },
+        ];
+
+        for case in cases {
+            let actual =
+                find_with_requirements(span.clone(), patient.clone(), &pool, &case.requirements)?
+                    .iter()
+                    .map(|s| {
+                        (
+                            (s.slot.start() - dt(0)).num_hours(),
+                            (s.slot.end() - dt(0)).num_hours(),
+                            s.resources.clone(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+            assert_eq!(actual, case.expected, "{}", case.name);
+        }
+        Ok(())
+    }
+}
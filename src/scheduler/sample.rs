@@ -0,0 +1,120 @@
+use crate::periods::period::Period;
+
+/// How the sampler weighs the candidates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Weighting {
+    /// Every candidate is equally likely. (default)
+    #[default]
+    Uniform,
+    /// Longer candidates are more likely, in proportion to their length.
+    Duration,
+}
+
+/// Picks candidate slots at random, reproducibly for the same seed
+/// (e.g. for A/B tests of suggestions, or spreading bookings of a shared link).
+#[derive(Debug, Clone)]
+pub struct Sampler {
+    state: u64,
+    weighting: Weighting,
+}
+
+impl Sampler {
+    /// constructor
+    pub fn new(seed: u64) -> Self {
+        Sampler {
+            state: seed,
+            weighting: Weighting::default(),
+        }
+    }
+
+    /// How the candidates are weighed.
+    pub fn weighting(mut self, weighting: Weighting) -> Self {
+        self.weighting = weighting;
+        self
+    }
+
+    // SplitMix64, so results do not change with the version of a dependency.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in (0, 1].
+    fn next_f64(&mut self) -> f64 {
+        ((self.next_u64() >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Pick n distinct candidates (all of them if there are fewer), returned in their order.
+    /// The same seed and candidates always give the same picks.
+    pub fn sample<P: Period + Clone>(&mut self, candidates: &[P], n: usize) -> Vec<P> {
+        // Weighted sampling without replacement: keep the n largest keys u^(1/weight).
+        let mut keys = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, candidate)| {
+                let weight = match self.weighting {
+                    Weighting::Uniform => 1.0,
+                    Weighting::Duration => {
+                        ((candidate.end() - candidate.start()).num_milliseconds() as f64).max(1.0)
+                    }
+                };
+                (self.next_f64().ln() / weight, i)
+            })
+            .collect::<Vec<_>>();
+        keys.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut picked = keys.into_iter().take(n).map(|(_, i)| i).collect::<Vec<_>>();
+        picked.sort_unstable();
+        picked.into_iter().map(|i| candidates[i].clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::{period::PeriodError, slot::Slot};
+    use chrono::{DateTime, Duration, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sampler() -> Result<(), PeriodError> {
+        let candidates = (0..10)
+            .map(|h| Slot::new(dt(h), dt(h) + Duration::minutes(30)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let starts = |slots: Vec<Slot>| slots.iter().map(|s| s.start()).collect::<Vec<_>>();
+
+        let first = starts(Sampler::new(42).sample(&candidates, 3));
+        assert_eq!(first.len(), 3);
+        assert!(first.windows(2).all(|w| w[0] < w[1]), "in order, distinct");
+        assert_eq!(first, starts(Sampler::new(42).sample(&candidates, 3)));
+        assert!(
+            (1..20).any(|seed| starts(Sampler::new(seed).sample(&candidates, 3)) != first),
+            "Other seeds pick other slots"
+        );
+        assert_eq!(Sampler::new(1).sample(&candidates, 20).len(), 10);
+        assert!(Sampler::new(1).sample(&candidates, 0).is_empty());
+
+        // A candidate 100 times longer than the others is almost always picked.
+        let mut weighted = candidates.clone();
+        weighted.push(Slot::new(dt(12), dt(12) + Duration::minutes(3000))?);
+        let picked = (0..100)
+            .filter(|seed| {
+                Sampler::new(*seed)
+                    .weighting(Weighting::Duration)
+                    .sample(&weighted, 1)[0]
+                    .start()
+                    == dt(12)
+            })
+            .count();
+        assert!(picked > 80, "picked {} times", picked);
+        Ok(())
+    }
+}
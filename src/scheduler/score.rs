@@ -0,0 +1,262 @@
+use chrono::{Duration, NaiveTime, Timelike};
+
+use crate::periods::{period::Period, slot::Slot};
+
+/// Scores a slot for ranking APIs. A higher score is better.
+pub trait SlotScorer {
+    fn score(&self, slot: &Slot) -> f64;
+}
+
+impl<F: Fn(&Slot) -> f64> SlotScorer for F {
+    fn score(&self, slot: &Slot) -> f64 {
+        self(slot)
+    }
+}
+
+/// Prefers slots starting earlier.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EarliestStart;
+
+impl SlotScorer for EarliestStart {
+    fn score(&self, slot: &Slot) -> f64 {
+        -(slot.start().timestamp() as f64)
+    }
+}
+
+/// Prefers longer slots.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LongestDuration;
+
+impl SlotScorer for LongestDuration {
+    fn score(&self, slot: &Slot) -> f64 {
+        (slot.end() - slot.start()).num_seconds() as f64
+    }
+}
+
+/// Prefers slots starting close to the local time of day (e.g. 09:00 to prefer mornings).
+#[derive(Debug, Clone, Copy)]
+pub struct TimeOfDayPreference {
+    preferred: NaiveTime,
+}
+
+impl TimeOfDayPreference {
+    /// constructor
+    pub fn new(preferred: NaiveTime) -> Self {
+        TimeOfDayPreference { preferred }
+    }
+}
+
+impl SlotScorer for TimeOfDayPreference {
+    // Minus the minutes between the start time and the preferred time of day.
+    fn score(&self, slot: &Slot) -> f64 {
+        const DAY: i64 = 24 * 60 * 60;
+        let start = slot.start().time().num_seconds_from_midnight() as i64;
+        let preferred = self.preferred.num_seconds_from_midnight() as i64;
+        let diff = (start - preferred).rem_euclid(DAY);
+        -(diff.min(DAY - diff) as f64 / 60.0)
+    }
+}
+
+/// Preferred (or avoided) local-time windows as soft constraints. Slots are not excluded,
+/// but score the sum of the weights of the windows, each weighted by the fraction of the slot within it.
+/// Ties keep their order in rank_slots, so the earliest slot comes first among equally preferred ones.
+#[derive(Debug, Clone, Default)]
+pub struct PreferenceWindows {
+    windows: Vec<(NaiveTime, NaiveTime, f64)>,
+}
+
+impl PreferenceWindows {
+    /// constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Raise the score of slots within the window (e.g. 10:00-12:00). A window ending before it starts crosses midnight.
+    pub fn prefer(mut self, start: NaiveTime, end: NaiveTime, weight: f64) -> Self {
+        self.windows.push((start, end, weight));
+        self
+    }
+
+    /// Lower the score of slots within the window.
+    pub fn avoid(self, start: NaiveTime, end: NaiveTime, weight: f64) -> Self {
+        self.prefer(start, end, -weight)
+    }
+}
+
+impl SlotScorer for PreferenceWindows {
+    fn score(&self, slot: &Slot) -> f64 {
+        let (start, end) = (slot.start().naive_local(), slot.end().naive_local());
+        let length = (end - start).num_seconds() as f64;
+        if length <= 0.0 {
+            return 0.0;
+        }
+        self.windows
+            .iter()
+            .map(|(from, to, weight)| {
+                let mut overlap = Duration::zero();
+                // The window of the day before may cross midnight into the slot.
+                let mut date = start.date() - Duration::days(1);
+                while date <= end.date() {
+                    let window_start = date.and_time(*from);
+                    let mut window_end = date.and_time(*to);
+                    if window_end <= window_start {
+                        window_end += Duration::days(1);
+                    }
+                    let (s, e) = (start.max(window_start), end.min(window_end));
+                    if s < e {
+                        overlap += e - s;
+                    }
+                    date += Duration::days(1);
+                }
+                weight * overlap.num_seconds() as f64 / length
+            })
+            .sum()
+    }
+}
+
+// Sort the slots by score, the best first. Ties keep their order, and NaN scores are ranked last.
+pub fn rank_slots(slots: Vec<Slot>, scorer: &dyn SlotScorer) -> Vec<Slot> {
+    let mut scored = slots
+        .into_iter()
+        .map(|slot| (scorer.score(&slot), slot))
+        .collect::<Vec<_>>();
+    scored.sort_by(|a, b| (a.0.is_nan().cmp(&b.0.is_nan())).then_with(|| b.0.total_cmp(&a.0)));
+    scored.into_iter().map(|(_, slot)| slot).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::period::PeriodError;
+    use chrono::TimeZone;
+
+    fn slot(start: u32, end: u32) -> Result<Slot, PeriodError> {
+        let dt = |h| {
+            chrono_tz::Japan
+                .with_ymd_and_hms(2024, 1, 1, h, 0, 0)
+                .unwrap()
+        };
+        Slot::new(dt(start), dt(end))
+    }
+
+    fn time(hour: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_preference_windows() -> Result<(), PeriodError> {
+        let scorer = PreferenceWindows::new()
+            .prefer(time(10), time(12), 2.0)
+            .avoid(time(22), time(2), 1.0);
+
+        let cases = vec![
+            ("Fully within preferred window", slot(10, 11)?, 2.0),
+            ("Half within preferred window", slot(11, 13)?, 1.0),
+            ("Outside of windows", slot(14, 15)?, 0.0),
+            (
+                "Within avoided window crossing midnight",
+                slot(22, 23)?,
+                -1.0,
+            ),
+            ("Partly within both windows", slot(8, 12)?, 1.0),
+        ];
+
+        for (name, slot, expected) in cases {
+            assert!((scorer.score(&slot) - expected).abs() < 1e-9, "{}", name);
+        }
+
+        let tz = chrono_tz::Japan;
+        let overnight = Slot::new(
+            tz.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap(),
+            tz.with_ymd_and_hms(2024, 1, 2, 4, 0, 0).unwrap(),
+        )?;
+        assert!(
+            (scorer.score(&overnight) + 0.5).abs() < 1e-9,
+            "Overnight slot"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_rank_slots() -> Result<(), PeriodError> {
+        let slots = vec![slot(14, 15)?, slot(8, 9)?, slot(11, 14)?, slot(22, 23)?];
+
+        struct TestCase {
+            name: &'static str,
+            scorer: Box<dyn SlotScorer>,
+            expected: Vec<u32>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Earliest start",
+                scorer: Box::new(EarliestStart),
+                expected: vec![8, 11, 14, 22],
+            },
+            TestCase {
+                name: "Longest duration",
+                scorer: Box::new(LongestDuration),
+                expected: vec![11, 14, 8, 22],
+            },
+            TestCase {
+                name: "Time of day preference",
+                scorer: Box::new(TimeOfDayPreference::new(
+                    NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+                )),
+                expected: vec![11, 8, 14, 22],
+            },
+            TestCase {
+                name: "Time of day preference across midnight",
+                scorer: Box::new(TimeOfDayPreference::new(
+                    NaiveTime::from_hms_opt(1, 0, 0).unwrap(),
+                )),
+                expected: vec![22, 8, 11, 14],
+            },
+            TestCase {
+                name: "Preference windows",
+                scorer: Box::new(
+                    PreferenceWindows::new()
+                        .prefer(time(10), time(12), 1.0)
+                        .avoid(time(21), time(9), 1.0),
+                ),
+                expected: vec![11, 14, 8, 22],
+            },
+            TestCase {
+                name: "Closure",
+                scorer: Box::new(|s: &Slot| -(s.end().hour() as f64)),
+                expected: vec![8, 11, 14, 22],
+            },
+            TestCase {
+                name: "NaN scores are ranked last",
+                scorer: Box::new(|s: &Slot| match s.start().hour() {
+                    8 | 14 => f64::NAN,
+                    hour => hour as f64,
+                }),
+                expected: vec![22, 11, 14, 8],
+            },
+        ];
+
+        for case in cases {
+            let ranked = rank_slots(slots.clone(), case.scorer.as_ref());
+            let starts = ranked.iter().map(|s| s.start().hour()).collect::<Vec<_>>();
+            assert_eq!(starts, case.expected, "{}", case.name);
+        }
+
+        // Many NaN scores among the others must not break the order of the others.
+        let many = (0..200)
+            .map(|i| slot(i % 23, i % 23 + 1))
+            .collect::<Result<Vec<_>, _>>()?;
+        let scorer = |s: &Slot| match s.start().hour() % 3 {
+            0 => f64::NAN,
+            _ => s.start().hour() as f64,
+        };
+        let scores = rank_slots(many, &scorer)
+            .iter()
+            .map(scorer)
+            .collect::<Vec<_>>();
+        let (scored, nan) = scores.split_at(scores.iter().position(|s| s.is_nan()).unwrap());
+        assert!(scored.windows(2).all(|w| w[0] >= w[1]), "Descending");
+        assert!(nan.iter().all(|s| s.is_nan()), "NaN last");
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use chrono::Duration;
+
+use crate::{
+    finder::find::find_slots,
+    periods::{
+        period::{Input, Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+// Segments of the duration over consecutive free slots, at the end of the first slot, all of the
+// slots in between, and the start of the last slot. None if they cannot sum to the duration with
+// every segment at least min_segment.
+fn segments(slots: &[Slot], duration: Duration, min_segment: Duration) -> Option<Vec<Slot>> {
+    let length = |s: &Slot| s.end() - s.start();
+    let (first, last) = (slots.first()?, slots.last()?);
+    if slots.len() == 1 {
+        if length(first) < duration {
+            return None;
+        }
+        return first.trim_to(duration).ok().map(|slot| vec![slot]);
+    }
+    let middle = &slots[1..slots.len() - 1];
+    let rest = duration
+        - middle
+            .iter()
+            .map(length)
+            .fold(Duration::zero(), |a, b| a + b);
+    let head = length(first).min(rest - min_segment);
+    let tail = rest - head;
+    if head < min_segment || tail < min_segment || length(last) < tail {
+        return None;
+    }
+    let mut segments = Vec::with_capacity(slots.len());
+    segments.push(Slot::new(first.end() - head, first.end()).ok()?);
+    segments.extend(middle.iter().cloned());
+    segments.push(Slot::new(last.start(), last.start() + tail).ok()?);
+    Some(segments)
+}
+
+// Suggest up to k ways to book the duration split over at most max_parts segments of at least
+// min_segment each, e.g. a long training session when no single free slot is long enough.
+// Segments are taken from consecutive free slots, and suggestions are ordered by proximity:
+// the shortest time from the start of the first segment to the end of the last, then the earliest.
+// A single free slot fitting the whole duration is the closest suggestion.
+pub fn suggest_split_booking<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+    duration: Duration,
+    max_parts: usize,
+    min_segment: Duration,
+    k: usize,
+) -> Result<Vec<Vec<Slot>>, PeriodError> {
+    for d in [duration, min_segment] {
+        if d <= Duration::zero() {
+            return Err(PeriodError::InvalidDuration(d));
+        }
+    }
+    let free = find_slots(span, inputs)?
+        .into_iter()
+        .filter(|s| s.end() - s.start() >= min_segment)
+        .collect::<Vec<_>>();
+    let mut suggestions = (1..=max_parts)
+        .flat_map(|parts| free.windows(parts))
+        .filter_map(|window| segments(window, duration, min_segment))
+        .collect::<Vec<_>>();
+    let spread = |s: &[Slot]| (s[s.len() - 1].end() - s[0].start(), s[0].start());
+    suggestions.sort_by_key(|s| spread(s));
+    suggestions.truncate(k);
+    Ok(suggestions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, TimeZone};
+    use chrono_tz::Tz;
+
+    fn dt(hour: u32, min: u32) -> DateTime<Tz> {
+        chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, hour, min, 0)
+            .unwrap()
+    }
+
+    fn slot(start: (u32, u32), end: (u32, u32)) -> Result<Slot, PeriodError> {
+        Slot::new(dt(start.0, start.1), dt(end.0, end.1))
+    }
+
+    #[test]
+    fn test_suggest_split_booking() -> Result<(), PeriodError> {
+        let span = || Span::new(dt(9, 0), dt(17, 0));
+        // Free: 9:00-10:00, 10:30-11:30, 12:00-12:20, 13:00-14:30, 15:00-17:00
+        let blocks = vec![
+            Block::new(dt(10, 0), dt(10, 30))?,
+            Block::new(dt(11, 30), dt(12, 0))?,
+            Block::new(dt(12, 20), dt(13, 0))?,
+            Block::new(dt(14, 30), dt(15, 0))?,
+        ];
+        let times = |s: &Vec<Slot>| s.iter().map(|s| (s.start(), s.end())).collect::<Vec<_>>();
+
+        struct TestCase {
+            name: &'static str,
+            duration: Duration,
+            max_parts: usize,
+            expected: Vec<Vec<Slot>>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Two parts",
+                duration: Duration::hours(3),
+                max_parts: 2,
+                expected: vec![vec![slot((13, 0), (14, 30))?, slot((15, 0), (16, 30))?]],
+            },
+            TestCase {
+                name: "Closest first, skipping free time shorter than the minimum segment",
+                duration: Duration::minutes(150),
+                max_parts: 3,
+                expected: vec![
+                    vec![slot((13, 0), (14, 30))?, slot((15, 0), (16, 0))?],
+                    vec![slot((10, 30), (11, 30))?, slot((13, 0), (14, 30))?],
+                    vec![
+                        slot((9, 0), (10, 0))?,
+                        slot((10, 30), (11, 30))?,
+                        slot((13, 0), (13, 30))?,
+                    ],
+                ],
+            },
+            TestCase {
+                name: "A single slot fits",
+                duration: Duration::hours(2),
+                max_parts: 2,
+                expected: vec![
+                    vec![slot((15, 0), (17, 0))?],
+                    vec![slot((9, 0), (10, 0))?, slot((10, 30), (11, 30))?],
+                    vec![slot((13, 0), (14, 30))?, slot((15, 0), (15, 30))?],
+                ],
+            },
+        ];
+
+        for case in cases {
+            let suggestions = suggest_split_booking(
+                span()?,
+                blocks.clone(),
+                case.duration,
+                case.max_parts,
+                Duration::minutes(30),
+                3,
+            )?;
+            let actual = suggestions.iter().map(times).collect::<Vec<_>>();
+            let expected = case.expected.iter().map(times).collect::<Vec<_>>();
+            assert_eq!(actual, expected, "{}", case.name);
+        }
+        assert!(
+            suggest_split_booking(span()?, blocks, Duration::hours(1), 2, Duration::zero(), 3)
+                .is_err()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use chrono::Duration;
+
+use super::score::{rank_slots, SlotScorer};
+use crate::{
+    finder::find::find_slots,
+    periods::{
+        period::{Input, Period, PeriodError},
+        slot::Slot,
+        span::Span,
+    },
+};
+
+/// How candidate placements of a new event are ranked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlacementRanking {
+    /// Earliest start time first.
+    #[default]
+    Earliest,
+    /// Placements next to existing blocks that leave the least free time in their gap first.
+    LeastFragmentation,
+    /// The middle of the largest gaps first.
+    LargestGapMidpoint,
+}
+
+// Candidate placements of an event of the duration in a gap, with a sort key (smaller is better).
+fn candidates(gap: &Slot, duration: Duration, ranking: PlacementRanking) -> Vec<(Duration, Slot)> {
+    let length = gap.end() - gap.start();
+    let leftover = length - duration;
+    if leftover < Duration::zero() {
+        return Vec::new();
+    }
+    let at = |start| Slot::new(start, start + duration).ok();
+    let placements = match ranking {
+        PlacementRanking::Earliest => vec![(Duration::zero(), at(gap.start()))],
+        PlacementRanking::LeastFragmentation if leftover > Duration::zero() => vec![
+            (leftover, at(gap.start())),
+            (leftover, at(gap.end() - duration)),
+        ],
+        PlacementRanking::LeastFragmentation => vec![(leftover, at(gap.start()))],
+        PlacementRanking::LargestGapMidpoint => {
+            vec![(-length, at(gap.start() + leftover / 2))]
+        }
+    };
+    placements
+        .into_iter()
+        .filter_map(|(key, slot)| slot.map(|slot| (key, slot)))
+        .collect()
+}
+
+// Suggest the top k placements of a new event of the duration, earliest first.
+pub fn suggest_placements<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+    duration: Duration,
+    k: usize,
+) -> Result<Vec<Slot>, PeriodError> {
+    suggest_placements_by(span, inputs, duration, k, PlacementRanking::default())
+}
+
+// Suggest the top k placements of a new event of the duration, ranked by the criteria.
+pub fn suggest_placements_by<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+    duration: Duration,
+    k: usize,
+    ranking: PlacementRanking,
+) -> Result<Vec<Slot>, PeriodError> {
+    if duration <= Duration::zero() {
+        return Err(PeriodError::InvalidDuration(duration));
+    }
+    let mut candidates = find_slots(span, inputs)?
+        .iter()
+        .flat_map(|gap| candidates(gap, duration, ranking))
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|(key, slot)| (*key, slot.start()));
+    Ok(candidates
+        .into_iter()
+        .take(k)
+        .map(|(_, slot)| slot)
+        .collect())
+}
+
+// Suggest the top k placements of a new event of the duration, ranked by the scorer.
+// Candidates are placed at the start and at the end of each gap.
+pub fn suggest_placements_scored<In: Input>(
+    span: Span,
+    inputs: Vec<In>,
+    duration: Duration,
+    k: usize,
+    scorer: &dyn SlotScorer,
+) -> Result<Vec<Slot>, PeriodError> {
+    if duration <= Duration::zero() {
+        return Err(PeriodError::InvalidDuration(duration));
+    }
+    let candidates = find_slots(span, inputs)?
+        .iter()
+        .flat_map(|gap| candidates(gap, duration, PlacementRanking::LeastFragmentation))
+        .map(|(_, slot)| slot)
+        .collect::<Vec<_>>();
+    Ok(rank_slots(candidates, scorer).into_iter().take(k).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::periods::block::Block;
+    use chrono::{DateTime, Utc};
+    use chrono_tz::Tz;
+
+    fn dt(now: DateTime<Tz>, minutes: i64) -> DateTime<Tz> {
+        now + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn test_suggest_placements() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        // Free 0-60, 90-120, 180-480 (minutes)
+        let span = Span::new(dt(now, 0), dt(now, 480))?;
+        let blocks = vec![
+            Block::new(dt(now, 60), dt(now, 90))?,
+            Block::new(dt(now, 120), dt(now, 180))?,
+        ];
+
+        struct TestCase {
+            name: &'static str,
+            ranking: PlacementRanking,
+            k: usize,
+            expected: Vec<i64>,
+        }
+
+        let cases = vec![
+            TestCase {
+                name: "Earliest",
+                ranking: PlacementRanking::Earliest,
+                k: 2,
+                expected: vec![0, 90],
+            },
+            TestCase {
+                name: "Least fragmentation",
+                ranking: PlacementRanking::LeastFragmentation,
+                k: 3,
+                expected: vec![90, 0, 30],
+            },
+            TestCase {
+                name: "Largest gap midpoint",
+                ranking: PlacementRanking::LargestGapMidpoint,
+                k: 5,
+                expected: vec![315, 15, 90],
+            },
+        ];
+
+        for case in cases {
+            let slots = suggest_placements_by(
+                span.clone(),
+                blocks.clone(),
+                Duration::minutes(30),
+                case.k,
+                case.ranking,
+            )?;
+            let starts = slots
+                .iter()
+                .map(|s| (s.start() - now).num_minutes())
+                .collect::<Vec<_>>();
+            assert_eq!(starts, case.expected, "{}", case.name);
+            for slot in slots {
+                assert_eq!(
+                    slot.end() - slot.start(),
+                    Duration::minutes(30),
+                    "{}",
+                    case.name
+                );
+            }
+        }
+
+        let slots = suggest_placements(span.clone(), blocks.clone(), Duration::minutes(90), 3)?;
+        assert_eq!(slots.len(), 1);
+        assert_eq!(slots[0].start(), dt(now, 180));
+
+        let invalid = suggest_placements(span, blocks, Duration::zero(), 3);
+        assert!(invalid.is_err(), "Zero duration should fail");
+        Ok(())
+    }
+
+    #[test]
+    fn test_suggest_placements_scored() -> Result<(), PeriodError> {
+        let now = Utc::now().with_timezone(&chrono_tz::Japan);
+        let span = Span::new(dt(now, 0), dt(now, 480))?;
+        let blocks = vec![Block::new(dt(now, 60), dt(now, 90))?];
+
+        let latest = |s: &Slot| s.start().timestamp() as f64;
+        let slots = suggest_placements_scored(
+            span.clone(),
+            blocks.clone(),
+            Duration::minutes(30),
+            3,
+            &latest,
+        )?;
+        let starts = slots
+            .iter()
+            .map(|s| (s.start() - now).num_minutes())
+            .collect::<Vec<_>>();
+        assert_eq!(starts, vec![450, 90, 30]);
+
+        let invalid = suggest_placements_scored(span, blocks, Duration::zero(), 3, &latest);
+        assert!(invalid.is_err(), "Zero duration should fail");
+        Ok(())
+    }
+}
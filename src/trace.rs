@@ -0,0 +1,87 @@
+// Logging with tracing. Without the tracing feature the macros expand to nothing,
+// so the arguments are not evaluated.
+
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+// Only the options of find_with warn, which need std.
+#[cfg(feature = "std")]
+macro_rules! warn {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::warn!($($arg)*);
+    };
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use chrono::{Duration, TimeZone};
+    use tracing::{
+        span::{Attributes, Id, Record},
+        Event, Metadata, Subscriber,
+    };
+
+    use crate::{
+        finder::find::find_slots,
+        periods::{block::Block, period::PeriodError, span::Span},
+    };
+
+    // Counts the spans and events recorded.
+    #[derive(Default, Clone)]
+    struct Counter {
+        spans: Arc<AtomicUsize>,
+        events: Arc<AtomicUsize>,
+    }
+
+    impl Subscriber for Counter {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(self.spans.fetch_add(1, Ordering::SeqCst) as u64 + 1)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event<'_>) {
+            self.events.fetch_add(1, Ordering::SeqCst);
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_tracing() -> Result<(), PeriodError> {
+        let start = chrono_tz::Japan
+            .with_ymd_and_hms(2024, 1, 1, 9, 0, 0)
+            .unwrap();
+        let hours = |h| start + Duration::hours(h);
+        let span = Span::new(hours(0), hours(8))?;
+        let blocks = vec![
+            Block::new(hours(1), hours(2))?,
+            Block::new(hours(7), hours(9))?,
+        ];
+
+        let counter = Counter::default();
+        tracing::subscriber::with_default(counter.clone(), || find_slots(span, blocks))?;
+        assert_eq!(counter.spans.load(Ordering::SeqCst), 1);
+        // One event per block, and the slots found.
+        assert_eq!(counter.events.load(Ordering::SeqCst), 3);
+        Ok(())
+    }
+}